@@ -1,4 +1,11 @@
-use crate::config_store::WalletConfig;
+use crate::blockchain::Amount;
+use crate::config_store::{CompoundingPolicy, KnownContract, WalletConfig};
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::{DecodedAction, ProposalState, VoteSupport};
+use crate::history_export::{ExportFormat, FiatPricing};
+use crate::light_client::BalanceVerification;
+use crate::network::NetworkProfile;
+use crate::staking::maturity::MaturityAction;
 use crate::storage::VaultMetadata;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -18,6 +25,9 @@ pub struct WalletSummary {
     pub signature_algorithm: Option<String>,
     #[serde(default)]
     pub supports_post_quantum: Option<bool>,
+    /// Accounts held besides the primary address.
+    #[serde(default)]
+    pub accounts: Vec<String>,
 }
 
 impl From<VaultMetadata> for WalletSummary {
@@ -31,6 +41,7 @@ impl From<VaultMetadata> for WalletSummary {
             public_key_hex: metadata.public_key_hex,
             signature_algorithm: metadata.signature_algorithm,
             supports_post_quantum: metadata.supports_post_quantum,
+            accounts: metadata.accounts,
         }
     }
 }
@@ -54,7 +65,7 @@ pub struct CreateWalletRequest {
 #[serde(rename_all = "camelCase")]
 pub struct CastVoteRequest {
     pub proposal_id: i64,
-    pub support: i32,
+    pub support: VoteSupport,
     pub reason: Option<String>,
 }
 
@@ -71,6 +82,42 @@ pub struct DelegateRequest {
     pub amount: i64,
 }
 
+/// The same vote cast from several of the wallet's accounts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchVoteRequest {
+    pub proposal_id: i64,
+    pub support: VoteSupport,
+    pub reason: Option<String>,
+    pub accounts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchVoteResult {
+    pub account: String,
+    pub vote: Option<VoteInfo>,
+    pub error: Option<String>,
+}
+
+/// Delegate voting power from several of the wallet's accounts to one delegatee.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchDelegateRequest {
+    pub delegatee: String,
+    pub accounts: Vec<String>,
+    /// Amount per account; defaults to each account's own voting power.
+    pub amount: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchDelegateResult {
+    pub account: String,
+    pub delegation: Option<DelegationInfo>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetValidatorsResponse {
@@ -125,6 +172,29 @@ pub struct GetAutoStakeStatusResponse {
     pub status: AutoStakeRecord,
 }
 
+/// Network profiles and the one in use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkProfilesResponse {
+    pub profiles: Vec<NetworkProfile>,
+    pub active: Option<String>,
+    pub chain_id: Option<String>,
+}
+
+/// Balance and staking position of one account, loaded in a single batch.
+/// Parts the node failed to return are `None`/empty with the reason in `errors`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverview {
+    pub address: String,
+    pub balance: Option<BalanceResponse>,
+    pub delegations: Vec<DelegationInfo>,
+    pub rewards: Option<StakingRewards>,
+    pub lockbox_records: Vec<LockBoxRecord>,
+    pub auto_stake: Option<AutoStakeRecord>,
+    pub errors: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DelegateTokensRequest {
@@ -153,6 +223,26 @@ pub struct UndelegateTokensRequest {
 pub struct UndelegateTokensResponse {
     pub transaction_id: String,
     pub updated_delegation: DelegationInfo,
+    /// Unix timestamp at which the undelegated funds become liquid, when reported.
+    #[serde(default)]
+    pub unbonding_completes_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedelegateTokensRequest {
+    pub delegator_address: String,
+    pub from_validator: String,
+    pub to_validator: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedelegateTokensResponse {
+    pub transaction_id: String,
+    #[serde(default)]
+    pub delegation: Option<DelegationInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,6 +253,19 @@ pub struct CreateLockboxStakeRequest {
     pub term_months: u32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanLockboxStakeRequest {
+    pub account: String,
+    /// Base units, as in `CreateLockboxStakeRequest`.
+    pub amount: u64,
+    pub term_months: Vec<u32>,
+    /// Months until the funds must be liquid again. Without it no term is
+    /// recommended.
+    #[serde(default)]
+    pub liquidity_horizon_months: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateLockboxStakeResponse {
@@ -236,6 +339,30 @@ pub struct LockBoxRecord {
     pub is_active: bool,
 }
 
+/// Reward multiplier the chain grants a lockbox of `term_months`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockboxMultiplier {
+    pub term_months: u32,
+    pub multiplier: f64,
+}
+
+/// Staking parameters published by the chain.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakingParams {
+    #[serde(default)]
+    pub lockbox_multipliers: Vec<LockboxMultiplier>,
+    #[serde(default)]
+    pub unbonding_period_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStakingParamsResponse {
+    pub params: StakingParams,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AutoStakeRecord {
@@ -245,7 +372,81 @@ pub struct AutoStakeRecord {
     pub is_active: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbondingEntry {
+    pub validator: String,
+    pub amount: u64,
+    pub completes_at: DateTime<Utc>,
+}
+
 // Staking API request/response types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUnbondingEntriesResponse {
+    pub entries: Vec<UnbondingEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCompoundingPolicyRequest {
+    pub account: String,
+    pub policy: CompoundingPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompoundingAuditRequest {
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteMaturityActionRequest {
+    pub entry_id: String,
+    pub action: MaturityAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteMaturityActionResponse {
+    pub entry_id: String,
+    pub action: MaturityAction,
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawStakeResponse {
+    pub transaction_id: String,
+    pub withdrawn_amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendDelegationRequest {
+    pub delegator_address: String,
+    pub amount: u64,
+    #[serde(default)]
+    pub max_validators: Option<usize>,
+    #[serde(default)]
+    pub max_commission: Option<u32>,
+    #[serde(default)]
+    pub min_reputation: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakingReportRequest {
+    pub address: String,
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
 
 // Basic wallet types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -278,6 +479,22 @@ pub struct ImportWalletResponse {
     pub algorithm: String,
 }
 
+/// Add another Ed25519 account, restored from its own mnemonic, to the vault.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAccountRequest {
+    pub password: String,
+    pub mnemonic: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAccountResponse {
+    pub summary: WalletSummary,
+    pub address: String,
+    pub public_key: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UnlockWalletResponse {
@@ -346,6 +563,7 @@ pub struct FormatAmountResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetProposalsRequest {
+    /// Passed to the node as given.
     pub state: Option<String>,
     pub proposer: Option<String>,
     pub limit: Option<u32>,
@@ -397,6 +615,50 @@ pub struct VerifySignatureResponse {
 }
 
 // Governance types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalAction {
+    pub target: String,
+    /// Amount of CHERT sent with the call, as a decimal string.
+    #[serde(default)]
+    pub value: String,
+    /// Optional function signature, e.g. `transfer(address,uint256)`.
+    #[serde(default)]
+    pub signature: String,
+    /// Hex-encoded call data.
+    #[serde(default)]
+    pub calldata: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProposalRequest {
+    pub title: String,
+    pub description: String,
+    pub actions: Vec<ProposalAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProposalResponse {
+    pub proposal_id: i64,
+    #[serde(default)]
+    pub transaction_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetKnownContractRequest {
+    pub address: String,
+    pub contract: KnownContract,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalThresholdResponse {
+    pub threshold: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProposalSummary {
@@ -408,7 +670,7 @@ pub struct ProposalSummary {
     pub votes_for: i64,
     pub votes_against: i64,
     pub votes_abstain: i64,
-    pub state: String,
+    pub state: ProposalState,
     pub created_at: i64,
 }
 
@@ -419,6 +681,10 @@ pub struct ProposalDetail {
     pub proposer: String,
     pub targets: Vec<String>,
     pub values: Vec<String>,
+    /// Function signature per action. When set, the matching calldata holds
+    /// only the ABI-encoded arguments.
+    #[serde(default)]
+    pub signatures: Vec<String>,
     pub calldatas: Vec<String>,
     pub description: String,
     pub vote_start: i64,
@@ -426,7 +692,7 @@ pub struct ProposalDetail {
     pub votes_for: i64,
     pub votes_against: i64,
     pub votes_abstain: i64,
-    pub state: String,
+    pub state: ProposalState,
     pub executed_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
@@ -439,7 +705,7 @@ pub struct ProposalDetail {
 pub struct VoteInfo {
     pub proposal_id: i64,
     pub voter: String,
-    pub support: i32,
+    pub support: VoteSupport,
     pub weight: i64,
     pub reason: Option<String>,
     pub voted_at: i64,
@@ -463,6 +729,28 @@ pub struct DelegationInfo {
     pub delegated_at: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainIdResponse {
+    #[serde(deserialize_with = "chain_id_text")]
+    pub chain_id: String,
+}
+
+/// Chain ids as nodes report them: numeric on Chert networks, free text on
+/// others. Both are compared as text.
+fn chain_id_text<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ChainId {
+        Number(u64),
+        Text(String),
+    }
+    Ok(match ChainId::deserialize(deserializer)? {
+        ChainId::Number(id) => id.to_string(),
+        ChainId::Text(id) => id,
+    })
+}
+
 // Missing basic types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -470,6 +758,19 @@ pub struct BalanceResponse {
     pub address: String,
     pub balance: String,
     pub nonce: u64,
+    /// Set by the wallet when the light client is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<BalanceVerification>,
+}
+
+impl BalanceResponse {
+    /// The spendable balance; the node reports it in base units.
+    pub fn spendable(&self) -> WalletResult<Amount> {
+        let base_units = self.balance.trim().parse::<u128>().map_err(|_| {
+            WalletError::InvalidAmount(format!("Node reported invalid balance: {}", self.balance))
+        })?;
+        Amount::from_base_units(base_units)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -483,6 +784,26 @@ pub struct TransactionInfo {
     pub status: String,
     pub timestamp: String,
     pub block_height: Option<u64>,
+    #[serde(default)]
+    pub transaction_type: Option<String>,
+    /// Set by the wallet from the active network profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+}
+
+/// A transfer accepted by the node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferReceipt {
+    pub tx_id: String,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u64,
+    /// Set by the wallet from the active network profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -492,6 +813,30 @@ pub struct TransactionHistoryResponse {
     pub total_count: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistoryRequest {
+    pub address: String,
+    pub format: ExportFormat,
+    pub destination: String,
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub fiat: Option<FiatPricing>,
+    #[serde(default)]
+    pub node_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistoryResponse {
+    pub path: String,
+    pub format: ExportFormat,
+    pub rows_written: u64,
+}
+
 // Governance response types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -503,6 +848,9 @@ pub struct GetProposalsResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetProposalResponse {
     pub proposal: ProposalDetail,
+    /// Filled in by the wallet; the node does not send it.
+    #[serde(default)]
+    pub decoded_actions: Vec<DecodedAction>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -5,17 +6,37 @@ use std::time::Duration;
 
 use secrecy::SecretString;
 
+use crate::blockchain_client::BlockchainClient;
 use crate::config_store::{ConfigStore, WalletConfig};
+use crate::crypto::{StealthKeyMaterial, WalletKeyPair};
 use crate::errors::{WalletError, WalletResult};
+use crate::governance::GovernanceWatchlist;
+use crate::light_client::LightClient;
+use crate::network::{self, ChainPin};
+use crate::proxy::ProxyRoutes;
+use crate::quorum::QuorumMonitor;
+use crate::rpc_pool::{endpoint_urls, RpcPool};
+use crate::security::get_security_config;
 use crate::session::SessionManager;
+use crate::staking::ledger::ledger_account;
+use crate::staking::{CompoundingAuditLog, MaturityTracker, RedelegationStore, StakingLedger};
 use crate::storage::{VaultCreateParams, VaultManager, VaultMetadata, VaultSecrets, WalletPaths};
+use crate::tls;
 
-#[derive(Debug)]
+/// Everything a command works on. Clones share the session, RPC client and
+/// on-disk stores with the original.
+#[derive(Debug, Clone)]
 pub struct WalletContext {
     paths: WalletPaths,
     vault: VaultManager,
     config_store: ConfigStore,
     session: SessionManager,
+    staking_ledger: StakingLedger,
+    redelegations: RedelegationStore,
+    maturity_tracker: MaturityTracker,
+    compounding_audit: CompoundingAuditLog,
+    governance_watchlist: GovernanceWatchlist,
+    rpc: BlockchainClient,
     environment: String,
 }
 
@@ -28,18 +49,40 @@ impl WalletContext {
 
         let vault = VaultManager::from_paths(&paths);
         let config_store = ConfigStore::from_paths(&paths);
+        let staking_ledger = StakingLedger::from_paths(&paths);
+        let redelegations = RedelegationStore::from_paths(&paths);
+        let maturity_tracker = MaturityTracker::from_paths(&paths);
+        let compounding_audit = CompoundingAuditLog::from_paths(&paths);
+        let governance_watchlist = GovernanceWatchlist::from_paths(&paths);
         let initial_config = config_store.load_or_default(environment.clone())?;
         let session_timeout = duration_from_minutes(initial_config.session.auto_lock_minutes);
         let session = SessionManager::new(
             session_timeout,
             initial_config.session.max_failed_attempts.max(1),
         );
+        let rpc = BlockchainClient::with_pool(RpcPool::from_config(&initial_config.network)?)?
+            .with_quorum(QuorumMonitor::new(initial_config.network.quorum.clone()))
+            .with_light_client(LightClient::new(
+                initial_config.network.light_client.clone(),
+            ))
+            .with_chain_pin(ChainPin::new(network::pinned_chain(&initial_config)))
+            .with_tls(tls::client_config(
+                &initial_config.network,
+                is_production(),
+            )?)?
+            .with_proxy(ProxyRoutes::from_config(&initial_config.network.proxy)?)?;
 
         Ok(Self {
             paths,
             vault,
             config_store,
             session,
+            staking_ledger,
+            redelegations,
+            maturity_tracker,
+            compounding_audit,
+            governance_watchlist,
+            rpc,
             environment,
         })
     }
@@ -56,6 +99,53 @@ impl WalletContext {
         &self.config_store
     }
 
+    pub fn staking_ledger(&self) -> &StakingLedger {
+        &self.staking_ledger
+    }
+
+    pub fn redelegations(&self) -> &RedelegationStore {
+        &self.redelegations
+    }
+
+    pub fn maturity_tracker(&self) -> &MaturityTracker {
+        &self.maturity_tracker
+    }
+
+    pub fn compounding_audit(&self) -> &CompoundingAuditLog {
+        &self.compounding_audit
+    }
+
+    pub fn governance_watchlist(&self) -> &GovernanceWatchlist {
+        &self.governance_watchlist
+    }
+
+    /// Client over the configured endpoint pool, shared by every command so
+    /// endpoint health carries across requests.
+    pub fn rpc_client(&self) -> &BlockchainClient {
+        &self.rpc
+    }
+
+    /// Accounts with staking activity known to this wallet: the primary
+    /// address, accounts in the staking ledger, and pending redelegations,
+    /// each once in its 0x form.
+    pub fn staking_accounts(&self) -> WalletResult<Vec<String>> {
+        let mut accounts = BTreeSet::new();
+        if let Some(address) = self
+            .vault
+            .read_metadata()?
+            .and_then(|metadata| metadata.primary_address)
+        {
+            accounts.insert(ledger_account(&address)?);
+        }
+        for account in self.staking_ledger.accounts()? {
+            accounts.insert(account);
+        }
+        for plan in self.redelegations.unfinished()? {
+            accounts.insert(ledger_account(&plan.delegator)?);
+        }
+        Ok(accounts.into_iter().collect())
+    }
+
     pub fn environment(&self) -> &str {
         &self.environment
     }
@@ -74,6 +164,20 @@ impl WalletContext {
         let session_timeout = duration_from_minutes(updated.session.auto_lock_minutes);
         self.session =
             SessionManager::new(session_timeout, updated.session.max_failed_attempts.max(1));
+        self.rpc
+            .pool()
+            .set_endpoints(endpoint_urls(&updated.network))?;
+        self.rpc.cache().clear();
+        self.rpc
+            .light_client()
+            .set_config(updated.network.light_client.clone());
+        self.rpc
+            .chain_pin()
+            .set_expected(network::pinned_chain(&updated));
+        self.rpc
+            .set_tls(tls::client_config(&updated.network, is_production())?)?;
+        self.rpc
+            .set_proxy(ProxyRoutes::from_config(&updated.network.proxy)?)?;
         Ok(updated)
     }
 
@@ -101,6 +205,36 @@ impl WalletContext {
         self.vault.create(params)
     }
 
+    /// Store `keypair` and the mnemonic it came from in a new vault named
+    /// `wallet_name`, then unlock it. Returns the stored metadata.
+    pub fn provision_vault(
+        &self,
+        password: &SecretString,
+        wallet_name: &str,
+        keypair: &WalletKeyPair,
+        mnemonic: &str,
+    ) -> WalletResult<VaultMetadata> {
+        let stealth_keys = StealthKeyMaterial::derive_from_seed(&keypair.core_keypair.private_key)?;
+        let secrets = VaultSecrets {
+            mnemonic_phrase: Some(mnemonic.to_string()),
+            seed_bytes: keypair.core_keypair.private_key.clone(),
+            stealth_material: stealth_keys.encode(),
+            pq_material: Vec::new(),
+            account_keys: Vec::new(),
+        };
+
+        let mut metadata = VaultMetadata::new(wallet_name);
+        metadata.primary_address = Some(keypair.address());
+        metadata.public_key_hex = Some(keypair.public_key_hex());
+        metadata.signature_algorithm = Some(format!("{:?}", keypair.core_keypair.algorithm));
+        metadata.supports_post_quantum = Some(keypair.supports_pq);
+
+        self.create_vault(password, metadata, secrets)?;
+        self.unlock(password)?;
+        self.session
+            .peek_unlocked(|metadata, _| Ok(metadata.clone()))
+    }
+
     pub fn unlock(&self, password: &SecretString) -> WalletResult<()> {
         let unlocked = self.vault.unlock(password)?;
         self.session.unlock(unlocked)
@@ -131,6 +265,12 @@ impl SharedWalletContext {
         op(&guard)
     }
 
+    /// A copy of the context for async commands, which must not hold the
+    /// lock across an await.
+    pub fn snapshot(&self) -> WalletResult<WalletContext> {
+        self.read(|ctx| Ok(ctx.clone()))
+    }
+
     pub fn write<F, T>(&self, op: F) -> WalletResult<T>
     where
         F: FnOnce(&mut WalletContext) -> WalletResult<T>,
@@ -143,6 +283,14 @@ impl SharedWalletContext {
     }
 }
 
+/// Whether the security configuration reports production. An uninitialized
+/// configuration counts as production so it never loosens TLS checks.
+pub fn is_production() -> bool {
+    get_security_config()
+        .map(|config| config.is_production())
+        .unwrap_or(true)
+}
+
 fn duration_from_minutes(minutes: u32) -> Duration {
     let clamped = minutes.max(1) as u64;
     Duration::from_secs(clamped.saturating_mul(60))
@@ -0,0 +1,546 @@
+//! Headless command-line wallet over `silica_wallet_lib`, for servers and
+//! scripts. It shares the vault, config and backups of the desktop app's data
+//! directory; every command that signs asks for the vault password again.
+
+mod output;
+mod password;
+
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use silica_wallet_lib::commands::transfer;
+use silica_wallet_lib::crypto::WalletKeyPair;
+use silica_wallet_lib::governance::batch::AccountVote;
+use silica_wallet_lib::governance::{voting, VoteSupport};
+use silica_wallet_lib::history_export::{
+    export_account_history_to_file, ExportFormat, HistoryExportOptions,
+};
+use silica_wallet_lib::proxy::ProxyRoutes;
+use silica_wallet_lib::security::init_security_config_from_env;
+use silica_wallet_lib::{
+    BlockchainClient, InputValidator, VaultMetadata, WalletContext, WalletError, WalletResult,
+    WalletSummary,
+};
+
+use crate::output::Output;
+use crate::password::PasswordSource;
+
+/// Tauri identifier of the desktop app, whose data directory is the default.
+const APP_IDENTIFIER: &str = "com.chert.wallet";
+
+#[derive(Debug, Parser)]
+#[command(name = "silica-wallet-cli", version, about = "Headless Silica wallet")]
+struct Cli {
+    /// Wallet data directory. Defaults to the desktop app's.
+    #[arg(long, global = true, env = "SILICA_WALLET_DIR")]
+    data_dir: Option<PathBuf>,
+    /// Send requests to this RPC endpoint instead of the configured pool.
+    #[arg(long, global = true)]
+    node_url: Option<String>,
+    /// Read the vault password from this file descriptor instead of the TTY.
+    #[arg(long, global = true, value_name = "FD")]
+    password_fd: Option<i32>,
+    /// Print one JSON document per command.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create a vault with a new mnemonic.
+    Create {
+        name: String,
+        #[arg(long, default_value_t = 24)]
+        words: u32,
+        #[arg(long)]
+        post_quantum: bool,
+    },
+    /// Create a vault from an existing mnemonic, read from the TTY or stdin.
+    Import {
+        name: String,
+        #[arg(long)]
+        post_quantum: bool,
+    },
+    /// Check the vault password and show the wallet.
+    Unlock,
+    /// Show the balance of an address, by default the wallet's.
+    Balance { address: Option<String> },
+    /// List recent transactions of an address, by default the wallet's.
+    History {
+        address: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+        #[arg(long, default_value_t = 0)]
+        offset: u32,
+    },
+    /// Sign and submit a transfer. Amounts are in base units.
+    Send {
+        to: String,
+        amount: u64,
+        /// Fee in base units. Defaults to the active network profile's.
+        #[arg(long)]
+        fee: Option<u64>,
+    },
+    /// Delegate base units to a validator.
+    Stake { validator: String, amount: u64 },
+    /// Undelegate base units from a validator.
+    Unstake { validator: String, amount: u64 },
+    /// Claim pending staking rewards.
+    Claim,
+    /// Sign and cast a vote with the wallet's primary account.
+    Vote {
+        proposal_id: i64,
+        #[arg(value_enum)]
+        support: Support,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Export the wallet's transaction history to a file.
+    ExportHistory {
+        destination: PathBuf,
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        /// RFC 3339 start of the exported range.
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+        /// RFC 3339 end of the exported range.
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+        #[arg(long)]
+        address: Option<String>,
+    },
+    /// Manage vault backups.
+    #[command(subcommand)]
+    Backup(BackupCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum BackupCommand {
+    /// List backups, newest first.
+    List,
+    /// Back up the current vault.
+    Create,
+    /// Replace the vault with a backup, backing up the current vault first.
+    Restore { path: PathBuf },
+    /// Delete all but the newest backups.
+    Prune {
+        #[arg(long)]
+        keep: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Support {
+    For,
+    Against,
+    Abstain,
+}
+
+impl From<Support> for VoteSupport {
+    fn from(support: Support) -> Self {
+        match support {
+            Support::For => VoteSupport::For,
+            Support::Against => VoteSupport::Against,
+            Support::Abstain => VoteSupport::Abstain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl From<Format> for ExportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Csv => ExportFormat::Csv,
+            Format::Json => ExportFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatedWallet {
+    summary: WalletSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mnemonic: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedHistory {
+    path: PathBuf,
+    rows_written: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupFiles {
+    backups: Vec<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let output = Output::new(cli.json);
+    match run(cli, output).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            output.error(&err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli, output: Output) -> WalletResult<()> {
+    init_security_config_from_env()?;
+    let data_dir = match cli.data_dir {
+        Some(dir) => dir,
+        None => default_data_dir()?,
+    };
+    let ctx = WalletContext::initialize(data_dir)?;
+    let passwords = PasswordSource::new(cli.password_fd);
+    let validator = InputValidator::default();
+
+    match cli.command {
+        Command::Create {
+            name,
+            words,
+            post_quantum,
+        } => {
+            validator.validate_wallet_name(&name)?;
+            ensure_no_vault(&ctx)?;
+            let password = passwords.new_password()?;
+            validator.validate_password(&password)?;
+            let (keypair, mnemonic) =
+                WalletKeyPair::generate_with_mnemonic(words, None, None, post_quantum)?;
+            let metadata = ctx.provision_vault(&password.into(), &name, &keypair, &mnemonic)?;
+            let created = CreatedWallet {
+                summary: WalletSummary::from(metadata),
+                mnemonic: Some(mnemonic),
+            };
+            output.emit(&created, |created| {
+                format!(
+                    "{}\n\nRecovery phrase (write it down, it is not shown again):\n{}",
+                    describe_wallet(&created.summary),
+                    created.mnemonic.as_deref().unwrap_or_default()
+                )
+            })
+        }
+        Command::Import { name, post_quantum } => {
+            validator.validate_wallet_name(&name)?;
+            ensure_no_vault(&ctx)?;
+            let mnemonic = read_mnemonic()?;
+            let password = passwords.new_password()?;
+            validator.validate_password(&password)?;
+            let keypair = WalletKeyPair::from_mnemonic(&mnemonic, None, None, post_quantum)?;
+            let metadata = ctx.provision_vault(&password.into(), &name, &keypair, &mnemonic)?;
+            let imported = CreatedWallet {
+                summary: WalletSummary::from(metadata),
+                mnemonic: None,
+            };
+            output.emit(&imported, |imported| describe_wallet(&imported.summary))
+        }
+        Command::Unlock => {
+            let summary = WalletSummary::from(unlock(&ctx, passwords)?);
+            output.emit(&summary, describe_wallet)
+        }
+        Command::Balance { address } => {
+            let address = account_or_primary(&ctx, &validator, address)?;
+            let balance = client(&ctx, cli.node_url)?.get_balance(&address).await?;
+            output.emit(&balance, |balance| {
+                format!(
+                    "{} {} (nonce {})",
+                    balance.address, balance.balance, balance.nonce
+                )
+            })
+        }
+        Command::History {
+            address,
+            limit,
+            offset,
+        } => {
+            let address = account_or_primary(&ctx, &validator, address)?;
+            let history = client(&ctx, cli.node_url)?
+                .get_transaction_history(&address, Some(limit), Some(offset))
+                .await?;
+            output.emit(&history, |history| {
+                let mut lines: Vec<String> = history
+                    .transactions
+                    .iter()
+                    .map(|tx| {
+                        format!(
+                            "{}  {}  {} -> {}  {} (fee {})  {}",
+                            tx.timestamp,
+                            tx.transaction_id,
+                            tx.from_address,
+                            tx.to_address,
+                            tx.amount,
+                            tx.fee,
+                            tx.status
+                        )
+                    })
+                    .collect();
+                lines.push(format!(
+                    "{} of {} transactions",
+                    history.transactions.len(),
+                    history.total_count
+                ));
+                lines.join("\n")
+            })
+        }
+        Command::Send { to, amount, fee } => {
+            unlock(&ctx, passwords)?;
+            let client = client(&ctx, cli.node_url)?;
+            let receipt = transfer::send_transfer(&ctx, &client, &to, amount, fee).await?;
+            output.emit(&receipt, |receipt| match &receipt.explorer_url {
+                Some(link) => format!("Submitted {}\n{}", receipt.tx_id, link),
+                None => format!("Submitted {}", receipt.tx_id),
+            })
+        }
+        Command::Stake {
+            validator: to,
+            amount,
+        } => {
+            validator.validate_address(&to)?;
+            let delegator = primary_address(&stored_metadata(&ctx)?)?;
+            let response = client(&ctx, cli.node_url)?
+                .delegate_tokens(&delegator, &to, amount)
+                .await?;
+            output.emit(&response, |response| {
+                format!("Delegated in {}", response.transaction_id)
+            })
+        }
+        Command::Unstake {
+            validator: from,
+            amount,
+        } => {
+            validator.validate_address(&from)?;
+            let delegator = primary_address(&stored_metadata(&ctx)?)?;
+            let response = client(&ctx, cli.node_url)?
+                .undelegate_tokens(&delegator, &from, amount)
+                .await?;
+            output.emit(&response, |response| {
+                match response.unbonding_completes_at {
+                    Some(at) => format!(
+                        "Undelegated in {}; liquid after {}",
+                        response.transaction_id,
+                        DateTime::from_timestamp(at, 0)
+                            .map(|at| at.to_rfc3339())
+                            .unwrap_or_else(|| at.to_string())
+                    ),
+                    None => format!("Undelegated in {}", response.transaction_id),
+                }
+            })
+        }
+        Command::Claim => {
+            let account = primary_address(&stored_metadata(&ctx)?)?;
+            let response = client(&ctx, cli.node_url)?
+                .claim_staking_rewards(&account)
+                .await?;
+            output.emit(&response, |response| {
+                format!(
+                    "Claimed {} in {}",
+                    response.claimed_amount, response.transaction_id
+                )
+            })
+        }
+        Command::Vote {
+            proposal_id,
+            support,
+            reason,
+        } => {
+            let client = client(&ctx, cli.node_url)?;
+            let proposal = client.get_proposal(proposal_id).await?.proposal;
+            voting::ensure_voting_open(&proposal, Utc::now())?;
+            let metadata = unlock(&ctx, passwords)?;
+            let vote = AccountVote {
+                voter: primary_address(&metadata)?,
+                proposal_id,
+                support: support.into(),
+                reason,
+            };
+            let signed = ctx
+                .session()
+                .with_unlocked(|_, secrets| vote.sign(&secrets.signing_key()?))?;
+            let response = client.cast_signed_vote(&signed).await?;
+            output.emit(&response, |_| {
+                format!("Vote recorded on proposal {}", proposal_id)
+            })
+        }
+        Command::ExportHistory {
+            destination,
+            format,
+            from,
+            to,
+            address,
+        } => {
+            let address = account_or_primary(&ctx, &validator, address)?;
+            let destination = std::path::absolute(&destination)?;
+            let mut options = HistoryExportOptions::new(format.into());
+            options.from = from;
+            options.to = to;
+            let rows_written = export_account_history_to_file(
+                &client(&ctx, cli.node_url)?,
+                &address,
+                options,
+                &destination,
+            )
+            .await?;
+            let exported = ExportedHistory {
+                path: destination,
+                rows_written,
+            };
+            output.emit(&exported, |exported| {
+                format!(
+                    "Wrote {} rows to {}",
+                    exported.rows_written,
+                    exported.path.display()
+                )
+            })
+        }
+        Command::Backup(command) => backup(&ctx, command, output),
+    }
+}
+
+fn backup(ctx: &WalletContext, command: BackupCommand, output: Output) -> WalletResult<()> {
+    let backups = match command {
+        BackupCommand::List => ctx.vault().available_backups()?,
+        BackupCommand::Create => vec![ctx.paths().create_vault_backup()?],
+        BackupCommand::Restore { path } => {
+            let path = std::path::absolute(&path)?;
+            let previous = ctx.paths().create_vault_backup()?;
+            ctx.vault().restore_from_backup(&path)?;
+            vec![previous]
+        }
+        BackupCommand::Prune { keep } => {
+            ctx.paths().prune_old_backups(keep)?;
+            ctx.vault().available_backups()?
+        }
+    };
+    output.emit(&BackupFiles { backups }, |files| {
+        if files.backups.is_empty() {
+            return "No backups".to_string();
+        }
+        files
+            .backups
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Data directory of the desktop app, so both front ends share one vault.
+fn default_data_dir() -> WalletResult<PathBuf> {
+    let config_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| Path::new(&home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+    };
+    config_dir
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| {
+            WalletError::NotFound("No config directory found; pass --data-dir".to_string())
+        })
+}
+
+fn client(ctx: &WalletContext, node_url: Option<String>) -> WalletResult<BlockchainClient> {
+    let pooled = ctx.rpc_client();
+    match node_url {
+        Some(url) if url.trim().is_empty() => Err(WalletError::ValidationError(
+            "--node-url cannot be empty".to_string(),
+        )),
+        // Overrides keep the configured certificate trust, proxy routes and
+        // chain pin, so a node on another chain cannot take a signed write.
+        Some(url) => Ok(BlockchainClient::new(url.trim().to_string())?
+            .with_tls(pooled.tls_config())?
+            .with_proxy(ProxyRoutes::clone(&pooled.proxy_routes()))?
+            .with_chain_pin(pooled.chain_pin().clone())),
+        None => Ok(pooled.clone()),
+    }
+}
+
+fn ensure_no_vault(ctx: &WalletContext) -> WalletResult<()> {
+    if ctx.vault().exists() {
+        return Err(WalletError::AlreadyExists(
+            ctx.vault().vault_path().display().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Metadata stored in the clear next to the vault, readable without a password.
+fn stored_metadata(ctx: &WalletContext) -> WalletResult<VaultMetadata> {
+    ctx.vault()
+        .read_metadata()?
+        .ok_or_else(|| WalletError::NotFound("No wallet in this data directory".to_string()))
+}
+
+fn unlock(ctx: &WalletContext, passwords: PasswordSource) -> WalletResult<VaultMetadata> {
+    stored_metadata(ctx)?;
+    ctx.unlock(&passwords.current()?)?;
+    ctx.session()
+        .peek_unlocked(|metadata, _| Ok(metadata.clone()))
+}
+
+fn primary_address(metadata: &VaultMetadata) -> WalletResult<String> {
+    metadata
+        .primary_address
+        .clone()
+        .ok_or_else(|| WalletError::ValidationError("Wallet has no primary address".to_string()))
+}
+
+fn account_or_primary(
+    ctx: &WalletContext,
+    validator: &InputValidator,
+    address: Option<String>,
+) -> WalletResult<String> {
+    match address {
+        Some(address) => {
+            validator.validate_address(&address)?;
+            Ok(address)
+        }
+        None => primary_address(&stored_metadata(ctx)?),
+    }
+}
+
+/// Mnemonic to import: a hidden prompt on a TTY, otherwise the first line of stdin.
+fn read_mnemonic() -> WalletResult<String> {
+    let phrase = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password("Recovery phrase: ")?
+    } else {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        line
+    };
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    if phrase.is_empty() {
+        return Err(WalletError::ValidationError(
+            "No recovery phrase given".to_string(),
+        ));
+    }
+    Ok(phrase)
+}
+
+fn describe_wallet(summary: &WalletSummary) -> String {
+    format!(
+        "{}  {}",
+        summary.wallet_name,
+        summary.primary_address.as_deref().unwrap_or("(no address)")
+    )
+}
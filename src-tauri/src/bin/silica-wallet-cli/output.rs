@@ -0,0 +1,75 @@
+//! Result printing: short text for people by default, or a single JSON
+//! document per invocation with `--json` so scripts can parse stdout.
+
+use serde::Serialize;
+use silica_wallet_lib::{WalletError, WalletResult};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    json: bool,
+}
+
+impl Output {
+    pub fn new(json: bool) -> Self {
+        Self { json }
+    }
+
+    /// Print `value`, formatted by `human` unless JSON was requested.
+    pub fn emit<T: Serialize>(
+        &self,
+        value: &T,
+        human: impl FnOnce(&T) -> String,
+    ) -> WalletResult<()> {
+        println!("{}", self.render(value, human)?);
+        Ok(())
+    }
+
+    /// Report a failed command. JSON errors go to stdout like results so
+    /// scripts only read one stream; the exit status tells them apart.
+    pub fn error(&self, err: &WalletError) {
+        if self.json {
+            println!("{}", serde_json::json!({ "error": err.to_string() }));
+        } else {
+            eprintln!("error: {}", err);
+        }
+    }
+
+    fn render<T: Serialize>(
+        &self,
+        value: &T,
+        human: impl FnOnce(&T) -> String,
+    ) -> WalletResult<String> {
+        if self.json {
+            Ok(serde_json::to_string(value)?)
+        } else {
+            Ok(human(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Sent {
+        tx_id: String,
+    }
+
+    #[test]
+    fn json_mode_prints_one_compact_document() {
+        let sent = Sent {
+            tx_id: "abc".to_string(),
+        };
+        let text = |sent: &Sent| format!("Submitted {}", sent.tx_id);
+        assert_eq!(
+            Output::new(true).render(&sent, text).unwrap(),
+            r#"{"txId":"abc"}"#
+        );
+        assert_eq!(
+            Output::new(false).render(&sent, text).unwrap(),
+            "Submitted abc"
+        );
+    }
+}
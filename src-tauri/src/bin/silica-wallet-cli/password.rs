@@ -0,0 +1,125 @@
+//! Password input for the CLI: an interactive TTY prompt, or one line read
+//! from a file descriptor handed over by a script or secrets manager.
+
+use std::io::{BufRead, BufReader, Read};
+
+use secrecy::SecretString;
+use silica_wallet_lib::{WalletError, WalletResult};
+
+/// Where secrets come from for this invocation.
+#[derive(Debug, Clone, Copy)]
+pub enum PasswordSource {
+    Tty,
+    Fd(i32),
+}
+
+impl PasswordSource {
+    pub fn new(fd: Option<i32>) -> Self {
+        fd.map_or(PasswordSource::Tty, PasswordSource::Fd)
+    }
+
+    /// Read the password of an existing vault.
+    pub fn current(self) -> WalletResult<SecretString> {
+        match self {
+            PasswordSource::Tty => prompt("Vault password: "),
+            PasswordSource::Fd(fd) => read_fd(fd),
+        }
+    }
+
+    /// Read the password for a new vault, asking twice on a TTY.
+    pub fn new_password(self) -> WalletResult<String> {
+        match self {
+            PasswordSource::Tty => {
+                let first = prompt_plain("New vault password: ")?;
+                let second = prompt_plain("Repeat password: ")?;
+                if first != second {
+                    return Err(WalletError::ValidationError(
+                        "Passwords do not match".to_string(),
+                    ));
+                }
+                Ok(first)
+            }
+            PasswordSource::Fd(fd) => read_fd_plain(fd),
+        }
+    }
+}
+
+fn prompt(message: &str) -> WalletResult<SecretString> {
+    prompt_plain(message).map(SecretString::from)
+}
+
+fn prompt_plain(message: &str) -> WalletResult<String> {
+    rpassword::prompt_password(message).map_err(|err| {
+        WalletError::ValidationError(format!(
+            "Cannot read password from the terminal ({}); use --password-fd",
+            err
+        ))
+    })
+}
+
+fn read_fd(fd: i32) -> WalletResult<SecretString> {
+    read_fd_plain(fd).map(SecretString::from)
+}
+
+#[cfg(unix)]
+fn read_fd_plain(fd: i32) -> WalletResult<String> {
+    use std::os::fd::FromRawFd;
+
+    if fd < 0 {
+        return Err(WalletError::ValidationError(format!(
+            "Invalid password descriptor {}",
+            fd
+        )));
+    }
+    // SAFETY: the caller passed this descriptor to us for reading the
+    // password; we take ownership and close it once the line is read.
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    read_line(file)
+}
+
+#[cfg(not(unix))]
+fn read_fd_plain(_fd: i32) -> WalletResult<String> {
+    Err(WalletError::ValidationError(
+        "--password-fd is only supported on Unix".to_string(),
+    ))
+}
+
+/// First line of `source` without its line ending.
+fn read_line(source: impl Read) -> WalletResult<String> {
+    let mut line = String::new();
+    BufReader::new(source).read_line(&mut line)?;
+    let trimmed = line.trim_end_matches(['\n', '\r']);
+    if trimmed.is_empty() {
+        return Err(WalletError::ValidationError(
+            "No password on the given descriptor".to_string(),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_first_line_without_line_ending() {
+        let input = b"correct horse\r\nignored\n".as_slice();
+        assert_eq!(read_line(input).unwrap(), "correct horse");
+        assert!(read_line(b"\n".as_slice()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_password_from_inherited_descriptor() {
+        use secrecy::ExposeSecret;
+        use std::io::{Seek, Write};
+        use std::os::fd::IntoRawFd;
+
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(b"s3cret-Passw0rd\n").unwrap();
+        file.rewind().unwrap();
+        let password = PasswordSource::Fd(file.into_raw_fd()).current().unwrap();
+        assert_eq!(password.expose_secret(), "s3cret-Passw0rd");
+        assert!(PasswordSource::Fd(-1).current().is_err());
+    }
+}
@@ -8,7 +8,7 @@ use silica_models::crypto::{ChertCrypto, ChertHash, HashAlgorithm, StandardCrypt
 use std::fmt;
 use std::str::FromStr;
 
-const CHERT_BECH32_HRP: &str = "chert";
+pub const CHERT_BECH32_HRP: &str = "chert";
 
 /// A Chert blockchain address
 ///
@@ -65,16 +65,23 @@ impl Address {
 
     /// Parse an address from a string, supporting both 0x and Bech32 formats
     pub fn from_string(address: &str) -> WalletResult<Self> {
-        if address.starts_with("chert") {
-            // Bech32 format
-            Self::from_bech32(address)
-        } else if address.starts_with("0x") {
+        Self::from_string_with_hrp(address, CHERT_BECH32_HRP)
+    }
+
+    /// Parse an address whose Bech32 form uses the prefix `hrp`, as set by
+    /// the active network profile
+    pub fn from_string_with_hrp(address: &str, hrp: &str) -> WalletResult<Self> {
+        if address.starts_with("0x") {
             // Hex format
             Self::from_hex(address)
+        } else if address.starts_with(hrp) {
+            // Bech32 format
+            Self::from_bech32(address, hrp)
         } else {
-            Err(WalletError::InvalidAddress(
-                "Address must start with '0x' or 'chert'".to_string(),
-            ))
+            Err(WalletError::InvalidAddress(format!(
+                "Address must start with '0x' or '{}'",
+                hrp
+            )))
         }
     }
 
@@ -104,18 +111,19 @@ impl Address {
     }
 
     /// Parse a Bech32 address
-    fn from_bech32(bech32_address: &str) -> WalletResult<Self> {
+    fn from_bech32(bech32_address: &str, expected_hrp: &str) -> WalletResult<Self> {
         use bech32::Hrp;
 
         let (hrp, data) = bech32::decode(bech32_address)
             .map_err(|e| WalletError::InvalidAddress(format!("Invalid Bech32: {}", e)))?;
 
-        let expected_hrp = Hrp::parse(CHERT_BECH32_HRP)
+        let parsed_hrp = Hrp::parse(expected_hrp)
             .map_err(|e| WalletError::InvalidAddress(format!("Invalid HRP: {}", e)))?;
-        if hrp != expected_hrp {
-            return Err(WalletError::InvalidAddress(
-                "Invalid Bech32 HRP (must be 'chert')".to_string(),
-            ));
+        if hrp != parsed_hrp {
+            return Err(WalletError::InvalidAddress(format!(
+                "Invalid Bech32 HRP (must be '{}')",
+                expected_hrp
+            )));
         }
 
         // bech32 0.11+ returns bytes directly from decode
@@ -138,9 +146,14 @@ impl Address {
 
     /// Get the Bech32 string representation
     pub fn as_bech32_string(&self) -> WalletResult<String> {
+        self.as_bech32_string_with_hrp(CHERT_BECH32_HRP)
+    }
+
+    /// Get the Bech32 string representation under the prefix `hrp`
+    pub fn as_bech32_string_with_hrp(&self, hrp: &str) -> WalletResult<String> {
         use bech32::Hrp;
 
-        let hrp = Hrp::parse(CHERT_BECH32_HRP)
+        let hrp = Hrp::parse(hrp)
             .map_err(|e| WalletError::InvalidAddress(format!("Invalid HRP: {}", e)))?;
         // bech32 0.11+ encode takes bytes directly
         bech32::encode::<bech32::Bech32>(hrp, &self.raw_bytes)
@@ -983,6 +996,19 @@ mod tests {
         assert_eq!(addr.as_string(), addr_str);
     }
 
+    #[test]
+    fn test_bech32_prefix_follows_the_network() {
+        let addr = Address::from_bytes(vec![7u8; 20], AddressType::Account).unwrap();
+        let staging = addr.as_bech32_string_with_hrp("stg").unwrap();
+        assert_eq!(
+            Address::from_string_with_hrp(&staging, "stg").unwrap(),
+            addr
+        );
+        assert!(Address::from_string(&staging).is_err());
+        let chert = addr.as_bech32_string().unwrap();
+        assert!(Address::from_string_with_hrp(&chert, "stg").is_err());
+    }
+
     #[test]
     fn test_amount_creation() {
         let amount = Amount::from_chert(5).unwrap();
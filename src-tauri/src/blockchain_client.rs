@@ -3,23 +3,57 @@
 /// This module provides HTTP-based JSON-RPC communication with Chert blockchain nodes,
 /// implementing the methods needed for wallet functionality.
 use crate::api::types::{
-    BalanceResponse, CastVoteRequest, CastVoteResponse, ClaimStakingRewardsResponse,
-    CreateLockboxStakeResponse, DelegateRequest, DelegateResponse, DelegateTokensResponse,
-    GetAutoStakeStatusResponse, GetDelegationsResponse, GetLockboxRecordsResponse,
-    GetProposalResponse, GetProposalVotesResponse, GetProposalsResponse, GetStakingRewardsResponse,
-    GetUserDelegationsResponse, GetValidatorsResponse, GetVotingPowerResponse,
-    ToggleAutoStakingResponse, TransactionHistoryResponse, TransactionInfo,
-    UndelegateTokensResponse,
+    BalanceResponse, CastVoteRequest, CastVoteResponse, ChainIdResponse,
+    ClaimStakingRewardsResponse, CreateLockboxStakeResponse, CreateProposalResponse,
+    DelegateRequest, DelegateResponse, DelegateTokensResponse, GetAutoStakeStatusResponse,
+    GetDelegationsResponse, GetLockboxRecordsResponse, GetProposalResponse,
+    GetProposalVotesResponse, GetProposalsResponse, GetStakingParamsResponse,
+    GetStakingRewardsResponse, GetUnbondingEntriesResponse, GetUserDelegationsResponse,
+    GetValidatorsResponse, GetVotingPowerResponse, ProposalThresholdResponse,
+    RedelegateTokensResponse, ToggleAutoStakingResponse, TransactionHistoryResponse,
+    TransactionInfo, TransferReceipt, UndelegateTokensResponse, WithdrawStakeResponse,
 };
+use crate::blockchain::{BlockchainTransaction, PrivateKey};
 use crate::errors::{WalletError, WalletResult};
-use reqwest::Client;
+use crate::governance::batch::{AccountDelegation, AccountSigned, AccountVote};
+use crate::governance::SignedProposal;
+use crate::light_client::{self, AccountProof, BalanceVerification, LightClient, SignedHeader};
+use crate::network::ChainPin;
+use crate::proxy::ProxyRoutes;
+use crate::quorum::{self, QuorumMonitor};
+use crate::rpc_batch::{BatchCall, BatchResponse, RpcBatch, MAX_BATCH_CALLS};
+use crate::rpc_cache::{CacheStats, CachedRead, RateLimiter, ResponseCache};
+use crate::rpc_pool::RpcPool;
+use crate::tls;
+use parking_lot::RwLock;
+use reqwest::{Client, Proxy, Url};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_rustls::rustls::ClientConfig;
+
+/// JSON-RPC error code returned when the node does not implement a method
+const METHOD_NOT_FOUND: i32 = -32601;
+/// JSON-RPC error code for malformed or invalid method parameters
+const INVALID_PARAMS: i32 = -32602;
+/// EIP-1474 error code for requests over the node's rate limit
+const LIMIT_EXCEEDED: i32 = -32005;
 
 /// HTTP client for blockchain RPC communication
+///
+/// Requests go through an `RpcPool`; clones share the pool and its health data.
+#[derive(Debug, Clone)]
 pub struct BlockchainClient {
-    client: Client,
-    base_url: String,
+    transport: Arc<RwLock<Transport>>,
+    pool: RpcPool,
+    quorum: QuorumMonitor,
+    light: LightClient,
+    chain: ChainPin,
+    cache: ResponseCache,
+    limiter: RateLimiter,
+    next_id: Arc<AtomicU64>,
 }
 
 /// JSON-RPC request structure
@@ -38,7 +72,7 @@ struct JsonRpcResponse<T> {
     jsonrpc: String,
     result: Option<T>,
     error: Option<JsonRpcError>,
-    id: u64,
+    id: Option<u64>,
 }
 
 /// JSON-RPC error structure
@@ -48,33 +82,240 @@ struct JsonRpcError {
     message: String,
 }
 
+impl JsonRpcError {
+    /// Map the node's error onto a typed `WalletError`. Nonce and balance
+    /// failures share generic server codes across nodes, so they are
+    /// recognised by message.
+    fn into_wallet_error(self) -> WalletError {
+        let message = self.message.to_ascii_lowercase();
+        match self.code {
+            METHOD_NOT_FOUND => WalletError::MethodNotFound(self.message),
+            INVALID_PARAMS => WalletError::InvalidParams(self.message),
+            LIMIT_EXCEEDED => WalletError::RateLimited(self.message),
+            _ if message.contains("nonce")
+                || message.contains("already known")
+                || message.contains("replacement transaction underpriced") =>
+            {
+                WalletError::NonceConflict(self.message)
+            }
+            _ if message.contains("insufficient funds")
+                || message.contains("insufficient balance") =>
+            {
+                WalletError::InsufficientBalance(self.message)
+            }
+            _ if message.contains("rate limit") || message.contains("too many requests") => {
+                WalletError::RateLimited(self.message)
+            }
+            code => WalletError::RpcError {
+                code,
+                message: self.message,
+            },
+        }
+    }
+}
+
 impl BlockchainClient {
-    /// Create a new blockchain client
+    /// Create a client talking to a single endpoint
     pub fn new(base_url: String) -> WalletResult<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .map_err(|e| {
-                WalletError::NetworkError(format!("Failed to create HTTP client: {}", e))
-            })?;
+        Self::with_pool(RpcPool::new(vec![base_url])?)
+    }
 
+    /// Create a client that fails over across the endpoints of `pool`
+    pub fn with_pool(pool: RpcPool) -> WalletResult<Self> {
         Ok(BlockchainClient {
-            client,
-            base_url: base_url.trim_end_matches('/').to_string(),
+            transport: Arc::new(RwLock::new(Transport::new(
+                tls::default_config()?,
+                Arc::new(ProxyRoutes::default()),
+            )?)),
+            pool,
+            quorum: QuorumMonitor::default(),
+            light: LightClient::default(),
+            chain: ChainPin::default(),
+            cache: ResponseCache::default(),
+            limiter: RateLimiter::default(),
+            next_id: Arc::new(AtomicU64::new(1)),
+        })
+    }
+
+    /// Verify endpoint certificates with `tls` instead of the web PKI roots
+    pub fn with_tls(self, tls: Arc<ClientConfig>) -> WalletResult<Self> {
+        self.set_tls(tls)?;
+        Ok(self)
+    }
+
+    /// Switch the TLS settings of this client and its clones; requests
+    /// already in flight finish on the old connections.
+    pub fn set_tls(&self, tls: Arc<ClientConfig>) -> WalletResult<()> {
+        let proxy = self.transport.read().proxy.clone();
+        *self.transport.write() = Transport::new(tls, proxy)?;
+        Ok(())
+    }
+
+    /// TLS settings, for connections made outside the HTTP client.
+    pub fn tls_config(&self) -> Arc<ClientConfig> {
+        self.transport.read().tls.clone()
+    }
+
+    /// Route connections through the SOCKS proxies of `proxy`
+    pub fn with_proxy(self, proxy: ProxyRoutes) -> WalletResult<Self> {
+        self.set_proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// Switch the proxy routes of this client and its clones, like `set_tls`.
+    pub fn set_proxy(&self, proxy: ProxyRoutes) -> WalletResult<()> {
+        let tls = self.transport.read().tls.clone();
+        *self.transport.write() = Transport::new(tls, Arc::new(proxy))?;
+        Ok(())
+    }
+
+    /// Proxy routes, for connections made outside the HTTP client.
+    pub fn proxy_routes(&self) -> Arc<ProxyRoutes> {
+        self.transport.read().proxy.clone()
+    }
+
+    /// Serve and invalidate cached reads through `cache`
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Space out requests with `limiter`'s per-endpoint token buckets
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    pub fn cache(&self) -> &ResponseCache {
+        &self.cache
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            methods: self.cache.stats(),
+            endpoints: self.limiter.stats(),
+        }
+    }
+
+    /// Cross-check critical reads according to `quorum`'s settings
+    pub fn with_quorum(mut self, quorum: QuorumMonitor) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
+    /// Verify balances against state proofs with `light`'s validator set
+    pub fn with_light_client(mut self, light: LightClient) -> Self {
+        self.light = light;
+        self
+    }
+
+    /// Only send signed requests to endpoints on `chain`'s pinned chain
+    pub fn with_chain_pin(mut self, chain: ChainPin) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    pub fn pool(&self) -> &RpcPool {
+        &self.pool
+    }
+
+    pub fn quorum(&self) -> &QuorumMonitor {
+        &self.quorum
+    }
+
+    pub fn light_client(&self) -> &LightClient {
+        &self.light
+    }
+
+    pub fn chain_pin(&self) -> &ChainPin {
+        &self.chain
+    }
+
+    /// Confirm an endpoint reports the pinned chain, returning its chain id.
+    /// Callers check this before signing so nothing is signed for the wrong
+    /// network.
+    pub async fn verify_chain(&self) -> WalletResult<String> {
+        self.failover("get_chain_id", Delivery::Idempotent, |url| async move {
+            self.endpoint_chain(&url).await
         })
+        .await
     }
 
-    /// Get account balance
+    /// Get account balance, checked against a state proof when the light
+    /// client is enabled
     pub async fn get_balance(&self, address: &str) -> WalletResult<BalanceResponse> {
         let params = serde_json::json!({ "address": address });
-        let response = self.rpc_call("get_balance", params).await?;
+        self.cached(CachedRead::Balance, &params, || {
+            self.fetch_balance(address, params.clone())
+        })
+        .await
+    }
+
+    /// Get an account's next nonce from the node, bypassing the read cache so
+    /// back-to-back signings never reuse a stale nonce
+    pub async fn get_nonce(&self, address: &str) -> WalletResult<u64> {
+        let params = serde_json::json!({ "address": address });
+        Ok(self.fetch_balance(address, params).await?.nonce)
+    }
+
+    async fn fetch_balance(
+        &self,
+        address: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<BalanceResponse> {
+        let mut response: BalanceResponse = self.rpc_call_verified("get_balance", params).await?;
+        if self.light.config().enabled {
+            let verification = match self.verify_balance(&response).await {
+                Ok(height) => BalanceVerification::verified(height),
+                Err(err) => {
+                    log::warn!("Balance of {} could not be verified: {}", address, err);
+                    BalanceVerification::unverified(&err)
+                }
+            };
+            response.verification = Some(verification);
+        }
         Ok(response)
     }
 
+    /// Get the signed header at `height`, or the latest one
+    pub async fn get_block_header(&self, height: Option<u64>) -> WalletResult<SignedHeader> {
+        let params = serde_json::json!({ "height": height });
+        self.rpc_call("get_block_header", params).await
+    }
+
+    /// Get an account's state with its Merkle proof at the latest height
+    pub async fn get_account_proof(&self, address: &str) -> WalletResult<AccountProof> {
+        let params = serde_json::json!({ "address": address });
+        self.rpc_call("get_account_proof", params).await
+    }
+
+    /// Prove `balance` against a header signed by the trusted validators,
+    /// returning the header's height. The latest header is tracked first, so
+    /// a proof of old state is refused even when its header verifies.
+    async fn verify_balance(&self, balance: &BalanceResponse) -> WalletResult<u64> {
+        self.light
+            .accept_header(self.get_block_header(None).await?)?;
+        let proof = self.get_account_proof(&balance.address).await?;
+        light_client::ensure_matches(balance, &proof)?;
+        self.light.ensure_fresh(proof.height)?;
+        let header = match self.light.trusted_header(proof.height) {
+            Some(header) => header,
+            None => self
+                .light
+                .accept_header(self.get_block_header(Some(proof.height)).await?)?,
+        };
+        light_client::verify_account_proof(&proof, &header)?;
+        Ok(header.height)
+    }
+
     /// Get transaction by ID
     pub async fn get_transaction(&self, tx_id: &str) -> WalletResult<TransactionInfo> {
         let params = serde_json::json!({ "tx_id": tx_id });
-        let response = self.rpc_call("get_transaction", params).await?;
+        let response = self.rpc_call_verified("get_transaction", params).await?;
         Ok(response)
     }
 
@@ -98,7 +339,7 @@ impl BlockchainClient {
     /// Send a transaction
     pub async fn send_transaction(&self, tx_data: serde_json::Value) -> WalletResult<String> {
         let params = serde_json::json!({ "transaction": tx_data });
-        let response: serde_json::Value = self.rpc_call("send_transaction", params).await?;
+        let response: serde_json::Value = self.rpc_submit("send_transaction", params).await?;
         let tx_id = response
             .get("tx_id")
             .and_then(|v| v.as_str())
@@ -107,6 +348,41 @@ impl BlockchainClient {
         Ok(tx_id.to_string())
     }
 
+    /// Sign a transfer from `sender` with `key` at the account's next nonce
+    /// and submit it. The submission itself checks the endpoint's chain.
+    pub async fn submit_transfer(
+        &self,
+        key: &PrivateKey,
+        sender: &str,
+        recipient: &str,
+        amount: u64,
+        fee: u64,
+        data: Option<Vec<u8>>,
+    ) -> WalletResult<TransferReceipt> {
+        let nonce = self.get_nonce(sender).await?;
+        let mut transaction = BlockchainTransaction::new(
+            sender.to_string(),
+            recipient.to_string(),
+            amount,
+            fee,
+            nonce,
+            data,
+        )?;
+        transaction.sign(key)?;
+        let tx_id = self
+            .send_transaction(serde_json::to_value(&transaction)?)
+            .await?;
+        Ok(TransferReceipt {
+            tx_id,
+            sender: transaction.sender,
+            recipient: transaction.recipient,
+            amount,
+            fee,
+            nonce,
+            explorer_url: None,
+        })
+    }
+
     /// Get current gas price
     pub async fn get_gas_price(&self) -> WalletResult<serde_json::Value> {
         let response = self
@@ -120,29 +396,16 @@ impl BlockchainClient {
         let response: serde_json::Value = self
             .rpc_call("eth_blockNumber", serde_json::Value::Null)
             .await?;
-        let block_num_str = response
-            .get("block_number")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                WalletError::NetworkError("Invalid block number response".to_string())
-            })?;
-
-        let block_num = if let Some(stripped) = block_num_str.strip_prefix("0x") {
-            u64::from_str_radix(stripped, 16)
-        } else {
-            block_num_str.parse()
-        }
-        .map_err(|_| WalletError::NetworkError("Invalid block number format".to_string()))?;
-
-        Ok(block_num)
+        parse_block_number(&response)
     }
 
     /// Get list of validators
     pub async fn get_validators(&self) -> WalletResult<GetValidatorsResponse> {
-        let response = self
-            .rpc_call("staking_get_validators", serde_json::Value::Null)
-            .await?;
-        Ok(response)
+        let params = serde_json::Value::Null;
+        self.cached(CachedRead::Validators, &params, || {
+            self.rpc_call("staking_get_validators", params.clone())
+        })
+        .await
     }
 
     /// Get user delegations
@@ -177,6 +440,13 @@ impl BlockchainClient {
         Ok(response)
     }
 
+    /// Get the chain's staking parameters, or `None` if the node does not
+    /// publish them
+    pub async fn get_staking_params(&self) -> WalletResult<Option<GetStakingParamsResponse>> {
+        self.rpc_call_optional("staking_get_params", serde_json::json!({}))
+            .await
+    }
+
     /// Get auto-stake status
     pub async fn get_auto_stake_status(
         &self,
@@ -201,7 +471,7 @@ impl BlockchainClient {
             "validator": validator,
             "amount": amount
         });
-        let response = self.rpc_call("staking_delegate_tokens", params).await?;
+        let response = self.rpc_submit("staking_delegate_tokens", params).await?;
         Ok(response)
     }
 
@@ -217,7 +487,53 @@ impl BlockchainClient {
             "validator": validator,
             "amount": amount
         });
-        let response = self.rpc_call("staking_undelegate_tokens", params).await?;
+        let response = self.rpc_submit("staking_undelegate_tokens", params).await?;
+        Ok(response)
+    }
+
+    /// Move delegated tokens between validators in a single transaction.
+    ///
+    /// Returns `None` when the node does not support native redelegation.
+    pub async fn redelegate_tokens(
+        &self,
+        delegator: &str,
+        from_validator: &str,
+        to_validator: &str,
+        amount: u64,
+    ) -> WalletResult<Option<RedelegateTokensResponse>> {
+        let params = serde_json::json!({
+            "delegator": delegator,
+            "fromValidator": from_validator,
+            "toValidator": to_validator,
+            "amount": amount
+        });
+        self.rpc_submit_optional("staking_redelegate_tokens", params)
+            .await
+    }
+
+    /// Get pending unbonding entries, or `None` if the node does not expose them
+    pub async fn get_unbonding_entries(
+        &self,
+        address: &str,
+    ) -> WalletResult<Option<GetUnbondingEntriesResponse>> {
+        let params = serde_json::json!({ "address": address });
+        self.rpc_call_optional("staking_get_unbonding_entries", params)
+            .await
+    }
+
+    /// Withdraw matured stake back to the liquid balance
+    pub async fn withdraw_stake(
+        &self,
+        account: &str,
+        source: &str,
+        amount: u64,
+    ) -> WalletResult<WithdrawStakeResponse> {
+        let params = serde_json::json!({
+            "account": account,
+            "source": source,
+            "amount": amount
+        });
+        let response = self.rpc_submit("staking_withdraw", params).await?;
         Ok(response)
     }
 
@@ -234,7 +550,7 @@ impl BlockchainClient {
             "termMonths": term_months
         });
         let response = self
-            .rpc_call("staking_create_lockbox_stake", params)
+            .rpc_submit("staking_create_lockbox_stake", params)
             .await?;
         Ok(response)
     }
@@ -249,7 +565,9 @@ impl BlockchainClient {
             "account": account,
             "enable": enable
         });
-        let response = self.rpc_call("staking_toggle_auto_staking", params).await?;
+        let response = self
+            .rpc_submit("staking_toggle_auto_staking", params)
+            .await?;
         Ok(response)
     }
 
@@ -259,7 +577,7 @@ impl BlockchainClient {
         account: &str,
     ) -> WalletResult<ClaimStakingRewardsResponse> {
         let params = serde_json::json!({ "account": account });
-        let response = self.rpc_call("staking_claim_rewards", params).await?;
+        let response = self.rpc_submit("staking_claim_rewards", params).await?;
         Ok(response)
     }
 
@@ -269,9 +587,10 @@ impl BlockchainClient {
         request: Option<serde_json::Value>,
     ) -> WalletResult<GetProposalsResponse> {
         let params = request.unwrap_or(serde_json::Value::Null);
-        let response: GetProposalsResponse =
-            self.rpc_call("governance_list_proposals", params).await?;
-        Ok(response)
+        self.cached(CachedRead::Proposals, &params, || {
+            self.rpc_call("governance_list_proposals", params.clone())
+        })
+        .await
     }
 
     /// Get specific proposal details
@@ -279,8 +598,9 @@ impl BlockchainClient {
         let params = serde_json::json!({
             "proposal_id": proposal_id
         });
-        let response: GetProposalResponse =
-            self.rpc_call("governance_get_proposal", params).await?;
+        let response: GetProposalResponse = self
+            .rpc_call_verified("governance_get_proposal", params)
+            .await?;
         Ok(response)
     }
 
@@ -307,8 +627,9 @@ impl BlockchainClient {
         let params = serde_json::json!({
             "address": address
         });
-        let response: GetVotingPowerResponse =
-            self.rpc_call("governance_get_voting_power", params).await?;
+        let response: GetVotingPowerResponse = self
+            .rpc_call_verified("governance_get_voting_power", params)
+            .await?;
         Ok(response)
     }
 
@@ -325,7 +646,49 @@ impl BlockchainClient {
     /// Cast vote on proposal
     pub async fn cast_vote(&self, request: CastVoteRequest) -> WalletResult<CastVoteResponse> {
         let params = serde_json::json!(request);
-        let response: CastVoteResponse = self.rpc_call("governance_cast_vote", params).await?;
+        let response: CastVoteResponse = self.rpc_submit("governance_cast_vote", params).await?;
+        Ok(response)
+    }
+
+    /// Get the voting power required to create a proposal, if the node reports it
+    pub async fn get_proposal_threshold(&self) -> WalletResult<Option<ProposalThresholdResponse>> {
+        self.rpc_call_optional("governance_get_proposal_threshold", serde_json::Value::Null)
+            .await
+    }
+
+    /// Submit a signed governance proposal
+    pub async fn create_proposal(
+        &self,
+        proposal: &SignedProposal,
+    ) -> WalletResult<CreateProposalResponse> {
+        let params = serde_json::json!(proposal);
+        let response = self
+            .rpc_submit("governance_create_proposal", params)
+            .await?;
+        Ok(response)
+    }
+
+    /// Submit a vote signed by the voting account
+    pub async fn cast_signed_vote(
+        &self,
+        vote: &AccountSigned<AccountVote>,
+    ) -> WalletResult<CastVoteResponse> {
+        let params = serde_json::json!(vote);
+        let response = self
+            .rpc_submit("governance_cast_signed_vote", params)
+            .await?;
+        Ok(response)
+    }
+
+    /// Submit a voting power delegation signed by the delegating account
+    pub async fn delegate_signed(
+        &self,
+        delegation: &AccountSigned<AccountDelegation>,
+    ) -> WalletResult<DelegateResponse> {
+        let params = serde_json::json!(delegation);
+        let response = self
+            .rpc_submit("governance_delegate_signed", params)
+            .await?;
         Ok(response)
     }
 
@@ -333,56 +696,551 @@ impl BlockchainClient {
     pub async fn delegate(&self, request: DelegateRequest) -> WalletResult<DelegateResponse> {
         let params = serde_json::json!(request);
         let response = self
-            .rpc_call("governance_delegate_voting_power", params)
+            .rpc_submit("governance_delegate_voting_power", params)
             .await?;
         Ok(response)
     }
 
-    /// Make a JSON-RPC call to the node
+    /// Query every endpoint in the pool for its block height and record the
+    /// result, so lagging or unreachable nodes are known before a request hits them.
+    pub async fn probe_endpoints(&self) {
+        for url in self.pool.urls() {
+            let request = self.request("eth_blockNumber", serde_json::Value::Null);
+            let started = Instant::now();
+            let outcome = self
+                .send_to::<serde_json::Value>(&url, &request)
+                .await
+                .map_err(|failure| failure.error)
+                .and_then(|response| parse_block_number(&response));
+            match outcome {
+                Ok(height) => {
+                    self.pool.record_success(&url, started.elapsed());
+                    self.pool.record_height(&url, height);
+                }
+                Err(err) => self.pool.record_failure(&url, &err, started.elapsed()),
+            }
+        }
+    }
+
+    /// Answer `read` from the cache, or fetch and remember it.
+    async fn cached<T, F, Fut>(
+        &self,
+        read: CachedRead,
+        params: &serde_json::Value,
+        fetch: F,
+    ) -> WalletResult<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = WalletResult<T>>,
+    {
+        let key = params.to_string();
+        if let Some(hit) = self.cache.get(read, &key) {
+            return Ok(hit);
+        }
+        let generation = self.cache.generation();
+        let value = fetch().await?;
+        self.cache.insert(read, key, value.clone(), generation);
+        Ok(value)
+    }
+
+    /// Make a JSON-RPC read call, failing over across the pool
     async fn rpc_call<T: for<'de> Deserialize<'de>>(
         &self,
         method: &str,
         params: serde_json::Value,
     ) -> WalletResult<T> {
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            method: method.to_string(),
-            params,
-            id: 1,
+        self.dispatch(method, params, Delivery::Idempotent).await
+    }
+
+    /// Like `rpc_call`, but maps a method-not-found error to `Ok(None)`.
+    async fn rpc_call_optional<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<Option<T>> {
+        optional(self.dispatch(method, params, Delivery::Idempotent).await)
+    }
+
+    /// Make a read a single lying node could exploit. In quorum mode it is
+    /// sent to every in-sync endpoint and only trusted if they all agree.
+    async fn rpc_call_verified<T: for<'de> Deserialize<'de> + Serialize>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<T> {
+        let config = self.quorum.config();
+        if !config.enabled {
+            return self.rpc_call(method, params).await;
+        }
+
+        let request = self.request(method, params);
+        let mut outcomes = Vec::new();
+        for url in self.pool.in_sync() {
+            let started = Instant::now();
+            let outcome = self
+                .send_to::<T>(&url, &request)
+                .await
+                .map_err(|failure| failure.error);
+            match &outcome {
+                Ok(_) => self.pool.record_success(&url, started.elapsed()),
+                Err(err) => self.pool.record_failure(&url, err, started.elapsed()),
+            }
+            outcomes.push((url, outcome));
+        }
+
+        quorum::reconcile(method, outcomes, config.min_responses).unwrap_or_else(|report| {
+            let err = WalletError::from(&report);
+            log::warn!("Quorum check failed: {}", err);
+            self.quorum.record(report);
+            Err(err)
+        })
+    }
+
+    /// Submit a state-changing JSON-RPC call. It is only resent to another
+    /// endpoint when the first one demonstrably never received it.
+    async fn rpc_submit<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<T> {
+        self.dispatch(method, params, Delivery::AtMostOnce).await
+    }
+
+    /// Like `rpc_submit`, but maps a method-not-found error to `Ok(None)`.
+    async fn rpc_submit_optional<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<Option<T>> {
+        optional(self.dispatch(method, params, Delivery::AtMostOnce).await)
+    }
+
+    /// Send a batch of reads in as few round trips as possible. Results are
+    /// matched to calls by request id, whatever order the node answers in.
+    /// Critical reads are made one by one while quorum mode or the light
+    /// client is enabled, and a node that refuses batches gets single calls.
+    pub async fn send_batch(&self, batch: &RpcBatch) -> WalletResult<BatchResponse> {
+        let checked = self.quorum.config().enabled || self.light.config().enabled;
+        let mut results = vec![None; batch.len()];
+        let mut batched = Vec::with_capacity(batch.len());
+        for (index, call) in batch.calls().iter().enumerate() {
+            if checked && call.critical {
+                results[index] = Some(self.checked_read(call).await);
+            } else {
+                batched.push(index);
+            }
+        }
+
+        for chunk in batched.chunks(MAX_BATCH_CALLS) {
+            let calls: Vec<&BatchCall> = chunk.iter().map(|&index| &batch.calls()[index]).collect();
+            let requests: Vec<_> = calls
+                .iter()
+                .map(|call| self.request(call.method, call.params.clone()))
+                .collect();
+            let requests = &requests;
+            let label = format!("batch of {}", requests.len());
+            let reply = self
+                .failover(&label, Delivery::Idempotent, |url| async move {
+                    self.send_batch_to(&url, requests).await
+                })
+                .await?;
+            let responses = match reply {
+                BatchReply::Answers(responses) => responses,
+                BatchReply::Rejected(err) => {
+                    log::warn!("Node refused a {}, sending calls singly: {}", label, err);
+                    let mut responses = Vec::with_capacity(calls.len());
+                    for call in &calls {
+                        responses.push(self.rpc_call(call.method, call.params.clone()).await);
+                    }
+                    responses
+                }
+            };
+            for (&index, response) in chunk.iter().zip(responses) {
+                results[index] = Some(response);
+            }
+        }
+
+        Ok(BatchResponse::new(results.into_iter().flatten().collect()))
+    }
+
+    /// Make a critical batched read the way its single-call method does.
+    async fn checked_read(&self, call: &BatchCall) -> WalletResult<serde_json::Value> {
+        if call.method != "get_balance" {
+            return self
+                .rpc_call_verified(call.method, call.params.clone())
+                .await;
+        }
+        let address = call.params["address"].as_str().unwrap_or_default();
+        let balance = self.get_balance(address).await?;
+        serde_json::to_value(balance)
+            .map_err(|e| WalletError::InvalidResponse(format!("Unexpected balance: {}", e)))
+    }
+
+    /// Try endpoints in health order, recording every outcome in the pool.
+    async fn dispatch<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        delivery: Delivery,
+    ) -> WalletResult<T> {
+        let request = self.request(method, params);
+        let request = &request;
+        let result = self
+            .failover(method, delivery, |url| async move {
+                if delivery == Delivery::AtMostOnce && self.chain.expected().is_some() {
+                    // The write itself has not been sent yet, whatever the check hit.
+                    self.endpoint_chain(&url)
+                        .await
+                        .map_err(|failure| AttemptError {
+                            delivered: false,
+                            error: failure.error,
+                        })?;
+                }
+                self.send_to(&url, request).await
+            })
+            .await;
+        if delivery == Delivery::AtMostOnce {
+            // Even a failed write may have reached the node.
+            self.cache.invalidate_after(method);
+        }
+        result
+    }
+
+    /// The chain `url` is on, refused unless it is the pinned one. Answers
+    /// are cached for a while so submissions do not cost an extra round trip.
+    /// A node that cannot report its chain is refused like one on another
+    /// chain, so the failure is never mistaken for the submitted method
+    /// being unsupported.
+    async fn endpoint_chain(&self, url: &str) -> Result<String, AttemptError> {
+        let chain_id = match self.chain.reported(url) {
+            Some(chain_id) => chain_id,
+            None => {
+                let request = self.request("get_chain_id", serde_json::Value::Null);
+                let response: ChainIdResponse =
+                    self.send_to(url, &request)
+                        .await
+                        .map_err(|failure| match failure.error {
+                            WalletError::MethodNotFound(_) => AttemptError {
+                                delivered: false,
+                                error: WalletError::ChainMismatch(format!(
+                                    "{} does not report its chain id",
+                                    url
+                                )),
+                            },
+                            _ => failure,
+                        })?;
+                self.chain.record(url, response.chain_id.clone());
+                response.chain_id
+            }
         };
+        self.chain
+            .check(url, &chain_id)
+            .map_err(|error| AttemptError {
+                delivered: false,
+                error,
+            })?;
+        Ok(chain_id)
+    }
 
-        let url = format!("{}/jsonrpc", self.base_url);
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
+    async fn failover<R, F, Fut>(
+        &self,
+        label: &str,
+        delivery: Delivery,
+        attempt: F,
+    ) -> WalletResult<R>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<R, AttemptError>>,
+    {
+        let mut last_error = None;
+
+        for url in self.pool.candidates() {
+            let started = Instant::now();
+            match attempt(url.clone()).await {
+                Ok(result) => {
+                    self.pool.record_success(&url, started.elapsed());
+                    return Ok(result);
+                }
+                Err(failure) => {
+                    self.pool
+                        .record_failure(&url, &failure.error, started.elapsed());
+                    let resend = failure.error.is_retryable()
+                        && (delivery == Delivery::Idempotent || !failure.delivered);
+                    if !resend {
+                        return Err(failure.error);
+                    }
+                    log::warn!("RPC {} failed on {}: {}", label, url, failure.error);
+                    last_error = Some(failure.error);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| WalletError::NetworkError("No RPC endpoints available".to_string())))
+    }
+
+    /// Send `request` to a single endpoint.
+    async fn send_to<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        request: &JsonRpcRequest<serde_json::Value>,
+    ) -> Result<T, AttemptError> {
+        let rpc_response: JsonRpcResponse<T> =
+            self.post(url, request).await?.json().await.map_err(|e| {
+                AttemptError::delivered(WalletError::InvalidResponse(format!(
+                    "Failed to parse response: {}",
+                    e
+                )))
+            })?;
+
+        if let Some(error) = rpc_response.error {
+            return Err(AttemptError::delivered(error.into_wallet_error()));
+        }
+        if rpc_response.id != Some(request.id) {
+            return Err(AttemptError::delivered(WalletError::InvalidResponse(
+                format!(
+                    "Response id {:?} does not match request id {}",
+                    rpc_response.id, request.id
+                ),
+            )));
+        }
+
+        rpc_response.result.ok_or_else(|| {
+            AttemptError::delivered(WalletError::InvalidResponse(
+                "No result in RPC response".to_string(),
+            ))
+        })
+    }
+
+    /// Send a batch to a single endpoint and match the answers to `requests`.
+    async fn send_batch_to(
+        &self,
+        url: &str,
+        requests: &[JsonRpcRequest<serde_json::Value>],
+    ) -> Result<BatchReply, AttemptError> {
+        let body: serde_json::Value =
+            self.post(url, requests).await?.json().await.map_err(|e| {
+                AttemptError::delivered(WalletError::InvalidResponse(format!(
+                    "Failed to parse response: {}",
+                    e
+                )))
+            })?;
+        let ids: Vec<u64> = requests.iter().map(|request| request.id).collect();
+        correlate_batch(&ids, body).map_err(AttemptError::delivered)
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        body: &(impl Serialize + ?Sized),
+    ) -> Result<reqwest::Response, AttemptError> {
+        let http = {
+            let transport = self.transport.read();
+            // Strict mode must never fall back to a direct connection.
+            let host = Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            transport.proxy.check(&host).map_err(|error| AttemptError {
+                delivered: false,
+                error,
+            })?;
+            transport.http.clone()
+        };
+        self.limiter.acquire(url).await;
+        let response = http
+            .post(format!("{}/jsonrpc", url))
+            .json(body)
             .send()
             .await
-            .map_err(|e| WalletError::NetworkError(format!("HTTP request failed: {}", e)))?;
+            .map_err(|e| AttemptError {
+                delivered: !e.is_connect(),
+                error: if e.is_timeout() {
+                    WalletError::ConnectionTimeout
+                } else {
+                    WalletError::NetworkError(format!("HTTP request failed: {}", e))
+                },
+            })?;
 
-        if !response.status().is_success() {
-            return Err(WalletError::NetworkError(format!(
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(AttemptError {
+                delivered: false,
+                error: WalletError::RateLimited(format!("HTTP error: {}", status)),
+            });
+        }
+        if !status.is_success() {
+            return Err(AttemptError::delivered(WalletError::NetworkError(format!(
                 "HTTP error: {}",
-                response.status()
-            )));
+                status
+            ))));
         }
+        Ok(response)
+    }
 
-        let rpc_response: JsonRpcResponse<T> = response
-            .json()
-            .await
-            .map_err(|e| WalletError::NetworkError(format!("Failed to parse response: {}", e)))?;
+    /// Build a request with an id unique to this client and its clones.
+    fn request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> JsonRpcRequest<serde_json::Value> {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
 
-        if let Some(error) = rpc_response.error {
-            return Err(WalletError::NetworkError(format!(
-                "RPC error {}: {}",
-                error.code, error.message
+/// HTTP client and the TLS and proxy settings it was built with.
+#[derive(Debug)]
+struct Transport {
+    http: Client,
+    tls: Arc<ClientConfig>,
+    proxy: Arc<ProxyRoutes>,
+}
+
+impl Transport {
+    fn new(tls: Arc<ClientConfig>, proxy: Arc<ProxyRoutes>) -> WalletResult<Self> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .use_preconfigured_tls(ClientConfig::clone(&tls));
+        // Without configured proxies the system proxy settings still apply.
+        if proxy.is_enabled() || proxy.is_strict() {
+            let routes = proxy.clone();
+            builder = builder.proxy(Proxy::custom(move |url| {
+                url.host_str().and_then(|host| routes.route(host)).cloned()
+            }));
+        }
+        let http = builder.build().map_err(|e| {
+            WalletError::NetworkError(format!("Failed to create HTTP client: {}", e))
+        })?;
+        Ok(Self { http, tls, proxy })
+    }
+}
+
+/// Whether a request that failed on one endpoint may be resent to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// Reads: safe to repeat after any retryable error.
+    Idempotent,
+    /// Writes: repeated only if the node never received the request.
+    AtMostOnce,
+}
+
+/// A failed attempt against one endpoint.
+struct AttemptError {
+    error: WalletError,
+    /// Whether the request may have reached the node.
+    delivered: bool,
+}
+
+impl AttemptError {
+    fn delivered(error: WalletError) -> Self {
+        Self {
+            error,
+            delivered: true,
+        }
+    }
+}
+
+/// How a node answered a batch request.
+#[derive(Debug)]
+enum BatchReply {
+    /// One result per request, in the order they were sent.
+    Answers(Vec<WalletResult<serde_json::Value>>),
+    /// The node answered with a single error instead of an array.
+    Rejected(WalletError),
+}
+
+/// Match the node's answers to a batch with the request `ids`, in request order.
+///
+/// Calls the node did not answer get an error of their own; answers to ids
+/// that were never sent, or repeated answers, invalidate the whole response.
+fn correlate_batch(ids: &[u64], body: serde_json::Value) -> WalletResult<BatchReply> {
+    let malformed = |e: serde_json::Error| {
+        WalletError::InvalidResponse(format!("Malformed batch response: {}", e))
+    };
+    let responses: Vec<JsonRpcResponse<serde_json::Value>> = match body {
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<_, _>>()
+            .map_err(malformed)?,
+        // A node that rejects the batch as a whole answers with one error object.
+        other => {
+            let response: JsonRpcResponse<serde_json::Value> =
+                serde_json::from_value(other).map_err(malformed)?;
+            return match response.error {
+                Some(error) => Ok(BatchReply::Rejected(error.into_wallet_error())),
+                None => Err(WalletError::InvalidResponse(
+                    "Expected an array in reply to a batch request".to_string(),
+                )),
+            };
+        }
+    };
+
+    let mut results: Vec<Option<WalletResult<serde_json::Value>>> = vec![None; ids.len()];
+    for response in responses {
+        // Entries the node could not attribute to a request carry a null id.
+        let Some(id) = response.id else {
+            continue;
+        };
+        let index = ids.iter().position(|&sent| sent == id).ok_or_else(|| {
+            WalletError::InvalidResponse(format!("Batch response contains unknown id {}", id))
+        })?;
+        if results[index].is_some() {
+            return Err(WalletError::InvalidResponse(format!(
+                "Batch response repeats id {}",
+                id
             )));
         }
+        results[index] = Some(match response.error {
+            Some(error) => Err(error.into_wallet_error()),
+            None => response.result.ok_or_else(|| {
+                WalletError::InvalidResponse("No result in RPC response".to_string())
+            }),
+        });
+    }
+
+    Ok(BatchReply::Answers(
+        results
+            .into_iter()
+            .zip(ids)
+            .map(|(result, id)| {
+                result.unwrap_or_else(|| {
+                    Err(WalletError::InvalidResponse(format!(
+                        "No response for request {}",
+                        id
+                    )))
+                })
+            })
+            .collect(),
+    ))
+}
+
+fn optional<T>(result: WalletResult<T>) -> WalletResult<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(WalletError::MethodNotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn parse_block_number(response: &serde_json::Value) -> WalletResult<u64> {
+    let block_num_str = response
+        .get("block_number")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| WalletError::NetworkError("Invalid block number response".to_string()))?;
 
-        rpc_response
-            .result
-            .ok_or_else(|| WalletError::NetworkError("No result in RPC response".to_string()))
+    if let Some(stripped) = block_num_str.strip_prefix("0x") {
+        u64::from_str_radix(stripped, 16)
+    } else {
+        block_num_str.parse()
     }
+    .map_err(|_| WalletError::NetworkError("Invalid block number format".to_string()))
 }
 
 impl Default for BlockchainClient {
@@ -391,6 +1249,103 @@ impl Default for BlockchainClient {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc_error(code: i32, message: &str) -> WalletError {
+        JsonRpcError {
+            code,
+            message: message.to_string(),
+        }
+        .into_wallet_error()
+    }
+
+    #[test]
+    fn node_errors_map_to_typed_variants() {
+        assert!(matches!(
+            rpc_error(-32601, "no such method"),
+            WalletError::MethodNotFound(_)
+        ));
+        assert!(matches!(
+            rpc_error(-32602, "bad address"),
+            WalletError::InvalidParams(_)
+        ));
+        assert!(matches!(
+            rpc_error(-32000, "nonce too low"),
+            WalletError::NonceConflict(_)
+        ));
+        assert!(matches!(
+            rpc_error(-32010, "Insufficient funds for gas * price + value"),
+            WalletError::InsufficientBalance(_)
+        ));
+        assert!(matches!(
+            rpc_error(-32005, "request limit reached"),
+            WalletError::RateLimited(_)
+        ));
+        assert!(matches!(
+            rpc_error(-32003, "transaction rejected"),
+            WalletError::RpcError { code: -32003, .. }
+        ));
+    }
+
+    #[test]
+    fn retry_classification_separates_transient_failures() {
+        assert!(WalletError::ConnectionTimeout.is_retryable());
+        assert!(rpc_error(-32005, "slow down").is_retryable());
+        assert!(rpc_error(-32603, "internal error").is_retryable());
+        assert!(!rpc_error(-32000, "nonce too low").is_retryable());
+        assert!(!rpc_error(-32000, "insufficient balance").is_retryable());
+        assert!(!rpc_error(-32602, "bad params").is_retryable());
+        assert!(!WalletError::ValidationError("bad".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn batch_answers_are_matched_by_id() {
+        let body = serde_json::json!([
+            { "jsonrpc": "2.0", "id": 12, "result": { "nonce": 2 } },
+            { "jsonrpc": "2.0", "id": 10, "result": { "nonce": 0 } },
+            { "jsonrpc": "2.0", "id": 13, "error": { "code": -32602, "message": "bad address" } },
+        ]);
+        let Ok(BatchReply::Answers(results)) = correlate_batch(&[10, 11, 12, 13], body) else {
+            panic!("expected per-call answers");
+        };
+
+        assert_eq!(results[0].as_ref().unwrap()["nonce"], 0);
+        assert!(matches!(results[1], Err(WalletError::InvalidResponse(_))));
+        assert_eq!(results[2].as_ref().unwrap()["nonce"], 2);
+        assert!(matches!(results[3], Err(WalletError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn inconsistent_batch_answers_are_rejected() {
+        let answer = |id: u64| serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": 1 });
+        let unknown = serde_json::json!([answer(1), answer(7)]);
+        assert!(correlate_batch(&[1, 2], unknown).is_err());
+        let repeated = serde_json::json!([answer(1), answer(1)]);
+        assert!(correlate_batch(&[1, 2], repeated).is_err());
+
+        let rejected = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32005, "message": "batch too large" },
+        });
+        assert!(matches!(
+            correlate_batch(&[1, 2], rejected),
+            Ok(BatchReply::Rejected(WalletError::RateLimited(_)))
+        ));
+    }
+
+    #[test]
+    fn request_ids_are_unique_across_clones() {
+        let client = BlockchainClient::default();
+        let clone = client.clone();
+        let first = client.request("get_balance", serde_json::Value::Null).id;
+        let second = clone.request("get_balance", serde_json::Value::Null).id;
+        assert_ne!(first, second);
+    }
+}
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
@@ -0,0 +1,96 @@
+//! Governance writes behind the proposal and vote commands.
+
+use crate::api::types::{
+    BatchVoteRequest, BatchVoteResult, CastVoteRequest, CastVoteResponse, CreateProposalRequest,
+    CreateProposalResponse,
+};
+use crate::app_state::WalletContext;
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::batch::{self, AccountVote};
+use crate::governance::proposal::ensure_proposal_threshold;
+use crate::governance::{voting, ProposalPayload};
+
+/// Vote through the node's operator account once the proposal is open.
+pub async fn cast_vote(
+    ctx: &WalletContext,
+    request: CastVoteRequest,
+) -> WalletResult<CastVoteResponse> {
+    let client = ctx.rpc_client();
+    let proposal = client.get_proposal(request.proposal_id).await?.proposal;
+    voting::ensure_voting_open(&proposal, chrono::Utc::now())?;
+    client.cast_vote(request).await
+}
+
+/// Propose from the primary address, checked against the node's proposal
+/// threshold before signing.
+pub async fn create_proposal(
+    ctx: &WalletContext,
+    request: CreateProposalRequest,
+) -> WalletResult<CreateProposalResponse> {
+    let proposer = ctx.session().with_unlocked(|metadata, _| {
+        metadata.primary_address.clone().ok_or_else(|| {
+            WalletError::ValidationError("Wallet has no primary address".to_string())
+        })
+    })?;
+    let payload = ProposalPayload::from_request(&proposer, &request)?;
+
+    let client = ctx.rpc_client();
+    let (voting_power, threshold) = tokio::try_join!(
+        client.get_voting_power(&proposer),
+        client.get_proposal_threshold(),
+    )?;
+    // Nodes without the threshold method decide on submission instead.
+    if let Some(threshold) = threshold {
+        ensure_proposal_threshold(voting_power.voting_power.total_power, threshold.threshold)?;
+    }
+
+    let signed = ctx
+        .session()
+        .with_unlocked(|_, secrets| payload.sign(secrets))?;
+    client.create_proposal(&signed).await
+}
+
+/// Vote once per requested vault account, each signed by its own key.
+pub async fn batch_cast_vote(
+    ctx: &WalletContext,
+    request: BatchVoteRequest,
+) -> WalletResult<Vec<BatchVoteResult>> {
+    let client = ctx.rpc_client();
+    let proposal = client.get_proposal(request.proposal_id).await?.proposal;
+    voting::ensure_voting_open(&proposal, chrono::Utc::now())?;
+
+    let signed = ctx.session().with_unlocked(|metadata, secrets| {
+        Ok(
+            batch::resolve_signers(metadata, secrets, &request.accounts)?
+                .into_iter()
+                .map(|(account, key)| {
+                    let vote = key.and_then(|key| {
+                        AccountVote {
+                            voter: account.clone(),
+                            proposal_id: request.proposal_id,
+                            support: request.support,
+                            reason: request.reason.clone(),
+                        }
+                        .sign(&key)
+                    });
+                    (account, vote)
+                })
+                .collect::<Vec<_>>(),
+        )
+    })?;
+
+    // One account failing does not stop the others.
+    let mut results = Vec::with_capacity(signed.len());
+    for (account, vote) in signed {
+        let outcome = match vote {
+            Ok(vote) => client.cast_signed_vote(&vote).await,
+            Err(err) => Err(err),
+        };
+        results.push(BatchVoteResult {
+            account,
+            error: outcome.as_ref().err().map(|e| e.to_string()),
+            vote: outcome.ok().map(|response| response.vote),
+        });
+    }
+    Ok(results)
+}
@@ -0,0 +1,9 @@
+//! Command bodies shared by the Tauri commands, the CLI and the integration
+//! tests. Each works on a `WalletContext`: the Tauri wrappers pass the locked
+//! context to synchronous bodies and a snapshot to async ones, so no lock is
+//! held across an await.
+
+pub mod governance;
+pub mod staking;
+pub mod transfer;
+pub mod wallet;
@@ -0,0 +1,112 @@
+//! Staking writes behind the staking commands.
+//!
+//! Delegations arrive from the frontend as loose JSON objects, so those
+//! bodies pull their own parameters out before calling the node.
+
+use serde_json::Value;
+
+use crate::api::types::{
+    DelegateTokensResponse, ExecuteMaturityActionRequest, ExecuteMaturityActionResponse,
+    RedelegateTokensRequest, UndelegateTokensResponse,
+};
+use crate::app_state::WalletContext;
+use crate::errors::{WalletError, WalletResult};
+use crate::staking::maturity;
+use crate::staking::redelegation::{self, RedelegationPlan};
+use crate::validation::InputValidator;
+
+fn str_param<'a>(request: &'a Value, name: &str) -> WalletResult<&'a str> {
+    request
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| WalletError::ValidationError(format!("Missing {} parameter", name)))
+}
+
+fn u64_param(request: &Value, name: &str) -> WalletResult<u64> {
+    request.get(name).and_then(Value::as_u64).ok_or_else(|| {
+        WalletError::ValidationError(format!("Missing or invalid {} parameter", name))
+    })
+}
+
+pub async fn delegate_tokens(
+    ctx: &WalletContext,
+    request: &Value,
+) -> WalletResult<DelegateTokensResponse> {
+    let delegator_address = str_param(request, "delegatorAddress")?;
+    let validator_address = str_param(request, "validatorAddress")?;
+    let amount = u64_param(request, "amount")?;
+
+    ctx.rpc_client()
+        .delegate_tokens(delegator_address, validator_address, amount)
+        .await
+}
+
+pub async fn undelegate_tokens(
+    ctx: &WalletContext,
+    request: &Value,
+) -> WalletResult<UndelegateTokensResponse> {
+    let delegator_address = str_param(request, "delegatorAddress")?;
+    let validator_address = str_param(request, "validatorAddress")?;
+    let amount = u64_param(request, "amount")?;
+
+    ctx.rpc_client()
+        .undelegate_tokens(delegator_address, validator_address, amount)
+        .await
+}
+
+/// Record a redelegation plan and take it as far as the node allows now;
+/// plans left waiting for unbonding are resumed in the background.
+pub async fn redelegate_tokens(
+    ctx: &WalletContext,
+    request: RedelegateTokensRequest,
+) -> WalletResult<RedelegationPlan> {
+    let validator = InputValidator::default();
+    for address in [
+        &request.delegator_address,
+        &request.from_validator,
+        &request.to_validator,
+    ] {
+        validator.validate_address(address)?;
+    }
+    if request
+        .from_validator
+        .eq_ignore_ascii_case(&request.to_validator)
+    {
+        return Err(WalletError::ValidationError(
+            "Source and destination validators must differ".to_string(),
+        ));
+    }
+    if request.amount == 0 {
+        return Err(WalletError::InvalidAmount(
+            "Redelegation amount must be greater than zero".to_string(),
+        ));
+    }
+
+    let store = ctx.redelegations();
+    let mut plan = RedelegationPlan::new(
+        &request.delegator_address,
+        &request.from_validator,
+        &request.to_validator,
+        request.amount,
+    );
+    store.save(&plan)?;
+    redelegation::advance(ctx.rpc_client(), store, &mut plan).await?;
+    Ok(plan)
+}
+
+/// Run the chosen action on a tracked maturity and stop tracking it.
+pub async fn execute_maturity_action(
+    ctx: &WalletContext,
+    request: ExecuteMaturityActionRequest,
+) -> WalletResult<ExecuteMaturityActionResponse> {
+    let tracker = ctx.maturity_tracker();
+    let entry = tracker.find(&request.entry_id)?;
+    let transaction_id = maturity::execute_action(ctx.rpc_client(), &entry, request.action).await?;
+    tracker.remove(&entry.id)?;
+
+    Ok(ExecuteMaturityActionResponse {
+        entry_id: entry.id,
+        action: request.action,
+        transaction_id,
+    })
+}
@@ -0,0 +1,49 @@
+//! Transfers signed by the wallet's primary key.
+
+use crate::api::types::TransferReceipt;
+use crate::app_state::WalletContext;
+use crate::blockchain::PrivateKey;
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+use crate::network;
+use crate::validation::InputValidator;
+
+/// Send `amount` from the primary address to `to` through `client`. Without
+/// an explicit `fee` the active network profile's default applies, and its
+/// explorer links the receipt.
+pub async fn send_transfer(
+    ctx: &WalletContext,
+    client: &BlockchainClient,
+    to: &str,
+    amount: u64,
+    fee: Option<u64>,
+) -> WalletResult<TransferReceipt> {
+    InputValidator::default().validate_address(to)?;
+    if amount == 0 {
+        return Err(WalletError::InvalidAmount(
+            "Amount must be greater than zero".to_string(),
+        ));
+    }
+    let profile = network::active_profile(&ctx.load_config()?);
+    let fee = match (fee, &profile) {
+        (Some(fee), _) => fee,
+        (None, Some(profile)) => profile.default_fee,
+        (None, None) => {
+            return Err(WalletError::ValidationError(
+                "No network profile is selected; pass a fee".to_string(),
+            ))
+        }
+    };
+
+    let (from, key) = ctx.session().with_unlocked(|metadata, secrets| {
+        let from = metadata.primary_address.clone().ok_or_else(|| {
+            WalletError::ValidationError("Wallet has no primary address".to_string())
+        })?;
+        Ok((from, PrivateKey::from_bytes(secrets.seed_bytes.clone())?))
+    })?;
+    let mut receipt = client
+        .submit_transfer(&key, &from, to, amount, fee, None)
+        .await?;
+    receipt.explorer_url = profile.map(|profile| profile.explorer_link(&receipt.tx_id));
+    Ok(receipt)
+}
@@ -0,0 +1,130 @@
+//! Vault writes behind the wallet commands.
+
+use secrecy::SecretString;
+
+use crate::api::types::{
+    AddAccountRequest, AddAccountResponse, CreateWalletRequest, CreateWalletResponse,
+    ImportWalletRequest, ImportWalletResponse, WalletSummary,
+};
+use crate::app_state::WalletContext;
+use crate::crypto::WalletKeyPair;
+use crate::errors::{WalletError, WalletResult};
+use crate::storage::AccountKey;
+use crate::validation::InputValidator;
+
+fn validate_credentials(wallet_name: &str, password: &str) -> WalletResult<()> {
+    let validator = InputValidator::default();
+    validator.validate_wallet_name(wallet_name)?;
+    validator.validate_password(password)
+}
+
+fn ensure_no_vault(ctx: &WalletContext) -> WalletResult<()> {
+    if ctx.vault().exists() {
+        return Err(WalletError::AlreadyExists(
+            ctx.vault().vault_path().display().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a vault around a freshly generated mnemonic and unlock it.
+pub fn create_wallet(
+    ctx: &WalletContext,
+    request: CreateWalletRequest,
+) -> WalletResult<CreateWalletResponse> {
+    let CreateWalletRequest {
+        wallet_name,
+        password,
+        mnemonic_word_count,
+        use_post_quantum,
+    } = request;
+    validate_credentials(&wallet_name, &password)?;
+    ensure_no_vault(ctx)?;
+
+    let (keypair, mnemonic) =
+        WalletKeyPair::generate_with_mnemonic(mnemonic_word_count, None, None, use_post_quantum)?;
+    let metadata = ctx.provision_vault(
+        &SecretString::from(password),
+        &wallet_name,
+        &keypair,
+        &mnemonic,
+    )?;
+    Ok(CreateWalletResponse {
+        summary: WalletSummary::from(metadata),
+        address: keypair.address(),
+        public_key: keypair.public_key_hex(),
+        mnemonic,
+        supports_post_quantum: keypair.supports_pq,
+        algorithm: format!("{:?}", keypair.core_keypair.algorithm),
+    })
+}
+
+/// Create a vault from an existing mnemonic and unlock it.
+pub fn import_wallet(
+    ctx: &WalletContext,
+    request: ImportWalletRequest,
+) -> WalletResult<ImportWalletResponse> {
+    let ImportWalletRequest {
+        wallet_name,
+        password,
+        mnemonic,
+        use_post_quantum,
+    } = request;
+    validate_credentials(&wallet_name, &password)?;
+    ensure_no_vault(ctx)?;
+
+    let keypair = WalletKeyPair::from_mnemonic(&mnemonic, None, None, use_post_quantum)?;
+    let metadata = ctx.provision_vault(
+        &SecretString::from(password),
+        &wallet_name,
+        &keypair,
+        &mnemonic,
+    )?;
+    Ok(ImportWalletResponse {
+        summary: WalletSummary::from(metadata),
+        address: keypair.address(),
+        public_key: keypair.public_key_hex(),
+        supports_post_quantum: keypair.supports_pq,
+        algorithm: format!("{:?}", keypair.core_keypair.algorithm),
+    })
+}
+
+/// Add an account restored from its own mnemonic to the vault, so batch
+/// votes and delegations can sign for it. Wrong passwords count towards the
+/// lockout.
+pub fn add_wallet_account(
+    ctx: &WalletContext,
+    request: AddAccountRequest,
+) -> WalletResult<AddAccountResponse> {
+    let AddAccountRequest { password, mnemonic } = request;
+    let keypair = WalletKeyPair::from_mnemonic(&mnemonic, None, None, false)?;
+    let account = AccountKey {
+        address: keypair.address(),
+        seed_bytes: keypair.core_keypair.private_key.clone(),
+    };
+
+    if !ctx.vault().exists() {
+        return Err(WalletError::NotFound(
+            "Wallet vault has not been initialized".to_string(),
+        ));
+    }
+    let password_secret = SecretString::from(password);
+    let metadata = match ctx.vault().add_account_key(&password_secret, account) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            if matches!(
+                err,
+                WalletError::CryptoError(_) | WalletError::ValidationError(_)
+            ) {
+                ctx.session().register_failed_attempt()?;
+            }
+            return Err(err);
+        }
+    };
+    ctx.unlock(&password_secret)?;
+    Ok(AddAccountResponse {
+        summary: WalletSummary::from(metadata),
+        address: keypair.address(),
+        public_key: keypair.public_key_hex(),
+    })
+}
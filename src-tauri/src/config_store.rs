@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -8,6 +9,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::errors::{WalletError, WalletResult};
+use crate::network::NetworkProfile;
 use crate::storage::WalletPaths;
 
 const CONFIG_VERSION: u16 = 1;
@@ -17,6 +19,22 @@ pub struct NetworkConfig {
     pub primary_endpoint: String,
     pub failover_endpoints: Vec<String>,
     pub allow_untrusted_certs: bool,
+    // Omitted while at its default so existing configs keep their checksum.
+    #[serde(default, skip_serializing_if = "QuorumConfig::is_default")]
+    pub quorum: QuorumConfig,
+    #[serde(default, skip_serializing_if = "LightClientConfig::is_default")]
+    pub light_client: LightClientConfig,
+    /// Name of the selected network profile, if endpoints came from one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Chain id every endpoint must report before signed requests are sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    /// Certificate trust overrides, keyed by endpoint URL.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub endpoint_tls: BTreeMap<String, EndpointTls>,
+    #[serde(default, skip_serializing_if = "ProxyConfig::is_default")]
+    pub proxy: ProxyConfig,
 }
 
 impl Default for NetworkConfig {
@@ -25,10 +43,108 @@ impl Default for NetworkConfig {
             primary_endpoint: "https://mainnet.chert.network".to_string(),
             failover_endpoints: vec!["https://rpc-backup.chert.network".to_string()],
             allow_untrusted_certs: false,
+            quorum: QuorumConfig::default(),
+            light_client: LightClientConfig::default(),
+            profile: None,
+            chain_id: None,
+            endpoint_tls: BTreeMap::new(),
+            proxy: ProxyConfig::default(),
         }
     }
 }
 
+/// How an endpoint's certificate is trusted instead of the web PKI roots.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointTls {
+    /// Base64 SHA-256 hashes of accepted SubjectPublicKeyInfo, optionally
+    /// prefixed with `sha256/`.
+    #[serde(default)]
+    pub pinned_spki_sha256: Vec<String>,
+    /// PEM certificates that replace the web PKI roots for this endpoint.
+    #[serde(default)]
+    pub ca_bundle_pem: Option<String>,
+}
+
+/// SOCKS5 routing of RPC and WebSocket connections.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    /// `socks5h://host:port` (names resolved by the proxy) or `socks5://`
+    /// proxy used for endpoints without an override.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Refuse connections that would bypass a proxy or resolve names locally.
+    #[serde(default)]
+    pub strict: bool,
+    /// Routes replacing `url`, keyed by endpoint URL.
+    #[serde(default)]
+    pub endpoints: BTreeMap<String, ProxyRoute>,
+}
+
+impl ProxyConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ProxyRoute {
+    Direct,
+    Proxy { url: String },
+}
+
+/// Cross-checking of critical reads (balances, nonces, transaction status,
+/// voting power, proposal state) across the configured endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumConfig {
+    pub enabled: bool,
+    /// Endpoints that must answer, all identically, before a read is trusted.
+    pub min_responses: usize,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_responses: 2,
+        }
+    }
+}
+
+impl QuorumConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Verification of balances and nonces against account state proofs and a
+/// header chain signed by a pinned validator set. Experimental until the
+/// proof format is confirmed against the protocol spec.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LightClientConfig {
+    pub enabled: bool,
+    /// Validators whose signatures make a header trusted.
+    pub validators: Vec<TrustedValidator>,
+}
+
+impl LightClientConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedValidator {
+    /// Hex-encoded Ed25519 public key.
+    pub public_key: String,
+    pub voting_power: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SessionConfig {
     pub auto_lock_minutes: u32,
@@ -50,11 +166,136 @@ pub struct TelemetryConfig {
     pub allow_error_reports: bool,
 }
 
+/// Wallet-side reward compounding for a single account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompoundingPolicy {
+    pub enabled: bool,
+    /// Claim once pending rewards reach this amount.
+    pub min_pending_rewards: Option<u64>,
+    /// Claim at least this often, regardless of the pending amount.
+    pub interval_hours: Option<u32>,
+    /// Validator to restake into; defaults to the account's largest delegation.
+    pub target_validator: Option<String>,
+    /// Base units kept liquid for fees: the account must hold this much
+    /// before claiming, and it is held back from each restake.
+    pub fee_reserve: u64,
+    /// Skip claims whose rewards exceed the fee reserve by less than this, so
+    /// fees never eat the reward.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_net_gain: Option<u64>,
+}
+
+impl CompoundingPolicy {
+    pub fn validate(&self) -> WalletResult<()> {
+        if self.min_pending_rewards.is_none() && self.interval_hours.is_none() {
+            return Err(WalletError::ValidationError(
+                "Compounding policy needs a reward threshold or a schedule".to_string(),
+            ));
+        }
+        if self.min_pending_rewards == Some(0) || self.interval_hours == Some(0) {
+            return Err(WalletError::ValidationError(
+                "Compounding threshold and interval must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct StakingConfig {
+    /// Compounding policies keyed by lowercase account address.
+    pub compounding: BTreeMap<String, CompoundingPolicy>,
+}
+
+impl StakingConfig {
+    pub fn is_empty(&self) -> bool {
+        self.compounding.is_empty()
+    }
+}
+
+/// Role of a contract that governance proposals may target.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ContractKind {
+    Staking,
+    Treasury,
+    Governance,
+    Proxy,
+    Other,
+}
+
+/// Human-readable label for a contract address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KnownContract {
+    pub name: String,
+    pub kind: ContractKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct GovernanceConfig {
+    /// Contract labels keyed by lowercase address.
+    pub known_contracts: BTreeMap<String, KnownContract>,
+}
+
+impl GovernanceConfig {
+    pub fn is_empty(&self) -> bool {
+        self.known_contracts.is_empty()
+    }
+}
+
+/// Loopback port the dApp connector listens on unless configured otherwise.
+pub const DEFAULT_CONNECTOR_PORT: u16 = 18_547;
+
+/// A site allowed to request signatures and transactions from the wallet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OriginGrant {
+    /// Accounts the site may see and ask to sign for.
+    pub accounts: Vec<String>,
+    /// Hex SHA-256 of the bearer token issued when the grant was approved.
+    pub token_hash: String,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// Local dApp connector settings. The connector stays off until enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConnectorConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Grants keyed by normalized origin, e.g. `https://app.example`.
+    #[serde(default)]
+    pub grants: BTreeMap<String, OriginGrant>,
+}
+
+impl Default for ConnectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_CONNECTOR_PORT,
+            grants: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConnectorConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct WalletConfig {
     pub network: NetworkConfig,
     pub session: SessionConfig,
     pub telemetry: TelemetryConfig,
+    // Omitted when empty so configs written before this section keep their checksum.
+    #[serde(default, skip_serializing_if = "StakingConfig::is_empty")]
+    pub staking: StakingConfig,
+    #[serde(default, skip_serializing_if = "GovernanceConfig::is_empty")]
+    pub governance: GovernanceConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_networks: Vec<NetworkProfile>,
+    #[serde(default, skip_serializing_if = "ConnectorConfig::is_empty")]
+    pub connector: ConnectorConfig,
     pub environment: String,
     pub last_updated: DateTime<Utc>,
     pub version: u16,
@@ -66,6 +307,10 @@ impl WalletConfig {
             network: NetworkConfig::default(),
             session: SessionConfig::default(),
             telemetry: TelemetryConfig::default(),
+            staking: StakingConfig::default(),
+            governance: GovernanceConfig::default(),
+            custom_networks: Vec::new(),
+            connector: ConnectorConfig::default(),
             environment: environment.into(),
             last_updated: Utc::now(),
             version: CONFIG_VERSION,
@@ -222,4 +467,31 @@ mod tests {
         let result = store.load_or_default("test");
         assert!(matches!(result, Err(WalletError::ValidationError(_))));
     }
+
+    #[test]
+    fn compounding_policies_persist() {
+        let temp = TempDir::new().unwrap();
+        let store = ConfigStore::new(temp.path().join("wallet.config"));
+        let policy = CompoundingPolicy {
+            enabled: true,
+            min_pending_rewards: Some(1_000),
+            interval_hours: None,
+            target_validator: None,
+            fee_reserve: 10,
+            min_net_gain: None,
+        };
+
+        store
+            .update("test", |config| {
+                config
+                    .staking
+                    .compounding
+                    .insert("0xabc".to_string(), policy.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        let loaded = store.load_or_default("test").unwrap();
+        assert_eq!(loaded.staking.compounding.get("0xabc"), Some(&policy));
+    }
 }
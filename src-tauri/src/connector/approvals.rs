@@ -0,0 +1,225 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
+
+use crate::connector::protocol::{ConnectorRequest, ProviderError, LIMIT_EXCEEDED, USER_REJECTED};
+use crate::errors::{WalletError, WalletResult};
+
+/// Tauri event carrying each [`PendingApproval`] the user has to answer.
+pub const CONNECTOR_EVENT: &str = "connector://request";
+/// How long a request waits for the user before it is turned down.
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+const MAX_PENDING: usize = 16;
+/// Keeps one site from burying the prompt under its own requests.
+const MAX_PENDING_PER_ORIGIN: usize = 2;
+
+/// A dApp request waiting for the user's decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApproval {
+    pub id: String,
+    pub origin: String,
+    pub request: ConnectorRequest,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectorStatus {
+    /// Address the connector is listening on, if it is running.
+    pub listening: Option<String>,
+    pub last_error: Option<String>,
+    pub pending: Vec<PendingApproval>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectorState {
+    pending: Vec<(PendingApproval, oneshot::Sender<bool>)>,
+    listening: Option<String>,
+    last_error: Option<String>,
+}
+
+/// Requests awaiting approval and the listener's status. Shared between
+/// commands and the background server.
+#[derive(Debug, Clone, Default)]
+pub struct DappConnector {
+    state: Arc<Mutex<ConnectorState>>,
+    changed: Arc<Notify>,
+}
+
+impl DappConnector {
+    pub fn status(&self) -> ConnectorStatus {
+        let state = self.state.lock();
+        ConnectorStatus {
+            listening: state.listening.clone(),
+            last_error: state.last_error.clone(),
+            pending: state
+                .pending
+                .iter()
+                .map(|(approval, _)| approval.clone())
+                .collect(),
+        }
+    }
+
+    /// Answer a pending request from the approval prompt.
+    pub fn resolve(&self, id: &str, approve: bool) -> WalletResult<()> {
+        let (_, reply) = self
+            .take(id)
+            .ok_or_else(|| WalletError::NotFound(format!("No pending connector request {}", id)))?;
+        // The site may have disconnected meanwhile; nothing is left to answer.
+        let _ = reply.send(approve);
+        Ok(())
+    }
+
+    /// Apply changed connector settings, starting or stopping the listener.
+    pub fn reload(&self) {
+        self.changed.notify_one();
+    }
+
+    pub(crate) async fn reloaded(&self) {
+        self.changed.notified().await
+    }
+
+    pub(crate) fn set_listening(&self, listening: Option<String>, error: Option<String>) {
+        let mut state = self.state.lock();
+        state.listening = listening;
+        if error.is_some() {
+            state.last_error = error;
+        }
+    }
+
+    /// Queue `request` for the user and wait for their answer. `emit` shows
+    /// the prompt. Rejections, timeouts and full queues are errors for the site.
+    pub(crate) async fn approve(
+        &self,
+        origin: &str,
+        request: ConnectorRequest,
+        emit: &(dyn Fn(&PendingApproval) + Sync),
+    ) -> Result<(), ProviderError> {
+        let approval = PendingApproval {
+            id: uuid::Uuid::new_v4().to_string(),
+            origin: origin.to_string(),
+            request,
+            received_at: Utc::now(),
+        };
+        let (reply, answer) = oneshot::channel();
+        {
+            let mut state = self.state.lock();
+            let from_origin = state
+                .pending
+                .iter()
+                .filter(|(pending, _)| pending.origin == origin)
+                .count();
+            if state.pending.len() >= MAX_PENDING || from_origin >= MAX_PENDING_PER_ORIGIN {
+                return Err(ProviderError::new(
+                    LIMIT_EXCEEDED,
+                    "Too many requests are waiting for approval",
+                ));
+            }
+            state.pending.push((approval.clone(), reply));
+        }
+        // Withdraw the prompt if the site goes away or the wait times out.
+        let _guard = PendingGuard {
+            connector: self,
+            id: &approval.id,
+        };
+        emit(&approval);
+
+        match tokio::time::timeout(APPROVAL_TIMEOUT, answer).await {
+            Ok(Ok(true)) => Ok(()),
+            Ok(_) => Err(ProviderError::rejected()),
+            Err(_) => Err(ProviderError::new(
+                USER_REJECTED,
+                "Request was not approved in time",
+            )),
+        }
+    }
+
+    fn take(&self, id: &str) -> Option<(PendingApproval, oneshot::Sender<bool>)> {
+        let mut state = self.state.lock();
+        let index = state
+            .pending
+            .iter()
+            .position(|(approval, _)| approval.id == id)?;
+        Some(state.pending.remove(index))
+    }
+}
+
+struct PendingGuard<'a> {
+    connector: &'a DappConnector,
+    id: &'a str,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.connector.take(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next_id(connector: &DappConnector) -> String {
+        connector.status().pending[0].id.clone()
+    }
+
+    #[tokio::test]
+    async fn approval_waits_for_the_users_answer() {
+        let connector = DappConnector::default();
+        let waiting = {
+            let connector = connector.clone();
+            tokio::spawn(async move {
+                connector
+                    .approve(
+                        "https://app.example",
+                        ConnectorRequest::RequestAccounts,
+                        &|_| {},
+                    )
+                    .await
+            })
+        };
+        while connector.status().pending.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        connector.resolve(&next_id(&connector), true).unwrap();
+        assert!(waiting.await.unwrap().is_ok());
+        assert!(connector.status().pending.is_empty());
+        assert!(connector.resolve("unknown", true).is_err());
+    }
+
+    #[tokio::test]
+    async fn rejected_and_abandoned_requests_leave_the_queue() {
+        let connector = DappConnector::default();
+        let origin = "https://app.example";
+        let mut first =
+            Box::pin(connector.approve(origin, ConnectorRequest::RequestAccounts, &|_| {}));
+        let mut second =
+            Box::pin(connector.approve(origin, ConnectorRequest::RequestAccounts, &|_| {}));
+        let third = connector.approve(origin, ConnectorRequest::RequestAccounts, &|_| {});
+
+        // Poll the first two into the queue; the third exceeds the per-origin cap.
+        assert!(poll_once(&mut first).is_none());
+        assert!(poll_once(&mut second).is_none());
+        assert_eq!(third.await.unwrap_err().code, LIMIT_EXCEEDED);
+
+        connector.resolve(&next_id(&connector), false).unwrap();
+        assert_eq!(first.await.unwrap_err().code, USER_REJECTED);
+        assert_eq!(connector.status().pending.len(), 1);
+        drop(second);
+        assert!(connector.status().pending.is_empty());
+    }
+
+    fn poll_once<F: std::future::Future + Unpin>(future: &mut F) -> Option<F::Output> {
+        use std::task::{Context, Poll};
+        let waker = std::task::Waker::noop();
+        match std::pin::Pin::new(future).poll(&mut Context::from_waker(waker)) {
+            Poll::Ready(output) => Some(output),
+            Poll::Pending => None,
+        }
+    }
+}
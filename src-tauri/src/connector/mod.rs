@@ -0,0 +1,12 @@
+/// Local dApp connector
+///
+/// An opt-in JSON-RPC endpoint on the loopback interface that lets web dApps
+/// ask for the wallet's accounts, typed-data signatures and transfers, in the
+/// spirit of EIP-1193. Sites connect once per origin and receive a bearer
+/// token for later calls; every call is held until the user approves it in
+/// the app.
+pub mod approvals;
+pub mod protocol;
+pub mod server;
+
+pub use approvals::{ConnectorStatus, DappConnector, CONNECTOR_EVENT};
@@ -0,0 +1,342 @@
+use rand::rngs::OsRng;
+use rand::RngCore;
+use reqwest::Url;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::batch::{domain_bytes, sign_with_domain, AccountSigned};
+use crate::storage::VaultSecrets;
+
+pub const METHOD_REQUEST_ACCOUNTS: &str = "silica_requestAccounts";
+pub const METHOD_SIGN_TYPED_DATA: &str = "silica_signTypedData";
+pub const METHOD_SEND_TRANSACTION: &str = "silica_sendTransaction";
+
+/// EIP-1193 provider error codes.
+pub const USER_REJECTED: i32 = 4001;
+pub const UNAUTHORIZED: i32 = 4100;
+pub const UNSUPPORTED_METHOD: i32 = 4200;
+/// EIP-1474 codes for malformed calls and wallet-side failures.
+pub const INVALID_REQUEST: i32 = -32600;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+pub const LIMIT_EXCEEDED: i32 = -32005;
+
+const TYPED_DATA_DOMAIN: &[u8] = b"silica-typed-data:v1:";
+
+/// Error object returned to the dApp in a JSON-RPC response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ProviderError {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn rejected() -> Self {
+        Self::new(USER_REJECTED, "User rejected the request")
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(UNAUTHORIZED, message)
+    }
+
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+}
+
+impl From<WalletError> for ProviderError {
+    fn from(err: WalletError) -> Self {
+        match err {
+            WalletError::ValidationError(message)
+            | WalletError::InvalidAddress(message)
+            | WalletError::InvalidAmount(message) => Self::invalid_params(message),
+            WalletError::PermissionDenied(message) => Self::unauthorized(message),
+            other => Self::new(INTERNAL_ERROR, other.to_string()),
+        }
+    }
+}
+
+/// What a dApp asks the wallet to do, as shown in the approval prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "method")]
+pub enum ConnectorRequest {
+    /// Connect the site and share the wallet's accounts with it.
+    RequestAccounts,
+    #[serde(rename_all = "camelCase")]
+    SignTypedData {
+        account: String,
+        domain: serde_json::Value,
+        primary_type: String,
+        message: serde_json::Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    SendTransaction {
+        from: String,
+        to: String,
+        amount: u64,
+        fee: u64,
+        /// Hex-encoded payload.
+        data: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TypedDataParams {
+    domain: serde_json::Value,
+    primary_type: String,
+    message: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransactionParams {
+    from: String,
+    to: String,
+    #[serde(deserialize_with = "base_units")]
+    amount: u64,
+    #[serde(deserialize_with = "base_units")]
+    fee: u64,
+    #[serde(default)]
+    data: Option<String>,
+}
+
+impl ConnectorRequest {
+    /// Parse a JSON-RPC call. Params are positional, as in EIP-1193:
+    /// `[account, {domain, primaryType, message}]` for typed data and
+    /// `[{from, to, amount, fee, data?}]` for transactions.
+    pub fn parse(method: &str, params: Option<serde_json::Value>) -> Result<Self, ProviderError> {
+        let params = match params {
+            None | Some(serde_json::Value::Null) => Vec::new(),
+            Some(serde_json::Value::Array(params)) => params,
+            Some(_) => return Err(ProviderError::invalid_params("Params must be an array")),
+        };
+        match method {
+            METHOD_REQUEST_ACCOUNTS => Ok(ConnectorRequest::RequestAccounts),
+            METHOD_SIGN_TYPED_DATA => {
+                let [account, typed] = positional::<2>(params)?;
+                let account: String = from_param(account)?;
+                let typed: TypedDataParams = from_param(typed)?;
+                if !typed.domain.is_object() {
+                    return Err(ProviderError::invalid_params("Domain must be an object"));
+                }
+                if typed.primary_type.trim().is_empty() {
+                    return Err(ProviderError::invalid_params("Primary type is required"));
+                }
+                Ok(ConnectorRequest::SignTypedData {
+                    account,
+                    domain: typed.domain,
+                    primary_type: typed.primary_type,
+                    message: typed.message,
+                })
+            }
+            METHOD_SEND_TRANSACTION => {
+                let [transaction] = positional::<1>(params)?;
+                let transaction: TransactionParams = from_param(transaction)?;
+                if transaction.amount == 0 {
+                    return Err(ProviderError::invalid_params(
+                        "Amount must be greater than zero",
+                    ));
+                }
+                if let Some(data) = &transaction.data {
+                    hex::decode(data.trim_start_matches("0x"))
+                        .map_err(|_| ProviderError::invalid_params("Data must be hex"))?;
+                }
+                Ok(ConnectorRequest::SendTransaction {
+                    from: transaction.from,
+                    to: transaction.to,
+                    amount: transaction.amount,
+                    fee: transaction.fee,
+                    data: transaction.data,
+                })
+            }
+            other => Err(ProviderError::new(
+                UNSUPPORTED_METHOD,
+                format!("Unsupported method {}", other),
+            )),
+        }
+    }
+
+    /// Account the request acts for, if it acts for one.
+    pub fn account(&self) -> Option<&str> {
+        match self {
+            ConnectorRequest::RequestAccounts => None,
+            ConnectorRequest::SignTypedData { account, .. } => Some(account),
+            ConnectorRequest::SendTransaction { from, .. } => Some(from),
+        }
+    }
+}
+
+fn positional<const N: usize>(
+    params: Vec<serde_json::Value>,
+) -> Result<[serde_json::Value; N], ProviderError> {
+    params
+        .try_into()
+        .map_err(|_| ProviderError::invalid_params(format!("Expected {} params", N)))
+}
+
+fn from_param<T: serde::de::DeserializeOwned>(
+    value: serde_json::Value,
+) -> Result<T, ProviderError> {
+    serde_json::from_value(value).map_err(|err| ProviderError::invalid_params(err.to_string()))
+}
+
+/// Amounts as JSON numbers or decimal strings, since JavaScript numbers lose
+/// precision above 2^53.
+fn base_units<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Units {
+        Number(u64),
+        Text(String),
+    }
+    match Units::deserialize(deserializer)? {
+        Units::Number(value) => Ok(value),
+        Units::Text(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| serde::de::Error::custom("amount must be a whole number of base units")),
+    }
+}
+
+/// `scheme://host[:port]` of a browser origin. Remote sites must use HTTPS;
+/// plain HTTP is only accepted from the local machine, for dApp development.
+pub fn normalize_origin(raw: &str) -> WalletResult<String> {
+    let invalid = || WalletError::ValidationError(format!("Invalid origin '{}'", raw));
+    let url = Url::parse(raw.trim()).map_err(|_| invalid())?;
+    let host = url.host_str().ok_or_else(invalid)?;
+    let local = matches!(host, "localhost" | "127.0.0.1" | "[::1]");
+    match url.scheme() {
+        "https" => {}
+        "http" if local => {}
+        _ => return Err(invalid()),
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+/// A fresh bearer token and the hash stored in the origin's grant.
+pub fn issue_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    let hash = token_hash(&token);
+    (token, hash)
+}
+
+pub fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Typed data as signed for a site. The origin and chain are part of the
+/// signed bytes, so a signature given to one site cannot be replayed as if
+/// another site had asked for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataPayload {
+    pub origin: String,
+    pub chain_id: String,
+    pub signer: String,
+    pub domain: serde_json::Value,
+    pub primary_type: String,
+    pub message: serde_json::Value,
+}
+
+/// Typed data with the signature of the wallet's primary key.
+pub type SignedTypedData = AccountSigned<TypedDataPayload>;
+
+impl TypedDataPayload {
+    /// Bytes covered by the signer's signature.
+    pub fn signing_bytes(&self) -> WalletResult<Vec<u8>> {
+        domain_bytes(TYPED_DATA_DOMAIN, self)
+    }
+
+    /// Sign with the vault's primary key, the only account sites are given.
+    pub fn sign(self, secrets: &VaultSecrets) -> WalletResult<SignedTypedData> {
+        sign_with_domain(TYPED_DATA_DOMAIN, self, &secrets.signing_key()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, SigningKey, Verifier};
+
+    #[test]
+    fn origins_are_normalized_and_remote_http_is_refused() {
+        assert_eq!(
+            normalize_origin("https://App.Example:443/path").unwrap(),
+            "https://app.example"
+        );
+        assert_eq!(
+            normalize_origin("http://localhost:5173").unwrap(),
+            "http://localhost:5173"
+        );
+        assert!(normalize_origin("http://app.example").is_err());
+        assert!(normalize_origin("null").is_err());
+    }
+
+    #[test]
+    fn transactions_accept_string_amounts_and_reject_bad_params() {
+        let request = ConnectorRequest::parse(
+            METHOD_SEND_TRANSACTION,
+            Some(serde_json::json!([{
+                "from": "0xaa", "to": "0xbb", "amount": "9007199254740993", "fee": 10
+            }])),
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            ConnectorRequest::SendTransaction {
+                amount: 9_007_199_254_740_993,
+                fee: 10,
+                ..
+            }
+        ));
+        assert_eq!(request.account(), Some("0xaa"));
+
+        let missing_fee = ConnectorRequest::parse(
+            METHOD_SEND_TRANSACTION,
+            Some(serde_json::json!([{ "from": "0xaa", "to": "0xbb", "amount": 1 }])),
+        );
+        assert_eq!(missing_fee.unwrap_err().code, INVALID_PARAMS);
+        let unknown = ConnectorRequest::parse("eth_sign", None);
+        assert_eq!(unknown.unwrap_err().code, UNSUPPORTED_METHOD);
+    }
+
+    #[test]
+    fn typed_data_signature_covers_the_origin() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let payload = TypedDataPayload {
+            origin: "https://app.example".to_string(),
+            chain_id: "silica-1".to_string(),
+            signer: "0xaa".to_string(),
+            domain: serde_json::json!({ "name": "Example", "version": "1" }),
+            primary_type: "Order".to_string(),
+            message: serde_json::json!({ "qty": 3 }),
+        };
+        let signed = payload
+            .clone()
+            .sign(&VaultSecrets::new(key.to_bytes().to_vec()))
+            .unwrap();
+        let signature: [u8; 64] = hex::decode(&signed.signature).unwrap().try_into().unwrap();
+        let signature = Signature::from_bytes(&signature);
+        let verifying = key.verifying_key();
+        assert!(verifying
+            .verify(&payload.signing_bytes().unwrap(), &signature)
+            .is_ok());
+
+        let mut elsewhere = payload;
+        elsewhere.origin = "https://evil.example".to_string();
+        assert!(verifying
+            .verify(&elsewhere.signing_bytes().unwrap(), &signature)
+            .is_err());
+    }
+}
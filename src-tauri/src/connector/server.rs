@@ -0,0 +1,667 @@
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Deserialize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::app_state::SharedWalletContext;
+use crate::blockchain::PrivateKey;
+use crate::config_store::{ConnectorConfig, OriginGrant};
+use crate::connector::approvals::{DappConnector, PendingApproval};
+use crate::connector::protocol::{
+    issue_token, normalize_origin, token_hash, ConnectorRequest, ProviderError, TypedDataPayload,
+    INVALID_REQUEST,
+};
+use crate::errors::{WalletError, WalletResult};
+use crate::network;
+use crate::validation::InputValidator;
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Time a client has to send its request; approvals may take far longer.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Emit = Arc<dyn Fn(&PendingApproval) + Send + Sync>;
+
+/// Serve the connector whenever it is enabled in `wallet`'s config, rebinding
+/// after [`DappConnector::reload`]. `emit` shows each request's approval prompt.
+pub async fn run(
+    wallet: SharedWalletContext,
+    connector: DappConnector,
+    emit: impl Fn(&PendingApproval) + Send + Sync + 'static,
+) {
+    let emit: Emit = Arc::new(emit);
+    loop {
+        let settings = wallet.read(|ctx| Ok(ctx.load_config()?.connector));
+        let listener = match settings {
+            Ok(settings) if settings.enabled => {
+                match TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port)).await {
+                    Ok(listener) => Some((listener, settings.port)),
+                    Err(err) => {
+                        let error = format!("Cannot listen on port {}: {}", settings.port, err);
+                        log::warn!("dApp connector: {}", error);
+                        connector.set_listening(None, Some(error));
+                        None
+                    }
+                }
+            }
+            Ok(_) => {
+                connector.set_listening(None, None);
+                None
+            }
+            Err(err) => {
+                connector.set_listening(None, Some(err.to_string()));
+                None
+            }
+        };
+
+        match listener {
+            Some((listener, port)) => {
+                let address = listener.local_addr().ok().map(|addr| addr.to_string());
+                connector.set_listening(address, None);
+                let service = Service {
+                    wallet: wallet.clone(),
+                    connector: connector.clone(),
+                    emit: emit.clone(),
+                    port,
+                };
+                tokio::select! {
+                    _ = serve(listener, service) => {}
+                    _ = connector.reloaded() => {}
+                }
+            }
+            None => connector.reloaded().await,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Service {
+    wallet: SharedWalletContext,
+    connector: DappConnector,
+    emit: Emit,
+    port: u16,
+}
+
+async fn serve(listener: TcpListener, service: Service) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream, service.clone()));
+            }
+            Err(err) => log::warn!("dApp connector accept failed: {}", err),
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, service: Service) {
+    let mut stream = BufReader::new(stream);
+    let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        // Answering may wait on the user. A site that hangs up meanwhile
+        // drops the wait, which withdraws its prompt.
+        Ok(Ok(request)) => tokio::select! {
+            response = service.respond(request) => response,
+            _ = closed(&mut stream) => {
+                log::debug!("dApp connector client left before its answer");
+                return;
+            }
+        },
+        Ok(Err(err)) => HttpResponse::plain(400, &err.to_string()),
+        Err(_) => HttpResponse::plain(408, "Request timed out"),
+    };
+    let stream = stream.get_mut();
+    if let Err(err) = stream.write_all(response.encode().as_bytes()).await {
+        log::debug!("dApp connector response not delivered: {}", err);
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// Resolves once the client closes the connection. Anything it sends after
+/// its request is discarded.
+async fn closed(stream: &mut (impl AsyncRead + Unpin)) {
+    let mut scratch = [0u8; 512];
+    while matches!(stream.read(&mut scratch).await, Ok(read) if read > 0) {}
+}
+
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    /// Header names are lowercase.
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+async fn read_request(reader: &mut (impl AsyncBufRead + Unpin)) -> WalletResult<HttpRequest> {
+    let invalid = |message: &str| WalletError::ValidationError(message.to_string());
+    let mut lines = Vec::new();
+    let mut header_bytes = 0;
+    loop {
+        let mut line = Vec::new();
+        let read = reader
+            .take((MAX_HEADER_BYTES - header_bytes) as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .await?;
+        header_bytes += read;
+        if read == 0 || header_bytes > MAX_HEADER_BYTES {
+            return Err(invalid("Incomplete or oversized request head"));
+        }
+        let line = String::from_utf8(line).map_err(|_| invalid("Request head is not UTF-8"))?;
+        let line = line.trim_end_matches(['\r', '\n']).to_string();
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut request_line = lines
+        .first()
+        .map(|line| line.split(' '))
+        .into_iter()
+        .flatten();
+    let (method, path) = match (request_line.next(), request_line.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(invalid("Malformed request line")),
+    };
+    let headers: Vec<(String, String)> = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .map(|(_, value)| value.parse::<usize>())
+        .transpose()
+        .map_err(|_| invalid("Invalid Content-Length"))?
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(invalid("Request body too large"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+#[derive(Debug)]
+struct HttpResponse {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+    /// Origin allowed to read the response, echoed in the CORS headers.
+    cors_origin: Option<String>,
+    private_network: bool,
+}
+
+impl HttpResponse {
+    fn plain(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.to_string(),
+            cors_origin: None,
+            private_network: false,
+        }
+    }
+
+    fn encode(&self) -> String {
+        let reason = match self.status {
+            200 => "OK",
+            204 => "No Content",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            415 => "Unsupported Media Type",
+            _ => "Error",
+        };
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        );
+        if let Some(origin) = &self.cors_origin {
+            head.push_str(&format!(
+                "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: content-type, authorization\r\nAccess-Control-Max-Age: 600\r\n",
+                origin
+            ));
+        }
+        if self.private_network {
+            head.push_str("Access-Control-Allow-Private-Network: true\r\n");
+        }
+        format!("{}\r\n{}", head, self.body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcCall {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+    #[serde(default)]
+    params: Option<serde_json::Value>,
+}
+
+impl Service {
+    async fn respond(&self, request: HttpRequest) -> HttpResponse {
+        // A page that rebinds its own hostname to 127.0.0.1 still sends its
+        // own Host header, so only requests addressed to loopback are served.
+        let host_ok = request.header("host").is_some_and(|host| {
+            host == format!("127.0.0.1:{}", self.port) || host == format!("localhost:{}", self.port)
+        });
+        if !host_ok {
+            return HttpResponse::plain(403, "Unexpected Host header");
+        }
+        let origin = match request.header("origin").map(normalize_origin) {
+            Some(Ok(origin)) => origin,
+            _ => return HttpResponse::plain(403, "A secure browser Origin is required"),
+        };
+
+        let mut response = match (request.method.as_str(), request.path.as_str()) {
+            ("OPTIONS", _) => HttpResponse::plain(204, ""),
+            ("POST", "/") => {
+                let is_json = request
+                    .header("content-type")
+                    .is_some_and(|value| value.starts_with("application/json"));
+                if is_json {
+                    self.rpc(&origin, &request).await
+                } else {
+                    HttpResponse::plain(415, "Expected application/json")
+                }
+            }
+            (_, "/") => HttpResponse::plain(405, "Use POST"),
+            _ => HttpResponse::plain(404, "Not found"),
+        };
+        response.cors_origin = Some(origin);
+        response.private_network = request
+            .header("access-control-request-private-network")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+        response
+    }
+
+    async fn rpc(&self, origin: &str, request: &HttpRequest) -> HttpResponse {
+        let bearer = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let (id, outcome) = match serde_json::from_slice::<RpcCall>(&request.body) {
+            Ok(call) => (call.id.clone(), self.dispatch(origin, bearer, call).await),
+            Err(err) => (
+                serde_json::Value::Null,
+                Err(ProviderError::new(INVALID_REQUEST, err.to_string())),
+            ),
+        };
+        let body = match outcome {
+            Ok(result) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        HttpResponse {
+            content_type: "application/json",
+            ..HttpResponse::plain(200, &body.to_string())
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        origin: &str,
+        bearer: Option<&str>,
+        call: RpcCall,
+    ) -> Result<serde_json::Value, ProviderError> {
+        let request = ConnectorRequest::parse(&call.method, call.params)?;
+        if request == ConnectorRequest::RequestAccounts {
+            return self.connect(origin).await;
+        }
+
+        let settings = self.settings()?;
+        let grant = authorize(&settings, origin, bearer)?;
+        let account = request.account().unwrap_or_default();
+        if !grant
+            .accounts
+            .iter()
+            .any(|shared| shared.eq_ignore_ascii_case(account))
+        {
+            return Err(ProviderError::unauthorized(
+                "Account is not shared with this site",
+            ));
+        }
+        match &request {
+            ConnectorRequest::SendTransaction { to, .. } => {
+                InputValidator::default().validate_address(to)?;
+            }
+            ConnectorRequest::SignTypedData { .. } => {
+                self.pinned_chain()?;
+            }
+            ConnectorRequest::RequestAccounts => {}
+        }
+
+        self.connector
+            .approve(origin, request.clone(), &*self.emit)
+            .await?;
+        match request {
+            ConnectorRequest::SignTypedData {
+                account,
+                domain,
+                primary_type,
+                message,
+            } => {
+                let payload = TypedDataPayload {
+                    origin: origin.to_string(),
+                    chain_id: self.pinned_chain()?,
+                    signer: account,
+                    domain,
+                    primary_type,
+                    message,
+                };
+                let signed = self.wallet.read(|ctx| {
+                    ctx.session()
+                        .with_unlocked(|_, secrets| payload.sign(secrets))
+                })?;
+                Ok(serde_json::to_value(signed).map_err(WalletError::from)?)
+            }
+            ConnectorRequest::SendTransaction {
+                from,
+                to,
+                amount,
+                fee,
+                data,
+            } => {
+                let data = data
+                    .map(|data| hex::decode(data.trim_start_matches("0x")))
+                    .transpose()
+                    .map_err(|_| WalletError::ValidationError("Data must be hex".to_string()))?;
+                let (client, key) = self.wallet.read(|ctx| {
+                    let key = ctx.session().with_unlocked(|_, secrets| {
+                        PrivateKey::from_bytes(secrets.seed_bytes.clone())
+                    })?;
+                    Ok((ctx.rpc_client().clone(), key))
+                })?;
+                let receipt = client
+                    .submit_transfer(&key, &from, &to, amount, fee, data)
+                    .await?;
+                Ok(serde_json::to_value(receipt).map_err(WalletError::from)?)
+            }
+            ConnectorRequest::RequestAccounts => unreachable!("handled above"),
+        }
+    }
+
+    /// Ask the user to connect `origin`, then grant it the wallet's primary
+    /// account and issue a fresh token, replacing any earlier grant.
+    async fn connect(&self, origin: &str) -> Result<serde_json::Value, ProviderError> {
+        let primary = self
+            .wallet
+            .read(|ctx| ctx.vault().read_metadata())?
+            .and_then(|metadata| metadata.primary_address)
+            .ok_or_else(|| ProviderError::unauthorized("No wallet has been set up"))?;
+        self.connector
+            .approve(origin, ConnectorRequest::RequestAccounts, &*self.emit)
+            .await?;
+
+        let (token, token_hash) = issue_token();
+        let grant = OriginGrant {
+            accounts: vec![primary.clone()],
+            token_hash,
+            granted_at: Utc::now(),
+        };
+        self.wallet.read(|ctx| {
+            ctx.config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    config.connector.grants.insert(origin.to_string(), grant);
+                    Ok(())
+                })
+                .map(|_| ())
+        })?;
+        Ok(serde_json::json!({ "accounts": [primary], "token": token }))
+    }
+
+    /// The chain typed data is signed for. Without a pin a signature would not
+    /// be bound to any chain, so signing is refused.
+    fn pinned_chain(&self) -> WalletResult<String> {
+        self.wallet
+            .read(|ctx| Ok(network::pinned_chain(&ctx.load_config()?)))?
+            .ok_or_else(|| {
+                WalletError::ChainMismatch(
+                    "No chain is pinned; select a network before signing typed data".to_string(),
+                )
+            })
+    }
+
+    fn settings(&self) -> WalletResult<ConnectorConfig> {
+        self.wallet.read(|ctx| Ok(ctx.load_config()?.connector))
+    }
+}
+
+/// The grant of `origin`, if `bearer` is the token issued with it.
+fn authorize<'a>(
+    settings: &'a ConnectorConfig,
+    origin: &str,
+    bearer: Option<&str>,
+) -> Result<&'a OriginGrant, ProviderError> {
+    let grant = settings.grants.get(origin).ok_or_else(|| {
+        ProviderError::unauthorized("Site is not connected; request accounts first")
+    })?;
+    match bearer {
+        Some(token) if token_hash(token) == grant.token_hash => Ok(grant),
+        _ => Err(ProviderError::unauthorized(
+            "Missing or invalid connector token",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::protocol::UNAUTHORIZED;
+    use crate::storage::{VaultMetadata, VaultSecrets};
+    use crate::WalletContext;
+
+    fn service(dir: &tempfile::TempDir) -> Service {
+        let wallet =
+            SharedWalletContext::new(WalletContext::initialize(dir.path().into()).unwrap());
+        Service {
+            wallet,
+            connector: DappConnector::default(),
+            emit: Arc::new(|_| {}),
+            port: 18_547,
+        }
+    }
+
+    fn post(host: &str, origin: &str, body: &str) -> HttpRequest {
+        HttpRequest {
+            method: "POST".to_string(),
+            path: "/".to_string(),
+            headers: vec![
+                ("host".to_string(), host.to_string()),
+                ("origin".to_string(), origin.to_string()),
+                ("content-type".to_string(), "application/json".to_string()),
+            ],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_request_head_and_body() {
+        let raw = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:18547\r\nContent-Length: 2\r\nOrigin: https://app.example\r\n\r\n{}trailing";
+        let request = read_request(&mut raw.as_slice()).await.unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("POST", "/")
+        );
+        assert_eq!(request.header("origin"), Some("https://app.example"));
+        assert_eq!(request.body, b"{}");
+
+        let oversized = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(read_request(&mut oversized.as_bytes()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn prompts_are_withdrawn_when_the_site_disconnects() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = service(&dir);
+        let mut metadata = VaultMetadata::new("Connector");
+        metadata.primary_address = Some("0xaa".to_string());
+        service
+            .wallet
+            .read(|ctx| {
+                ctx.create_vault(
+                    &"Password123!".to_string().into(),
+                    metadata,
+                    VaultSecrets::new(vec![7; 32]),
+                )
+            })
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let connector = service.connector.clone();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            handle_connection(stream, service).await;
+        });
+
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"silica_requestAccounts"}"#;
+        let mut client = TcpStream::connect(address).await.unwrap();
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: 127.0.0.1:18547\r\nOrigin: https://app.example\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            call.len(),
+            call
+        );
+        client.write_all(request.as_bytes()).await.unwrap();
+        let until = |pending: usize| {
+            let connector = connector.clone();
+            async move {
+                while connector.status().pending.len() != pending {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), until(1))
+            .await
+            .unwrap();
+
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(5), until(0))
+            .await
+            .expect("prompt withdrawn after the site left");
+    }
+
+    #[tokio::test]
+    async fn foreign_hosts_and_insecure_origins_are_refused() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = service(&dir);
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"silica_requestAccounts"}"#;
+
+        let rebound = service
+            .respond(post("evil.example:18547", "https://evil.example", call))
+            .await;
+        assert_eq!(rebound.status, 403);
+        let insecure = service
+            .respond(post("127.0.0.1:18547", "http://evil.example", call))
+            .await;
+        assert_eq!(insecure.status, 403);
+        assert!(service.connector.status().pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unconnected_sites_cannot_reach_the_prompt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = service(&dir);
+        let call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "silica_signTypedData",
+            "params": ["0xaa", { "domain": {}, "primaryType": "Login", "message": {} }]
+        });
+
+        let response = service
+            .respond(post(
+                "localhost:18547",
+                "https://app.example",
+                &call.to_string(),
+            ))
+            .await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.cors_origin.as_deref(), Some("https://app.example"));
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["error"]["code"], UNAUTHORIZED);
+        assert!(service.connector.status().pending.is_empty());
+
+        // A connected site still needs the token issued with its grant.
+        let (token, token_hash) = issue_token();
+        let mut settings = ConnectorConfig::default();
+        settings.grants.insert(
+            "https://app.example".to_string(),
+            OriginGrant {
+                accounts: vec!["0xaa".to_string()],
+                token_hash,
+                granted_at: Utc::now(),
+            },
+        );
+        assert!(authorize(&settings, "https://app.example", Some(&token)).is_ok());
+        assert!(authorize(&settings, "https://app.example", Some("guess")).is_err());
+        assert!(authorize(&settings, "https://other.example", Some(&token)).is_err());
+    }
+
+    #[tokio::test]
+    async fn typed_data_is_refused_without_a_pinned_chain() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = service(&dir);
+        let (token, token_hash) = issue_token();
+        service
+            .wallet
+            .read(|ctx| {
+                ctx.config_store()
+                    .update(ctx.environment().to_string(), |config| {
+                        config.network.chain_id = None;
+                        config.connector.grants.insert(
+                            "https://app.example".to_string(),
+                            OriginGrant {
+                                accounts: vec!["0xaa".to_string()],
+                                token_hash,
+                                granted_at: Utc::now(),
+                            },
+                        );
+                        Ok(())
+                    })
+                    .map(drop)
+            })
+            .unwrap();
+
+        let call: RpcCall = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "method": "silica_signTypedData",
+            "params": ["0xaa", { "domain": {}, "primaryType": "Login", "message": {} }]
+        }))
+        .unwrap();
+        let refused = service
+            .dispatch("https://app.example", Some(&token), call)
+            .await;
+        assert!(refused.is_err());
+        assert!(service.connector.status().pending.is_empty());
+    }
+}
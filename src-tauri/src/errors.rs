@@ -13,6 +13,16 @@ pub enum WalletError {
     ConnectionTimeout,
     InvalidResponse(String),
 
+    // Node (JSON-RPC) errors
+    NonceConflict(String),
+    InsufficientBalance(String),
+    RateLimited(String),
+    MethodNotFound(String),
+    InvalidParams(String),
+    RpcError { code: i32, message: String },
+    QuorumMismatch(String),
+    ChainMismatch(String),
+
     // Storage errors
     StorageError(String),
     FileNotFound(String),
@@ -43,6 +53,17 @@ impl fmt::Display for WalletError {
             WalletError::ConnectionTimeout => write!(f, "Connection timeout"),
             WalletError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
 
+            WalletError::NonceConflict(msg) => write!(f, "Nonce conflict: {}", msg),
+            WalletError::InsufficientBalance(msg) => write!(f, "Insufficient balance: {}", msg),
+            WalletError::RateLimited(msg) => write!(f, "Rate limited: {}", msg),
+            WalletError::MethodNotFound(msg) => write!(f, "Method not found: {}", msg),
+            WalletError::InvalidParams(msg) => write!(f, "Invalid parameters: {}", msg),
+            WalletError::RpcError { code, message } => {
+                write!(f, "RPC error {}: {}", code, message)
+            }
+            WalletError::QuorumMismatch(msg) => write!(f, "Endpoints disagree: {}", msg),
+            WalletError::ChainMismatch(msg) => write!(f, "Wrong chain: {}", msg),
+
             WalletError::StorageError(msg) => write!(f, "Storage error: {}", msg),
             WalletError::FileNotFound(msg) => write!(f, "File not found: {}", msg),
             WalletError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
@@ -62,6 +83,28 @@ impl fmt::Display for WalletError {
 
 impl std::error::Error for WalletError {}
 
+impl WalletError {
+    /// Whether the same request may succeed on another endpoint or a later
+    /// attempt. Failover loops stop at the first error that is not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            WalletError::NetworkError(_)
+            | WalletError::ConnectionTimeout
+            | WalletError::InvalidResponse(_)
+            | WalletError::RateLimited(_) => true,
+            // Nodes on another version may implement the method.
+            WalletError::MethodNotFound(_) => true,
+            // Another endpoint may be on the pinned chain.
+            WalletError::ChainMismatch(_) => true,
+            // Internal (-32603) and implementation-defined server errors.
+            WalletError::RpcError { code, .. } => {
+                *code == -32603 || (-32099..=-32000).contains(code)
+            }
+            _ => false,
+        }
+    }
+}
+
 pub type WalletResult<T> = Result<T, WalletError>;
 
 // Helper macro for easy error creation
@@ -0,0 +1,318 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::DelegationInfo;
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::VoteSupport;
+use crate::storage::{VaultMetadata, VaultSecrets};
+
+pub const MAX_BATCH_ACCOUNTS: usize = 25;
+
+const VOTE_DOMAIN: &[u8] = b"silica-governance-vote:v1:";
+const DELEGATION_DOMAIN: &[u8] = b"silica-governance-delegation:v1:";
+
+/// Resolve the signing key for each of `accounts`, in order: the primary key
+/// or the key of an account added to the vault. An account the vault does
+/// not hold gets its own error so the rest of the batch still goes ahead.
+pub fn resolve_signers(
+    metadata: &VaultMetadata,
+    secrets: &VaultSecrets,
+    accounts: &[String],
+) -> WalletResult<Vec<(String, WalletResult<SigningKey>)>> {
+    if accounts.is_empty() || accounts.len() > MAX_BATCH_ACCOUNTS {
+        return Err(WalletError::ValidationError(format!(
+            "Batches need 1-{} accounts",
+            MAX_BATCH_ACCOUNTS
+        )));
+    }
+    let unique: BTreeSet<String> = accounts.iter().map(|a| a.to_ascii_lowercase()).collect();
+    if unique.len() != accounts.len() {
+        return Err(WalletError::ValidationError(
+            "Batch contains duplicate accounts".to_string(),
+        ));
+    }
+
+    Ok(accounts
+        .iter()
+        .map(|account| (account.clone(), secrets.signing_key_for(metadata, account)))
+        .collect())
+}
+
+/// A vote cast by one account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountVote {
+    pub voter: String,
+    pub proposal_id: i64,
+    pub support: VoteSupport,
+    pub reason: Option<String>,
+}
+
+/// A voting power delegation from one account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDelegation {
+    pub delegator: String,
+    pub delegatee: String,
+    pub amount: i64,
+}
+
+/// `payload` with the signature of the account it was issued for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSigned<T> {
+    #[serde(flatten)]
+    pub payload: T,
+    pub algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// `payload` prefixed with its signing domain: the bytes its signature covers.
+pub(crate) fn domain_bytes<T: Serialize>(domain: &[u8], payload: &T) -> WalletResult<Vec<u8>> {
+    let mut bytes = domain.to_vec();
+    bytes.extend(serde_json::to_vec(payload)?);
+    Ok(bytes)
+}
+
+pub(crate) fn sign_with_domain<T: Serialize>(
+    domain: &[u8],
+    payload: T,
+    key: &SigningKey,
+) -> WalletResult<AccountSigned<T>> {
+    let signature = key.sign(&domain_bytes(domain, &payload)?);
+    Ok(AccountSigned {
+        payload,
+        algorithm: "Ed25519".to_string(),
+        public_key: hex::encode(key.verifying_key().to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+    })
+}
+
+impl AccountVote {
+    /// Bytes covered by the voter's signature.
+    pub fn signing_bytes(&self) -> WalletResult<Vec<u8>> {
+        domain_bytes(VOTE_DOMAIN, self)
+    }
+
+    pub fn sign(self, key: &SigningKey) -> WalletResult<AccountSigned<Self>> {
+        sign_with_domain(VOTE_DOMAIN, self, key)
+    }
+}
+
+impl AccountDelegation {
+    /// Bytes covered by the delegator's signature.
+    pub fn signing_bytes(&self) -> WalletResult<Vec<u8>> {
+        domain_bytes(DELEGATION_DOMAIN, self)
+    }
+
+    pub fn sign(self, key: &SigningKey) -> WalletResult<AccountSigned<Self>> {
+        sign_with_domain(DELEGATION_DOMAIN, self, key)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationNode {
+    pub address: String,
+    /// Whether the address is one of the wallet's accounts.
+    pub is_local: bool,
+    pub delegated_out: i64,
+    pub delegated_in: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationEdge {
+    pub from: String,
+    pub to: String,
+    pub amount: i64,
+    pub delegated_at: i64,
+}
+
+/// Consolidated view of voting power delegations around a set of accounts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationGraph {
+    pub nodes: Vec<DelegationNode>,
+    pub edges: Vec<DelegationEdge>,
+}
+
+/// Merge per-account `get_delegations` results into one graph. The same
+/// delegation reported from both ends appears once, keeping the newest record.
+pub fn build_delegation_graph(
+    accounts: &[String],
+    delegations: impl IntoIterator<Item = DelegationInfo>,
+) -> DelegationGraph {
+    let local: BTreeSet<String> = accounts.iter().map(|a| a.to_ascii_lowercase()).collect();
+
+    let mut edges: BTreeMap<(String, String), DelegationInfo> = BTreeMap::new();
+    for delegation in delegations {
+        let key = (
+            delegation.delegator.to_ascii_lowercase(),
+            delegation.delegatee.to_ascii_lowercase(),
+        );
+        match edges.get(&key) {
+            Some(existing) if existing.delegated_at >= delegation.delegated_at => {}
+            _ => {
+                edges.insert(key, delegation);
+            }
+        }
+    }
+
+    let mut nodes: BTreeMap<String, DelegationNode> = local
+        .iter()
+        .map(|address| {
+            (
+                address.clone(),
+                DelegationNode {
+                    address: address.clone(),
+                    is_local: true,
+                    delegated_out: 0,
+                    delegated_in: 0,
+                },
+            )
+        })
+        .collect();
+
+    let mut graph_edges = Vec::with_capacity(edges.len());
+    for ((from, to), delegation) in edges {
+        for (address, outgoing) in [(&from, true), (&to, false)] {
+            let entry = nodes
+                .entry(address.clone())
+                .or_insert_with(|| DelegationNode {
+                    address: address.clone(),
+                    is_local: false,
+                    delegated_out: 0,
+                    delegated_in: 0,
+                });
+            if outgoing {
+                entry.delegated_out += delegation.amount;
+            } else {
+                entry.delegated_in += delegation.amount;
+            }
+        }
+        graph_edges.push(DelegationEdge {
+            from,
+            to,
+            amount: delegation.amount,
+            delegated_at: delegation.delegated_at,
+        });
+    }
+
+    DelegationGraph {
+        nodes: nodes.into_values().collect(),
+        edges: graph_edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::AccountKey;
+    use ed25519_dalek::{Signature, Verifier};
+
+    const PRIMARY: &str = "0x00000000000000000000000000000000000000aa";
+    const SECOND: &str = "0x00000000000000000000000000000000000000bb";
+
+    fn delegation(delegator: &str, delegatee: &str, amount: i64, at: i64) -> DelegationInfo {
+        DelegationInfo {
+            delegator: delegator.to_string(),
+            delegatee: delegatee.to_string(),
+            amount,
+            delegated_at: at,
+        }
+    }
+
+    #[test]
+    fn each_held_account_resolves_to_its_own_key() {
+        let mut secrets = VaultSecrets::new(vec![9u8; 32]);
+        secrets.account_keys.push(AccountKey {
+            address: SECOND.to_string(),
+            seed_bytes: vec![8u8; 32],
+        });
+        let mut metadata = VaultMetadata::new("Test");
+        metadata.primary_address = Some(PRIMARY.to_string());
+        metadata.accounts.push(SECOND.to_string());
+
+        let primary = PRIMARY.to_uppercase();
+        let foreign = "0x0000000000000000000000000000000000000001".to_string();
+        let signers = resolve_signers(
+            &metadata,
+            &secrets,
+            &[primary.clone(), SECOND.to_string(), foreign.clone()],
+        )
+        .expect("batch resolves");
+        assert_eq!(signers[0].0, primary);
+        assert_eq!(
+            signers[0].1.as_ref().expect("primary signs").to_bytes(),
+            secrets.signing_key().unwrap().to_bytes()
+        );
+        assert_eq!(
+            signers[1]
+                .1
+                .as_ref()
+                .expect("second account signs")
+                .to_bytes(),
+            [8u8; 32]
+        );
+        assert_eq!(signers[2].0, foreign);
+        assert!(matches!(
+            signers[2].1,
+            Err(WalletError::PermissionDenied(_))
+        ));
+
+        assert!(resolve_signers(&metadata, &secrets, &[primary.clone(), primary]).is_err());
+        let unassigned = resolve_signers(&VaultMetadata::new("New"), &secrets, &[PRIMARY.into()])
+            .expect("batch resolves");
+        assert!(unassigned[0].1.is_err());
+    }
+
+    #[test]
+    fn each_vote_is_signed_by_its_own_account() {
+        let secrets = VaultSecrets::new(vec![3u8; 32]);
+        let key = secrets.signing_key().unwrap();
+        let vote = AccountVote {
+            voter: PRIMARY.to_string(),
+            proposal_id: 4,
+            support: VoteSupport::For,
+            reason: None,
+        };
+        let signed = vote.clone().sign(&key).unwrap();
+
+        let mut message = VOTE_DOMAIN.to_vec();
+        message.extend(serde_json::to_vec(&vote).unwrap());
+        let sig_bytes: [u8; 64] = hex::decode(&signed.signature).unwrap().try_into().unwrap();
+        key.verifying_key()
+            .verify(&message, &Signature::from_bytes(&sig_bytes))
+            .expect("signature verifies");
+        assert_eq!(
+            signed.public_key,
+            hex::encode(key.verifying_key().to_bytes())
+        );
+    }
+
+    #[test]
+    fn graph_merges_delegations_seen_from_both_ends() {
+        let accounts = vec!["0xa".to_string(), "0xb".to_string()];
+        let graph = build_delegation_graph(
+            &accounts,
+            vec![
+                delegation("0xA", "0xc", 10, 1),
+                delegation("0xa", "0xc", 15, 2),
+                delegation("0xb", "0xc", 5, 1),
+                delegation("0xd", "0xa", 7, 1),
+            ],
+        );
+
+        assert_eq!(graph.edges.len(), 3);
+        let node = |address: &str| graph.nodes.iter().find(|n| n.address == address).unwrap();
+        assert_eq!(node("0xa").delegated_out, 15);
+        assert_eq!(node("0xa").delegated_in, 7);
+        assert_eq!(node("0xc").delegated_in, 20);
+        assert!(!node("0xc").is_local);
+        assert!(node("0xb").is_local);
+    }
+}
@@ -0,0 +1,529 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::api::types::ProposalDetail;
+use crate::blockchain::Amount;
+use crate::config_store::{ContractKind, KnownContract};
+
+const SELECTOR_BYTES: usize = 4;
+const WORD_BYTES: usize = 32;
+const ADDRESS_BYTES: usize = 20;
+
+/// ABI parameter types the decoder understands. `Amount` is a `uint256`
+/// rendered as CHERT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Param {
+    Address,
+    Uint,
+    Amount,
+    Bytes,
+}
+
+impl Param {
+    fn abi_type(self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Uint | Self::Amount => "uint256",
+            Self::Bytes => "bytes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FunctionCategory {
+    StakingParameter,
+    GovernanceParameter,
+    Transfer,
+    Upgrade,
+}
+
+struct KnownFunction {
+    name: &'static str,
+    params: &'static [(&'static str, Param)],
+    category: FunctionCategory,
+    /// Description with `{n}` replaced by the n-th decoded argument.
+    template: &'static str,
+}
+
+const KNOWN_FUNCTIONS: &[KnownFunction] = &[
+    KnownFunction {
+        name: "setMinimumStake",
+        params: &[("amount", Param::Amount)],
+        category: FunctionCategory::StakingParameter,
+        template: "Set the minimum validator stake to {0}",
+    },
+    KnownFunction {
+        name: "setUnbondingPeriod",
+        params: &[("seconds", Param::Uint)],
+        category: FunctionCategory::StakingParameter,
+        template: "Set the unbonding period to {0} seconds",
+    },
+    KnownFunction {
+        name: "setMaxValidators",
+        params: &[("count", Param::Uint)],
+        category: FunctionCategory::StakingParameter,
+        template: "Set the active validator cap to {0}",
+    },
+    KnownFunction {
+        name: "setCommissionCap",
+        params: &[("basisPoints", Param::Uint)],
+        category: FunctionCategory::StakingParameter,
+        template: "Cap validator commission at {0} basis points",
+    },
+    KnownFunction {
+        name: "setRewardRate",
+        params: &[("basisPoints", Param::Uint)],
+        category: FunctionCategory::StakingParameter,
+        template: "Set the staking reward rate to {0} basis points",
+    },
+    KnownFunction {
+        name: "setVotingDelay",
+        params: &[("seconds", Param::Uint)],
+        category: FunctionCategory::GovernanceParameter,
+        template: "Set the voting delay to {0} seconds",
+    },
+    KnownFunction {
+        name: "setVotingPeriod",
+        params: &[("seconds", Param::Uint)],
+        category: FunctionCategory::GovernanceParameter,
+        template: "Set the voting period to {0} seconds",
+    },
+    KnownFunction {
+        name: "setProposalThreshold",
+        params: &[("votingPower", Param::Uint)],
+        category: FunctionCategory::GovernanceParameter,
+        template: "Set the proposal threshold to {0} voting power",
+    },
+    KnownFunction {
+        name: "transfer",
+        params: &[("recipient", Param::Address), ("amount", Param::Amount)],
+        category: FunctionCategory::Transfer,
+        template: "Transfer {1} to {0}",
+    },
+    KnownFunction {
+        name: "withdraw",
+        params: &[("recipient", Param::Address), ("amount", Param::Amount)],
+        category: FunctionCategory::Transfer,
+        template: "Withdraw {1} to {0}",
+    },
+    KnownFunction {
+        name: "approve",
+        params: &[("spender", Param::Address), ("amount", Param::Amount)],
+        category: FunctionCategory::Transfer,
+        template: "Allow {0} to spend {1}",
+    },
+    KnownFunction {
+        name: "upgradeTo",
+        params: &[("implementation", Param::Address)],
+        category: FunctionCategory::Upgrade,
+        template: "Upgrade the contract implementation to {0}",
+    },
+    KnownFunction {
+        name: "upgradeToAndCall",
+        params: &[("implementation", Param::Address), ("data", Param::Bytes)],
+        category: FunctionCategory::Upgrade,
+        template: "Upgrade the contract implementation to {0} and call it with {1}",
+    },
+    KnownFunction {
+        name: "changeAdmin",
+        params: &[("newAdmin", Param::Address)],
+        category: FunctionCategory::Upgrade,
+        template: "Hand proxy admin rights to {0}",
+    },
+];
+
+impl KnownFunction {
+    fn signature(&self) -> String {
+        let types: Vec<&str> = self.params.iter().map(|(_, p)| p.abi_type()).collect();
+        format!("{}({})", self.name, types.join(","))
+    }
+
+    fn selector(&self) -> [u8; SELECTOR_BYTES] {
+        selector(&self.signature())
+    }
+}
+
+/// First four bytes of the Keccak-256 hash of a canonical function signature.
+pub fn selector(signature: &str) -> [u8; SELECTOR_BYTES] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    let mut selector = [0u8; SELECTOR_BYTES];
+    selector.copy_from_slice(&hash[..SELECTOR_BYTES]);
+    selector
+}
+
+/// Points a reviewer should look at before voting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionFlag {
+    /// Calls a transfer function on a known treasury contract, moving funds
+    /// or spending rights out of it.
+    TreasuryTransfer,
+    /// Calls a transfer function on a contract the wallet does not know, so
+    /// whose funds move cannot be told from the target alone.
+    UnknownTransferTarget,
+    /// Sends CHERT along with the call, whatever the calldata does.
+    SendsValue,
+    /// The function selector is not one the wallet can describe.
+    UnknownSelector,
+    /// The calldata does not match the recognised function's arguments.
+    MalformedCalldata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedArgument {
+    pub name: String,
+    pub abi_type: String,
+    pub value: String,
+}
+
+/// Readable view of one proposal action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedAction {
+    pub index: usize,
+    pub target: String,
+    pub target_name: Option<String>,
+    pub target_kind: Option<ContractKind>,
+    pub value: String,
+    pub selector: Option<String>,
+    pub function: Option<String>,
+    pub category: Option<FunctionCategory>,
+    pub arguments: Vec<DecodedArgument>,
+    pub description: String,
+    pub flags: Vec<ActionFlag>,
+}
+
+/// Decode every action of `proposal`, labelling addresses found in `contracts`.
+pub fn decode_proposal(
+    proposal: &ProposalDetail,
+    contracts: &BTreeMap<String, KnownContract>,
+) -> Vec<DecodedAction> {
+    let count = proposal
+        .targets
+        .len()
+        .max(proposal.values.len())
+        .max(proposal.signatures.len())
+        .max(proposal.calldatas.len());
+    (0..count)
+        .map(|index| {
+            let field = |items: &[String]| items.get(index).cloned().unwrap_or_default();
+            decode_action(
+                index,
+                &field(&proposal.targets),
+                &field(&proposal.values),
+                &field(&proposal.signatures),
+                &field(&proposal.calldatas),
+                contracts,
+            )
+        })
+        .collect()
+}
+
+/// Decode a single action. With a `signature` the calldata holds only the
+/// arguments, otherwise it starts with the function selector. Transfers are
+/// flagged when they move funds out of a contract registered as a treasury.
+pub fn decode_action(
+    index: usize,
+    target: &str,
+    value: &str,
+    signature: &str,
+    calldata: &str,
+    contracts: &BTreeMap<String, KnownContract>,
+) -> DecodedAction {
+    let label = |address: &str| contracts.get(&address.to_ascii_lowercase());
+    let known_target = label(target);
+    let target_display = display_address(target, known_target);
+
+    let value = match value.trim() {
+        "" => "0".to_string(),
+        value => value.to_string(),
+    };
+    let sends_value = Amount::from_string(&value).map_or(true, |amount| !amount.is_zero());
+
+    let mut action = DecodedAction {
+        index,
+        target: target.to_string(),
+        target_name: known_target.map(|contract| contract.name.clone()),
+        target_kind: known_target.map(|contract| contract.kind),
+        value: value.clone(),
+        selector: None,
+        function: None,
+        category: None,
+        arguments: Vec::new(),
+        description: String::new(),
+        flags: Vec::new(),
+    };
+    if sends_value {
+        action.flags.push(ActionFlag::SendsValue);
+    }
+
+    let bytes = calldata
+        .trim()
+        .strip_prefix("0x")
+        .and_then(|body| hex::decode(body).ok());
+    let Some(bytes) = bytes else {
+        action.flags.push(ActionFlag::MalformedCalldata);
+        action.description = format!("Call {} with undecodable calldata", target_display);
+        return action;
+    };
+
+    let signature: String = signature.chars().filter(|c| !c.is_whitespace()).collect();
+    let (head, args) = if !signature.is_empty() {
+        (selector(&signature), bytes.as_slice())
+    } else if bytes.is_empty() {
+        action.description = if sends_value {
+            format!("Send {} CHERT to {}", value, target_display)
+        } else {
+            format!("Empty call to {}", target_display)
+        };
+        return action;
+    } else if bytes.len() < SELECTOR_BYTES {
+        action.flags.push(ActionFlag::MalformedCalldata);
+        action.description = format!("Call {} with truncated calldata", target_display);
+        return action;
+    } else {
+        let (head, args) = bytes.split_at(SELECTOR_BYTES);
+        (head.try_into().unwrap_or_default(), args)
+    };
+
+    action.selector = Some(format!("0x{}", hex::encode(head)));
+    let Some(function) = KNOWN_FUNCTIONS
+        .iter()
+        .find(|function| function.selector() == head)
+    else {
+        action.flags.push(ActionFlag::UnknownSelector);
+        let function = if signature.is_empty() {
+            action.selector.clone().unwrap_or_default()
+        } else {
+            action.function = Some(signature);
+            action.function.clone().unwrap_or_default()
+        };
+        action.description = format!("Call unknown function {} on {}", function, target_display);
+        return action;
+    };
+
+    action.function = Some(function.signature());
+    action.category = Some(function.category);
+    let from_treasury =
+        known_target.is_some_and(|contract| contract.kind == ContractKind::Treasury);
+    if function.category == FunctionCategory::Transfer {
+        if from_treasury {
+            action.flags.push(ActionFlag::TreasuryTransfer);
+        } else if known_target.is_none() {
+            action.flags.push(ActionFlag::UnknownTransferTarget);
+        }
+    }
+
+    let mut rendered = Vec::with_capacity(function.params.len());
+    for (position, (name, param)) in function.params.iter().enumerate() {
+        let Some(value) = decode_param(args, position, *param) else {
+            action.flags.push(ActionFlag::MalformedCalldata);
+            action.description = format!(
+                "Call {} on {} with malformed arguments",
+                function.signature(),
+                target_display
+            );
+            return action;
+        };
+        rendered.push(match param {
+            Param::Address => display_address(&value, label(&value)),
+            Param::Amount => display_amount(&value),
+            _ => value.clone(),
+        });
+        action.arguments.push(DecodedArgument {
+            name: name.to_string(),
+            abi_type: param.abi_type().to_string(),
+            value,
+        });
+    }
+
+    let mut description = function.template.to_string();
+    for (position, value) in rendered.iter().enumerate() {
+        description = description.replace(&format!("{{{}}}", position), value);
+    }
+    action.description = format!("{} on {}", description, target_display);
+    action
+}
+
+fn display_address(address: &str, contract: Option<&KnownContract>) -> String {
+    match contract {
+        Some(contract) => format!("{} ({})", contract.name, address),
+        None => address.to_string(),
+    }
+}
+
+fn display_amount(base_units: &str) -> String {
+    base_units
+        .parse::<u128>()
+        .ok()
+        .and_then(|units| Amount::from_base_units(units).ok())
+        .map(|amount| format!("{} CHERT", amount.as_string()))
+        .unwrap_or_else(|| format!("{} base units", base_units))
+}
+
+fn word(args: &[u8], index: usize) -> Option<&[u8]> {
+    let start = index.checked_mul(WORD_BYTES)?;
+    args.get(start..start.checked_add(WORD_BYTES)?)
+}
+
+fn word_as_usize(word: &[u8]) -> Option<usize> {
+    let (high, low) = word.split_at(WORD_BYTES - 8);
+    if high.iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(low.try_into().ok()?)).ok()
+}
+
+fn decode_param(args: &[u8], position: usize, param: Param) -> Option<String> {
+    let head = word(args, position)?;
+    match param {
+        Param::Address => {
+            let (padding, address) = head.split_at(WORD_BYTES - ADDRESS_BYTES);
+            padding
+                .iter()
+                .all(|byte| *byte == 0)
+                .then(|| format!("0x{}", hex::encode(address)))
+        }
+        Param::Uint | Param::Amount => {
+            let (high, low) = head.split_at(WORD_BYTES / 2);
+            if high.iter().all(|byte| *byte == 0) {
+                Some(u128::from_be_bytes(low.try_into().ok()?).to_string())
+            } else {
+                Some(format!("0x{}", hex::encode(head)))
+            }
+        }
+        Param::Bytes => {
+            let offset = word_as_usize(head)?;
+            let length_word = args.get(offset..offset.checked_add(WORD_BYTES)?)?;
+            let length = word_as_usize(length_word)?;
+            let start = offset + WORD_BYTES;
+            let data = args.get(start..start.checked_add(length)?)?;
+            Some(format!("0x{}", hex::encode(data)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TREASURY: &str = "0x2222222222222222222222222222222222222222";
+    const RECIPIENT: &str = "0x3333333333333333333333333333333333333333";
+
+    fn contracts() -> BTreeMap<String, KnownContract> {
+        BTreeMap::from([(
+            TREASURY.to_string(),
+            KnownContract {
+                name: "Treasury".to_string(),
+                kind: ContractKind::Treasury,
+            },
+        )])
+    }
+
+    fn address_word(address: &str) -> String {
+        format!("{:0>64}", address.trim_start_matches("0x"))
+    }
+
+    fn uint_word(value: u128) -> String {
+        format!("{:064x}", value)
+    }
+
+    #[test]
+    fn selectors_match_the_abi() {
+        assert_eq!(
+            hex::encode(selector("transfer(address,uint256)")),
+            "a9059cbb"
+        );
+        assert_eq!(hex::encode(selector("upgradeTo(address)")), "3659cfe6");
+    }
+
+    #[test]
+    fn decodes_and_flags_treasury_transfers() {
+        let calldata = format!(
+            "0xa9059cbb{}{}",
+            address_word(RECIPIENT),
+            uint_word(Amount::UNITS_PER_CHERT * 5)
+        );
+        let action = decode_action(0, TREASURY, "0", "", &calldata, &contracts());
+
+        assert_eq!(
+            action.function.as_deref(),
+            Some("transfer(address,uint256)")
+        );
+        assert_eq!(action.target_kind, Some(ContractKind::Treasury));
+        assert_eq!(action.arguments[0].value, RECIPIENT);
+        assert_eq!(action.flags, vec![ActionFlag::TreasuryTransfer]);
+        assert!(action.description.contains("Transfer 5"));
+        assert!(action.description.contains("Treasury"));
+
+        // The same transfer from a contract the wallet does not know.
+        let elsewhere = decode_action(0, RECIPIENT, "0", "", &calldata, &contracts());
+        assert_eq!(elsewhere.category, Some(FunctionCategory::Transfer));
+        assert_eq!(elsewhere.flags, vec![ActionFlag::UnknownTransferTarget]);
+    }
+
+    #[test]
+    fn decodes_signature_with_argument_only_calldata() {
+        let args = format!(
+            "0x{}{}",
+            address_word(RECIPIENT),
+            uint_word(Amount::UNITS_PER_CHERT)
+        );
+        let action = decode_action(
+            0,
+            TREASURY,
+            "0",
+            "transfer(address, uint256)",
+            &args,
+            &contracts(),
+        );
+        assert_eq!(action.selector.as_deref(), Some("0xa9059cbb"));
+        assert_eq!(
+            action.arguments[1].value,
+            Amount::UNITS_PER_CHERT.to_string()
+        );
+        assert_eq!(action.flags, vec![ActionFlag::TreasuryTransfer]);
+
+        let unknown = decode_action(0, TREASURY, "0", "pause()", "0x", &contracts());
+        assert_eq!(unknown.function.as_deref(), Some("pause()"));
+        assert_eq!(unknown.flags, vec![ActionFlag::UnknownSelector]);
+    }
+
+    #[test]
+    fn decodes_dynamic_bytes_arguments() {
+        let calldata = format!(
+            "0x{}{}{}{}{:0<64}",
+            hex::encode(selector("upgradeToAndCall(address,bytes)")),
+            address_word(RECIPIENT),
+            uint_word(64),
+            uint_word(2),
+            "beef"
+        );
+        let action = decode_action(0, TREASURY, "", "", &calldata, &contracts());
+
+        assert_eq!(action.category, Some(FunctionCategory::Upgrade));
+        assert_eq!(action.arguments[1].value, "0xbeef");
+        assert!(action.flags.is_empty());
+    }
+
+    #[test]
+    fn flags_unknown_selectors_and_malformed_calldata() {
+        let action = decode_action(0, RECIPIENT, "1.5", "", "0xdeadbeef", &contracts());
+        assert_eq!(action.selector.as_deref(), Some("0xdeadbeef"));
+        assert_eq!(
+            action.flags,
+            vec![ActionFlag::SendsValue, ActionFlag::UnknownSelector]
+        );
+
+        // A plain value transfer has no calldata to flag, but still moves funds.
+        let payment = decode_action(0, RECIPIENT, "2", "", "0x", &contracts());
+        assert_eq!(payment.flags, vec![ActionFlag::SendsValue]);
+
+        let truncated = decode_action(0, TREASURY, "0", "", "0xa9059cbb00", &contracts());
+        assert!(truncated.flags.contains(&ActionFlag::MalformedCalldata));
+    }
+}
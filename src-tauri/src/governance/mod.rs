@@ -0,0 +1,11 @@
+pub mod batch;
+pub mod calldata;
+pub mod proposal;
+pub mod voting;
+pub mod watchlist;
+
+pub use batch::DelegationGraph;
+pub use calldata::DecodedAction;
+pub use proposal::{ProposalPayload, SignedProposal};
+pub use voting::{ProposalState, VoteSupport};
+pub use watchlist::{GovernanceWatchlist, WatchlistState};
@@ -0,0 +1,252 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{CreateProposalRequest, ProposalAction};
+use crate::blockchain::Amount;
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::batch::{domain_bytes, sign_with_domain, AccountSigned};
+use crate::storage::VaultSecrets;
+use crate::validation::InputValidator;
+
+pub const MAX_PROPOSAL_ACTIONS: usize = 10;
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_DESCRIPTION_LENGTH: usize = 20_000;
+
+/// Domain separator so a proposal signature cannot be replayed as another message.
+const SIGNING_DOMAIN: &[u8] = b"silica-governance-proposal:v1:";
+const SELECTOR_BYTES: usize = 4;
+const ABI_WORD_BYTES: usize = 32;
+
+/// Validated proposal contents, in the shape submitted to the node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalPayload {
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub targets: Vec<String>,
+    pub values: Vec<String>,
+    pub signatures: Vec<String>,
+    pub calldatas: Vec<String>,
+}
+
+/// Proposal payload with the proposer's signature over its canonical encoding.
+pub type SignedProposal = AccountSigned<ProposalPayload>;
+
+impl ProposalPayload {
+    /// Validate `request` and normalize it into a payload for `proposer`.
+    pub fn from_request(proposer: &str, request: &CreateProposalRequest) -> WalletResult<Self> {
+        let validator = InputValidator::default();
+        validator.validate_address(proposer)?;
+
+        let title = request.title.trim();
+        if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+            return Err(WalletError::ValidationError(format!(
+                "Proposal title must be 1-{} characters",
+                MAX_TITLE_LENGTH
+            )));
+        }
+        let description = request.description.trim();
+        if description.is_empty() || description.len() > MAX_DESCRIPTION_LENGTH {
+            return Err(WalletError::ValidationError(format!(
+                "Proposal description must be 1-{} characters",
+                MAX_DESCRIPTION_LENGTH
+            )));
+        }
+        if request.actions.is_empty() || request.actions.len() > MAX_PROPOSAL_ACTIONS {
+            return Err(WalletError::ValidationError(format!(
+                "Proposals need 1-{} actions",
+                MAX_PROPOSAL_ACTIONS
+            )));
+        }
+
+        let mut payload = Self {
+            proposer: proposer.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+            targets: Vec::with_capacity(request.actions.len()),
+            values: Vec::with_capacity(request.actions.len()),
+            signatures: Vec::with_capacity(request.actions.len()),
+            calldatas: Vec::with_capacity(request.actions.len()),
+        };
+        for (index, action) in request.actions.iter().enumerate() {
+            let (value, signature, calldata) =
+                validate_action(&validator, action).map_err(|err| {
+                    WalletError::ValidationError(format!("Action {}: {}", index + 1, err))
+                })?;
+            payload.targets.push(action.target.clone());
+            payload.values.push(value);
+            payload.signatures.push(signature);
+            payload.calldatas.push(calldata);
+        }
+        Ok(payload)
+    }
+
+    /// Bytes covered by the proposer's signature.
+    pub fn signing_bytes(&self) -> WalletResult<Vec<u8>> {
+        domain_bytes(SIGNING_DOMAIN, self)
+    }
+
+    /// Sign with the wallet's Ed25519 key, as `sign_message` does.
+    pub fn sign(self, secrets: &VaultSecrets) -> WalletResult<SignedProposal> {
+        sign_with_domain(SIGNING_DOMAIN, self, &secrets.signing_key()?)
+    }
+}
+
+/// Reject proposers below the governance threshold.
+pub fn ensure_proposal_threshold(voting_power: i64, threshold: i64) -> WalletResult<()> {
+    if voting_power < threshold {
+        return Err(WalletError::PermissionDenied(format!(
+            "Voting power {} is below the proposal threshold of {}",
+            voting_power, threshold
+        )));
+    }
+    Ok(())
+}
+
+// `usize::is_multiple_of` needs Rust 1.87.
+#[allow(clippy::manual_is_multiple_of)]
+fn validate_action(
+    validator: &InputValidator,
+    action: &ProposalAction,
+) -> WalletResult<(String, String, String)> {
+    validator.validate_address(&action.target)?;
+
+    let value = match action.value.trim() {
+        "" => "0".to_string(),
+        value => {
+            Amount::from_string(value)?;
+            value.to_string()
+        }
+    };
+
+    let signature = action.signature.trim().to_string();
+    if !signature.is_empty() {
+        let pattern =
+            Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*\(([A-Za-z0-9_\[\]]+(,[A-Za-z0-9_\[\]]+)*)?\)$")
+                .map_err(|e| {
+                    WalletError::ValidationError(format!("Invalid signature regex: {}", e))
+                })?;
+        if !pattern.is_match(&signature) {
+            return Err(WalletError::ValidationError(format!(
+                "Invalid function signature: {}",
+                signature
+            )));
+        }
+    }
+
+    let calldata = match action.calldata.trim() {
+        "" => "0x".to_string(),
+        calldata => calldata.to_ascii_lowercase(),
+    };
+    let hex_body = calldata.strip_prefix("0x").ok_or_else(|| {
+        WalletError::ValidationError("Calldata must be 0x-prefixed hex".to_string())
+    })?;
+    let bytes = hex::decode(hex_body)
+        .map_err(|e| WalletError::ValidationError(format!("Calldata is not valid hex: {}", e)))?;
+    if signature.is_empty() {
+        // Without a signature the calldata carries its own selector.
+        if !bytes.is_empty()
+            && (bytes.len() < SELECTOR_BYTES
+                || (bytes.len() - SELECTOR_BYTES) % ABI_WORD_BYTES != 0)
+        {
+            return Err(WalletError::ValidationError(
+                "Calldata must be a 4-byte selector followed by 32-byte words".to_string(),
+            ));
+        }
+    } else if bytes.len() % ABI_WORD_BYTES != 0 {
+        return Err(WalletError::ValidationError(
+            "Calldata arguments must be 32-byte ABI words".to_string(),
+        ));
+    }
+
+    Ok((value, signature, calldata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    const PROPOSER: &str = "0x1111111111111111111111111111111111111111";
+    const TARGET: &str = "0x2222222222222222222222222222222222222222";
+
+    fn request(actions: Vec<ProposalAction>) -> CreateProposalRequest {
+        CreateProposalRequest {
+            title: "Raise validator cap".to_string(),
+            description: "Allow more validators.".to_string(),
+            actions,
+        }
+    }
+
+    fn action(signature: &str, calldata: &str) -> ProposalAction {
+        ProposalAction {
+            target: TARGET.to_string(),
+            value: String::new(),
+            signature: signature.to_string(),
+            calldata: calldata.to_string(),
+        }
+    }
+
+    #[test]
+    fn accepts_well_formed_actions() {
+        let word = "00".repeat(32);
+        let payload = ProposalPayload::from_request(
+            PROPOSER,
+            &request(vec![
+                action("setCap(uint256)", &format!("0x{}", word)),
+                action("", &format!("0xa9059cbb{}{}", word, word)),
+                action("", "0x"),
+            ]),
+        )
+        .expect("valid proposal");
+
+        assert_eq!(payload.targets.len(), 3);
+        assert_eq!(payload.values, vec!["0", "0", "0"]);
+    }
+
+    #[test]
+    fn rejects_bad_targets_and_calldata() {
+        let mut bad_target = action("", "0x");
+        bad_target.target = "not-an-address".to_string();
+        for bad in [
+            bad_target,
+            action("", "deadbeef"),
+            action("", "0xzz"),
+            action("", "0xa9059cbb00"),
+            action("setCap(uint256)", "0xa9059cbb"),
+            action("setCap(uint256", "0x"),
+        ] {
+            assert!(ProposalPayload::from_request(PROPOSER, &request(vec![bad])).is_err());
+        }
+        assert!(ProposalPayload::from_request(PROPOSER, &request(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn signature_covers_canonical_payload() {
+        let payload =
+            ProposalPayload::from_request(PROPOSER, &request(vec![action("", "0x")])).unwrap();
+        let signed = payload
+            .clone()
+            .sign(&VaultSecrets::new(vec![7u8; 32]))
+            .expect("sign");
+
+        let key_bytes: [u8; 32] = hex::decode(&signed.public_key).unwrap().try_into().unwrap();
+        let sig_bytes: [u8; 64] = hex::decode(&signed.signature).unwrap().try_into().unwrap();
+        let key = VerifyingKey::from_bytes(&key_bytes).unwrap();
+        key.verify(
+            &payload.signing_bytes().unwrap(),
+            &Signature::from_bytes(&sig_bytes),
+        )
+        .expect("signature verifies");
+    }
+
+    #[test]
+    fn threshold_is_enforced() {
+        assert!(ensure_proposal_threshold(100, 100).is_ok());
+        assert!(matches!(
+            ensure_proposal_threshold(99, 100),
+            Err(WalletError::PermissionDenied(_))
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::api::types::ProposalDetail;
+use crate::errors::{WalletError, WalletResult};
+
+/// Vote direction. Serialized as the integer the node expects
+/// (0 = against, 1 = for, 2 = abstain); names are accepted on input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoteSupport {
+    Against,
+    For,
+    Abstain,
+}
+
+impl VoteSupport {
+    pub fn as_i32(self) -> i32 {
+        match self {
+            Self::Against => 0,
+            Self::For => 1,
+            Self::Abstain => 2,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Against => "against",
+            Self::For => "for",
+            Self::Abstain => "abstain",
+        }
+    }
+}
+
+impl TryFrom<i64> for VoteSupport {
+    type Error = WalletError;
+
+    fn try_from(value: i64) -> WalletResult<Self> {
+        match value {
+            0 => Ok(Self::Against),
+            1 => Ok(Self::For),
+            2 => Ok(Self::Abstain),
+            other => Err(WalletError::ValidationError(format!(
+                "Invalid vote support {}: expected 0 (against), 1 (for) or 2 (abstain)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::str::FromStr for VoteSupport {
+    type Err = WalletError;
+
+    fn from_str(value: &str) -> WalletResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "against" | "no" => Ok(Self::Against),
+            "for" | "yes" => Ok(Self::For),
+            "abstain" => Ok(Self::Abstain),
+            other => other
+                .parse::<i64>()
+                .map_err(|_| {
+                    WalletError::ValidationError(format!("Invalid vote support: {}", value))
+                })
+                .and_then(Self::try_from),
+        }
+    }
+}
+
+impl fmt::Display for VoteSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for VoteSupport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.as_i32())
+    }
+}
+
+impl<'de> Deserialize<'de> for VoteSupport {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(i64),
+            Name(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(value) => Self::try_from(value),
+            Raw::Name(name) => name.parse(),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Proposal lifecycle state as reported by the node, serialized with the
+/// spellings the frontend matches on. States this wallet does not recognise
+/// are kept verbatim in `Unknown` rather than failing the response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProposalState {
+    Pending,
+    Active,
+    Cancelled,
+    Vetoed,
+    Defeated,
+    Succeeded,
+    Queued,
+    Expired,
+    Executed,
+    Unknown(String),
+}
+
+impl ProposalState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Pending => "pending",
+            Self::Active => "active",
+            Self::Cancelled => "cancelled",
+            Self::Vetoed => "vetoed",
+            Self::Defeated => "defeated",
+            Self::Succeeded => "succeeded",
+            Self::Queued => "queued",
+            Self::Expired => "expired",
+            Self::Executed => "executed",
+            Self::Unknown(state) => state,
+        }
+    }
+
+    /// States from which the proposal can no longer collect votes.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Cancelled
+                | Self::Vetoed
+                | Self::Defeated
+                | Self::Succeeded
+                | Self::Queued
+                | Self::Expired
+                | Self::Executed
+        )
+    }
+}
+
+impl From<&str> for ProposalState {
+    fn from(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pending" => Self::Pending,
+            "active" => Self::Active,
+            "cancelled" | "canceled" => Self::Cancelled,
+            "vetoed" => Self::Vetoed,
+            "defeated" => Self::Defeated,
+            "succeeded" => Self::Succeeded,
+            "queued" => Self::Queued,
+            "expired" => Self::Expired,
+            "executed" => Self::Executed,
+            _ => Self::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ProposalState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ProposalState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProposalState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Self::from(value.as_str()))
+    }
+}
+
+/// Refuse a vote unless `proposal` is active and inside its voting window
+/// at `now`. Any other state, including one this wallet does not recognise,
+/// is refused.
+///
+/// `vote_start`/`vote_end` are unix seconds and the window is `[start, end)`.
+/// This applies equally to re-votes: an existing vote on the proposal does
+/// not exempt a change from the window.
+pub fn ensure_voting_open(proposal: &ProposalDetail, now: DateTime<Utc>) -> WalletResult<()> {
+    let now = now.timestamp();
+    if proposal.state != ProposalState::Active {
+        return Err(WalletError::ValidationError(format!(
+            "Proposal {} is {} and does not accept votes",
+            proposal.proposal_id, proposal.state
+        )));
+    }
+    if now < proposal.vote_start {
+        return Err(WalletError::ValidationError(format!(
+            "Voting on proposal {} has not started yet",
+            proposal.proposal_id
+        )));
+    }
+    if now >= proposal.vote_end {
+        return Err(WalletError::ValidationError(format!(
+            "Voting on proposal {} has ended",
+            proposal.proposal_id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::VoteInfo;
+
+    fn proposal(state: ProposalState, vote_start: i64, vote_end: i64) -> ProposalDetail {
+        ProposalDetail {
+            proposal_id: 7,
+            proposer: "0x1111111111111111111111111111111111111111".to_string(),
+            targets: Vec::new(),
+            values: Vec::new(),
+            signatures: Vec::new(),
+            calldatas: Vec::new(),
+            description: "Test".to_string(),
+            vote_start,
+            vote_end,
+            votes_for: 0,
+            votes_against: 0,
+            votes_abstain: 0,
+            state,
+            executed_at: None,
+            created_at: 0,
+            updated_at: 0,
+            has_voted: None,
+            user_vote: None,
+        }
+    }
+
+    #[test]
+    fn vote_support_keeps_integer_wire_format() {
+        assert_eq!(serde_json::to_string(&VoteSupport::For).unwrap(), "1");
+        let parsed: VoteSupport = serde_json::from_str("2").unwrap();
+        assert_eq!(parsed, VoteSupport::Abstain);
+        let named: VoteSupport = serde_json::from_str("\"Against\"").unwrap();
+        assert_eq!(named, VoteSupport::Against);
+        assert!(serde_json::from_str::<VoteSupport>("3").is_err());
+        assert!(serde_json::from_str::<VoteSupport>("\"maybe\"").is_err());
+    }
+
+    #[test]
+    fn proposal_state_parses_leniently() {
+        let state: ProposalState = serde_json::from_str("\"Active\"").unwrap();
+        assert_eq!(state, ProposalState::Active);
+        let state: ProposalState = serde_json::from_str("\"canceled\"").unwrap();
+        assert_eq!(state, ProposalState::Cancelled);
+        let state: ProposalState = serde_json::from_str("\"vetoed\"").unwrap();
+        assert_eq!(state, ProposalState::Vetoed);
+        let state: ProposalState = serde_json::from_str("\"paused\"").unwrap();
+        assert_eq!(state, ProposalState::Unknown("paused".to_string()));
+        assert_eq!(
+            serde_json::to_string(&ProposalState::Executed).unwrap(),
+            "\"executed\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ProposalState::Cancelled).unwrap(),
+            "\"cancelled\""
+        );
+    }
+
+    #[test]
+    fn voting_window_is_enforced_for_new_and_changed_votes() {
+        let now = Utc::now();
+        let ts = now.timestamp();
+
+        assert!(
+            ensure_voting_open(&proposal(ProposalState::Active, ts - 10, ts + 10), now).is_ok()
+        );
+        assert!(
+            ensure_voting_open(&proposal(ProposalState::Pending, ts + 1, ts + 10), now).is_err()
+        );
+        assert!(ensure_voting_open(&proposal(ProposalState::Active, ts - 10, ts), now).is_err());
+        for state in [
+            ProposalState::Cancelled,
+            ProposalState::Vetoed,
+            ProposalState::Unknown("paused".to_string()),
+        ] {
+            assert!(ensure_voting_open(&proposal(state, ts - 10, ts + 10), now).is_err());
+        }
+
+        let mut voted = proposal(ProposalState::Succeeded, ts - 20, ts - 10);
+        voted.has_voted = Some(true);
+        voted.user_vote = Some(VoteInfo {
+            proposal_id: 7,
+            voter: "0x1111111111111111111111111111111111111111".to_string(),
+            support: VoteSupport::For,
+            weight: 1,
+            reason: None,
+            voted_at: ts - 15,
+        });
+        assert!(ensure_voting_open(&voted, now).is_err());
+    }
+}
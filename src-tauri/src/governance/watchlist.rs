@@ -0,0 +1,626 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{ProposalDetail, ProposalSummary};
+use crate::blockchain_client::BlockchainClient;
+use crate::config_store::{ContractKind, KnownContract};
+use crate::errors::{WalletError, WalletResult};
+use crate::governance::calldata::{decode_proposal, FunctionCategory};
+use crate::governance::ProposalState;
+use crate::storage::WalletPaths;
+
+const WATCHLIST_FILE: &str = "governance/watchlist.json";
+
+/// Tauri event emitted for watched proposal transitions and vote reminders.
+pub const GOVERNANCE_EVENT: &str = "governance://watchlist";
+
+/// How often watched proposals are refreshed.
+pub const WATCHLIST_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Auto-watch rules applied to proposals first seen by the refresher.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchRules {
+    pub new_proposals: bool,
+    /// Watch proposals whose actions target staking contracts or parameters.
+    pub staking_proposals: bool,
+    /// Remind accounts that have not voted this long before `vote_end`.
+    pub reminder_lead_hours: u32,
+}
+
+impl Default for WatchRules {
+    fn default() -> Self {
+        Self {
+            new_proposals: false,
+            staking_proposals: true,
+            reminder_lead_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchReason {
+    Manual,
+    NewProposal,
+    Staking,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedProposal {
+    pub proposal_id: i64,
+    pub description: String,
+    pub reason: WatchReason,
+    pub state: ProposalState,
+    pub vote_start: i64,
+    pub vote_end: i64,
+    pub added_at: DateTime<Utc>,
+    /// Accounts already reminded to vote on this proposal.
+    #[serde(default)]
+    pub reminded_accounts: BTreeSet<String>,
+}
+
+impl WatchedProposal {
+    fn from_summary(summary: &ProposalSummary, reason: WatchReason, now: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id: summary.proposal_id,
+            description: summary.description.clone(),
+            reason,
+            state: summary.state.clone(),
+            vote_start: summary.vote_start,
+            vote_end: summary.vote_end,
+            added_at: now,
+            reminded_accounts: BTreeSet::new(),
+        }
+    }
+
+    fn from_detail(detail: &ProposalDetail, reason: WatchReason, now: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id: detail.proposal_id,
+            description: detail.description.clone(),
+            reason,
+            state: detail.state.clone(),
+            vote_start: detail.vote_start,
+            vote_end: detail.vote_end,
+            added_at: now,
+            reminded_accounts: BTreeSet::new(),
+        }
+    }
+
+    /// Whether `now` falls inside the reminder lead time before voting closes.
+    fn reminder_due(&self, lead_hours: u32, now: DateTime<Utc>) -> bool {
+        let now = now.timestamp();
+        self.state == ProposalState::Active
+            && now >= self.vote_start
+            && now < self.vote_end
+            && self.vote_end - now <= i64::from(lead_hours) * 3600
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum GovernanceAlert {
+    StateChanged {
+        proposal_id: i64,
+        description: String,
+        from: ProposalState,
+        to: ProposalState,
+    },
+    VoteReminder {
+        proposal_id: i64,
+        description: String,
+        account: String,
+        vote_end: i64,
+    },
+}
+
+/// Payload of [`GOVERNANCE_EVENT`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceNotification {
+    pub alert: GovernanceAlert,
+    /// Push registrations subscribed to governance alerts.
+    pub push_registrations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistState {
+    #[serde(default)]
+    pub rules: WatchRules,
+    pub proposals: BTreeMap<i64, WatchedProposal>,
+    /// Highest proposal id the auto-watch rules have been applied to.
+    pub last_seen_proposal_id: Option<i64>,
+}
+
+/// Whether any action of `detail` targets a staking contract or parameter.
+pub fn touches_staking(
+    detail: &ProposalDetail,
+    contracts: &BTreeMap<String, KnownContract>,
+) -> bool {
+    decode_proposal(detail, contracts).iter().any(|action| {
+        action.target_kind == Some(ContractKind::Staking)
+            || action.category == Some(FunctionCategory::StakingParameter)
+    })
+}
+
+/// Votes requested per page when checking who still has to vote.
+const VOTES_PAGE_SIZE: u64 = 100;
+/// Stop paging after this many pages, e.g. if a node ignores the offset.
+const MAX_VOTE_PAGES: u64 = 1_000;
+
+/// What the node reported for one watched proposal during a refresh.
+#[derive(Debug, Clone)]
+struct Observation {
+    state: ProposalState,
+    vote_start: i64,
+    vote_end: i64,
+    /// Lowercase accounts found among the voters, when votes were checked.
+    voters: Option<BTreeSet<String>>,
+}
+
+/// Everything a refresh learned from the node, merged into the stored state
+/// in one step so edits made while it ran are kept.
+#[derive(Debug, Default)]
+struct RefreshOutcome {
+    additions: Vec<WatchedProposal>,
+    observations: BTreeMap<i64, Observation>,
+    last_seen_proposal_id: Option<i64>,
+}
+
+impl RefreshOutcome {
+    /// Merge into `state` and return the alerts for proposals still watched.
+    fn apply(
+        self,
+        state: &mut WatchlistState,
+        accounts: &[String],
+        now: DateTime<Utc>,
+    ) -> Vec<GovernanceAlert> {
+        for added in self.additions {
+            state.proposals.entry(added.proposal_id).or_insert(added);
+        }
+        state.last_seen_proposal_id = state.last_seen_proposal_id.max(self.last_seen_proposal_id);
+
+        let mut alerts = Vec::new();
+        let lead_hours = state.rules.reminder_lead_hours;
+        for (proposal_id, observed) in self.observations {
+            // Unwatched while the node was being queried.
+            let Some(watched) = state.proposals.get_mut(&proposal_id) else {
+                continue;
+            };
+            if observed.state != watched.state {
+                alerts.push(GovernanceAlert::StateChanged {
+                    proposal_id,
+                    description: watched.description.clone(),
+                    from: watched.state.clone(),
+                    to: observed.state.clone(),
+                });
+                watched.state = observed.state;
+            }
+            watched.vote_start = observed.vote_start;
+            watched.vote_end = observed.vote_end;
+
+            let Some(voters) = observed.voters else {
+                continue;
+            };
+            if !watched.reminder_due(lead_hours, now) {
+                continue;
+            }
+            for account in accounts {
+                if !watched.reminded_accounts.insert(account.clone()) {
+                    continue;
+                }
+                if !voters.contains(&account.to_ascii_lowercase()) {
+                    alerts.push(GovernanceAlert::VoteReminder {
+                        proposal_id,
+                        description: watched.description.clone(),
+                        account: account.clone(),
+                        vote_end: watched.vote_end,
+                    });
+                }
+            }
+        }
+        alerts
+    }
+}
+
+/// Apply auto-watch rules to the node's proposal list, refresh watched
+/// proposals, and return the alerts to deliver. Reminders go to each of
+/// `accounts` that has no vote recorded, once per proposal. A proposal the
+/// node fails to return is logged and tried again on the next refresh.
+pub async fn refresh(
+    client: &BlockchainClient,
+    watchlist: &GovernanceWatchlist,
+    accounts: &[String],
+    contracts: &BTreeMap<String, KnownContract>,
+    now: DateTime<Utc>,
+) -> WalletResult<Vec<GovernanceAlert>> {
+    let listing = client.get_proposals(None).await?.proposals;
+    let snapshot = watchlist.state()?;
+    let mut outcome = RefreshOutcome::default();
+
+    let mut first_failed = None;
+    for summary in &listing {
+        let unseen = snapshot
+            .last_seen_proposal_id
+            .is_none_or(|seen| summary.proposal_id > seen);
+        if !unseen
+            || summary.state.is_final()
+            || snapshot.proposals.contains_key(&summary.proposal_id)
+        {
+            continue;
+        }
+        if snapshot.rules.new_proposals {
+            outcome.additions.push(WatchedProposal::from_summary(
+                summary,
+                WatchReason::NewProposal,
+                now,
+            ));
+        } else if snapshot.rules.staking_proposals {
+            match client.get_proposal(summary.proposal_id).await {
+                Ok(response) if touches_staking(&response.proposal, contracts) => {
+                    outcome.additions.push(WatchedProposal::from_detail(
+                        &response.proposal,
+                        WatchReason::Staking,
+                        now,
+                    ));
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log::warn!("Skipping proposal {} for now: {}", summary.proposal_id, err);
+                    first_failed = Some(
+                        first_failed
+                            .map_or(summary.proposal_id, |id: i64| id.min(summary.proposal_id)),
+                    );
+                }
+            }
+        }
+    }
+    // Proposals that could not be checked stay unseen until they can be.
+    outcome.last_seen_proposal_id = listing
+        .iter()
+        .map(|summary| summary.proposal_id)
+        .filter(|id| first_failed.is_none_or(|failed| *id < failed))
+        .max();
+
+    for watched in snapshot.proposals.values() {
+        if watched.state.is_final() {
+            continue;
+        }
+        let (state, vote_start, vote_end) = match listing
+            .iter()
+            .find(|summary| summary.proposal_id == watched.proposal_id)
+        {
+            Some(summary) => (summary.state.clone(), summary.vote_start, summary.vote_end),
+            None => match client.get_proposal(watched.proposal_id).await {
+                Ok(response) => {
+                    let detail = response.proposal;
+                    (detail.state, detail.vote_start, detail.vote_end)
+                }
+                Err(err) => {
+                    log::warn!("Skipping proposal {} for now: {}", watched.proposal_id, err);
+                    continue;
+                }
+            },
+        };
+        let mut fresh = watched.clone();
+        fresh.state = state.clone();
+        fresh.vote_start = vote_start;
+        fresh.vote_end = vote_end;
+
+        let pending: Vec<&String> = accounts
+            .iter()
+            .filter(|account| !watched.reminded_accounts.contains(*account))
+            .collect();
+        let voters =
+            if !pending.is_empty() && fresh.reminder_due(snapshot.rules.reminder_lead_hours, now) {
+                match voters_among(client, watched.proposal_id, &pending).await {
+                    Ok(voters) => Some(voters),
+                    Err(err) => {
+                        log::warn!(
+                            "Could not check votes on proposal {}: {}",
+                            watched.proposal_id,
+                            err
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+        outcome.observations.insert(
+            watched.proposal_id,
+            Observation {
+                state,
+                vote_start,
+                vote_end,
+                voters,
+            },
+        );
+    }
+
+    watchlist.update(|state| Ok(outcome.apply(state, accounts, now)))
+}
+
+/// Which of `accounts` have voted on `proposal_id`, paging through the votes
+/// until all of them are found or the votes run out.
+async fn voters_among(
+    client: &BlockchainClient,
+    proposal_id: i64,
+    accounts: &[&String],
+) -> WalletResult<BTreeSet<String>> {
+    let wanted: BTreeSet<String> = accounts
+        .iter()
+        .map(|account| account.to_ascii_lowercase())
+        .collect();
+    let mut found = BTreeSet::new();
+    let mut offset = 0;
+    for _ in 0..MAX_VOTE_PAGES {
+        let page = client
+            .get_proposal_votes(proposal_id, Some(VOTES_PAGE_SIZE), Some(offset))
+            .await?
+            .votes;
+        if page.is_empty() {
+            return Ok(found);
+        }
+        offset += page.len() as u64;
+        found.extend(
+            page.iter()
+                .map(|vote| vote.voter.to_ascii_lowercase())
+                .filter(|voter| wanted.contains(voter)),
+        );
+        if found.len() == wanted.len() {
+            return Ok(found);
+        }
+    }
+    Err(WalletError::InvalidResponse(format!(
+        "Votes on proposal {} did not end after {} pages",
+        proposal_id, MAX_VOTE_PAGES
+    )))
+}
+
+/// Persisted set of followed proposals and auto-watch rules. Clones share a
+/// lock so refreshes and user edits update the file one at a time.
+#[derive(Debug, Clone)]
+pub struct GovernanceWatchlist {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl GovernanceWatchlist {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::default(),
+        }
+    }
+
+    pub fn from_paths(paths: &WalletPaths) -> Self {
+        Self::new(paths.cache_dir().join(WATCHLIST_FILE))
+    }
+
+    pub fn state(&self) -> WalletResult<WatchlistState> {
+        let _lock = self.lock.lock();
+        self.load()
+    }
+
+    /// Follow `proposal` manually. Re-watching keeps reminder history.
+    pub fn watch(
+        &self,
+        proposal: &ProposalDetail,
+        now: DateTime<Utc>,
+    ) -> WalletResult<WatchlistState> {
+        self.edit(|state| {
+            state
+                .proposals
+                .entry(proposal.proposal_id)
+                .and_modify(|watched| watched.reason = WatchReason::Manual)
+                .or_insert_with(|| {
+                    WatchedProposal::from_detail(proposal, WatchReason::Manual, now)
+                });
+        })
+    }
+
+    pub fn unwatch(&self, proposal_id: i64) -> WalletResult<WatchlistState> {
+        self.edit(|state| {
+            state.proposals.remove(&proposal_id);
+        })
+    }
+
+    pub fn set_rules(&self, rules: WatchRules) -> WalletResult<WatchlistState> {
+        if rules.reminder_lead_hours == 0 {
+            return Err(WalletError::ValidationError(
+                "Reminder lead time must be at least one hour".to_string(),
+            ));
+        }
+        self.edit(|state| state.rules = rules)
+    }
+
+    /// Apply `change` to the stored state and return the result.
+    fn edit(&self, change: impl FnOnce(&mut WatchlistState)) -> WalletResult<WatchlistState> {
+        self.update(|state| {
+            change(state);
+            Ok(state.clone())
+        })
+    }
+
+    /// Load, modify and save the state while holding the lock.
+    fn update<T>(
+        &self,
+        change: impl FnOnce(&mut WatchlistState) -> WalletResult<T>,
+    ) -> WalletResult<T> {
+        let _lock = self.lock.lock();
+        let mut state = self.load()?;
+        let result = change(&mut state)?;
+        self.save(&state)?;
+        Ok(result)
+    }
+
+    fn load(&self) -> WalletResult<WatchlistState> {
+        if !self.path.exists() {
+            return Ok(WatchlistState::default());
+        }
+        let data = fs::read(&self.path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| WalletError::StorageError(format!("Corrupt governance watchlist: {}", e)))
+    }
+
+    fn save(&self, state: &WatchlistState) -> WalletResult<()> {
+        let dir = self
+            .path
+            .parent()
+            .ok_or_else(|| WalletError::StorageError("Invalid watchlist path".to_string()))?;
+        fs::create_dir_all(dir)?;
+
+        let tmp_path = self.path.with_extension("new");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(state)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn detail(proposal_id: i64, vote_start: i64, vote_end: i64) -> ProposalDetail {
+        ProposalDetail {
+            proposal_id,
+            proposer: "0x1111111111111111111111111111111111111111".to_string(),
+            targets: vec!["0x2222222222222222222222222222222222222222".to_string()],
+            values: vec!["0".to_string()],
+            signatures: vec![String::new()],
+            calldatas: vec!["0x".to_string()],
+            description: "Tune staking".to_string(),
+            vote_start,
+            vote_end,
+            votes_for: 0,
+            votes_against: 0,
+            votes_abstain: 0,
+            state: ProposalState::Active,
+            executed_at: None,
+            created_at: 0,
+            updated_at: 0,
+            has_voted: None,
+            user_vote: None,
+        }
+    }
+
+    #[test]
+    fn reminder_is_due_only_inside_the_lead_window() {
+        let now = Utc::now();
+        let ts = now.timestamp();
+        let closing = WatchedProposal::from_detail(
+            &detail(1, ts - 3600, ts + 3600),
+            WatchReason::Manual,
+            now,
+        );
+        assert!(closing.reminder_due(24, now));
+        assert!(!closing.reminder_due(24, now + Duration::hours(2)));
+
+        let distant = WatchedProposal::from_detail(
+            &detail(2, ts - 3600, ts + 72 * 3600),
+            WatchReason::Manual,
+            now,
+        );
+        assert!(!distant.reminder_due(24, now));
+    }
+
+    #[test]
+    fn staking_proposals_are_recognised_by_target_or_function() {
+        let proposal = detail(1, 0, 1);
+        let mut contracts = BTreeMap::new();
+        assert!(!touches_staking(&proposal, &contracts));
+
+        contracts.insert(
+            proposal.targets[0].clone(),
+            KnownContract {
+                name: "Staking".to_string(),
+                kind: ContractKind::Staking,
+            },
+        );
+        assert!(touches_staking(&proposal, &contracts));
+    }
+
+    #[test]
+    fn watchlist_persists_manual_entries_and_rules() {
+        let dir = TempDir::new().expect("temp dir");
+        let watchlist = GovernanceWatchlist::new(dir.path().join("watchlist.json"));
+        let now = Utc::now();
+
+        watchlist.watch(&detail(7, 0, 10), now).unwrap();
+        let rules = WatchRules {
+            new_proposals: true,
+            staking_proposals: false,
+            reminder_lead_hours: 6,
+        };
+        watchlist.set_rules(rules.clone()).unwrap();
+
+        let state = watchlist.state().unwrap();
+        assert_eq!(state.rules, rules);
+        assert_eq!(state.proposals[&7].reason, WatchReason::Manual);
+        assert!(watchlist
+            .set_rules(WatchRules {
+                reminder_lead_hours: 0,
+                ..rules
+            })
+            .is_err());
+
+        assert!(watchlist.unwatch(7).unwrap().proposals.is_empty());
+    }
+
+    #[test]
+    fn refresh_results_merge_with_edits_made_meanwhile() {
+        let dir = TempDir::new().expect("temp dir");
+        let watchlist = GovernanceWatchlist::new(dir.path().join("watchlist.json"));
+        let now = Utc::now();
+        let ts = now.timestamp();
+        watchlist
+            .watch(&detail(7, ts - 3600, ts + 3600), now)
+            .unwrap();
+        watchlist
+            .watch(&detail(8, ts - 3600, ts + 3600), now)
+            .unwrap();
+
+        let observed = |voters: &[&str]| Observation {
+            state: ProposalState::Active,
+            vote_start: ts - 3600,
+            vote_end: ts + 3600,
+            voters: Some(voters.iter().map(|voter| voter.to_string()).collect()),
+        };
+        let outcome = RefreshOutcome {
+            additions: Vec::new(),
+            observations: BTreeMap::from([(7, observed(&[])), (8, observed(&["0xaa"]))]),
+            last_seen_proposal_id: Some(8),
+        };
+        // The user stops following proposal 7 while the node is queried.
+        watchlist.unwatch(7).unwrap();
+
+        let accounts = vec!["0xAA".to_string(), "0xbb".to_string()];
+        let alerts = watchlist
+            .update(|state| Ok(outcome.apply(state, &accounts, now)))
+            .unwrap();
+        let state = watchlist.state().unwrap();
+        assert!(!state.proposals.contains_key(&7));
+        assert_eq!(state.last_seen_proposal_id, Some(8));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(
+            &alerts[0],
+            GovernanceAlert::VoteReminder { proposal_id: 8, account, .. } if account == "0xbb"
+        ));
+        assert_eq!(state.proposals[&8].reminded_accounts.len(), 2);
+    }
+}
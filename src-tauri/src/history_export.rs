@@ -0,0 +1,571 @@
+//! Streaming export of an account's transaction history for accounting tools.
+//!
+//! Rows are written page by page as they arrive from the node, so exporting a
+//! long history never holds more than one RPC page in memory.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::TransactionInfo;
+use crate::blockchain::Amount;
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+
+/// Default number of transactions requested per RPC page.
+pub const DEFAULT_EXPORT_PAGE_SIZE: u32 = 200;
+/// Upper bound on the page size accepted from callers.
+pub const MAX_EXPORT_PAGE_SIZE: u32 = 1_000;
+
+const CSV_COLUMNS: [&str; 8] = [
+    "timestamp",
+    "direction",
+    "counterparty",
+    "amount",
+    "fee",
+    "tx_id",
+    "block_height",
+    "category",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionDirection {
+    In,
+    Out,
+    #[serde(rename = "self")]
+    SelfTransfer,
+}
+
+impl TransactionDirection {
+    fn resolve(account: &str, info: &TransactionInfo) -> Self {
+        let from_self = info.from_address.eq_ignore_ascii_case(account);
+        let to_self = info.to_address.eq_ignore_ascii_case(account);
+        match (from_self, to_self) {
+            (true, true) => TransactionDirection::SelfTransfer,
+            (true, false) => TransactionDirection::Out,
+            _ => TransactionDirection::In,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransactionDirection::In => "in",
+            TransactionDirection::Out => "out",
+            TransactionDirection::SelfTransfer => "self",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionCategory {
+    Transfer,
+    Stake,
+    Reward,
+    Vote,
+}
+
+impl TransactionCategory {
+    /// Classify a transaction from the node-reported type, defaulting to a plain transfer.
+    pub fn classify(info: &TransactionInfo) -> Self {
+        let kind = match info.transaction_type.as_deref() {
+            Some(kind) => kind.trim().to_ascii_lowercase(),
+            None => return TransactionCategory::Transfer,
+        };
+
+        if kind.contains("reward") || kind.contains("claim") {
+            TransactionCategory::Reward
+        } else if kind.contains("vote") || kind.contains("governance") {
+            TransactionCategory::Vote
+        } else if kind.contains("stake")
+            || kind.contains("delegat")
+            || kind.contains("lockbox")
+            || kind.contains("staking")
+        {
+            TransactionCategory::Stake
+        } else {
+            TransactionCategory::Transfer
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransactionCategory::Transfer => "transfer",
+            TransactionCategory::Stake => "stake",
+            TransactionCategory::Reward => "reward",
+            TransactionCategory::Vote => "vote",
+        }
+    }
+}
+
+/// Daily reference prices used to fill the optional fiat value column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatPricing {
+    /// ISO 4217 currency code, e.g. `USD`.
+    pub currency: String,
+    /// Price of one CHERT in `currency`, keyed by UTC date.
+    pub daily_prices: BTreeMap<NaiveDate, f64>,
+}
+
+impl FiatPricing {
+    fn value_of(&self, amount: &Amount, at: &DateTime<Utc>) -> Option<f64> {
+        let price = self.daily_prices.get(&at.date_naive())?;
+        let value = amount.as_chert() * price;
+        Some((value * 100.0).round() / 100.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryExportOptions {
+    pub format: ExportFormat,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub fiat: Option<FiatPricing>,
+    pub page_size: u32,
+}
+
+impl HistoryExportOptions {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            from: None,
+            to: None,
+            fiat: None,
+            page_size: DEFAULT_EXPORT_PAGE_SIZE,
+        }
+    }
+
+    fn validate(&self) -> WalletResult<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(WalletError::ValidationError(
+                    "Export range start must not be after its end".to_string(),
+                ));
+            }
+        }
+
+        if let Some(fiat) = &self.fiat {
+            let currency = fiat.currency.trim();
+            if currency.is_empty() || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(WalletError::ValidationError(format!(
+                    "Invalid fiat currency code '{}'",
+                    fiat.currency
+                )));
+            }
+            if fiat
+                .daily_prices
+                .values()
+                .any(|price| !price.is_finite() || *price < 0.0)
+            {
+                return Err(WalletError::ValidationError(
+                    "Fiat prices must be finite and non-negative".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| *timestamp >= from) && self.to.is_none_or(|to| *timestamp <= to)
+    }
+}
+
+/// Single exported history line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportRow {
+    pub timestamp: DateTime<Utc>,
+    pub direction: TransactionDirection,
+    pub counterparty: String,
+    pub amount: String,
+    pub fee: String,
+    pub tx_id: String,
+    pub block_height: Option<u64>,
+    pub category: TransactionCategory,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fiat_value: Option<f64>,
+}
+
+impl HistoryExportRow {
+    pub fn from_transaction(
+        account: &str,
+        info: &TransactionInfo,
+        fiat: Option<&FiatPricing>,
+    ) -> WalletResult<Self> {
+        let timestamp = parse_timestamp(&info.timestamp)?;
+        let direction = TransactionDirection::resolve(account, info);
+        let counterparty = match direction {
+            TransactionDirection::In => info.from_address.clone(),
+            TransactionDirection::Out | TransactionDirection::SelfTransfer => {
+                info.to_address.clone()
+            }
+        };
+
+        let fiat_value = match fiat {
+            Some(pricing) => {
+                let amount = Amount::from_string(info.amount.trim()).map_err(|e| {
+                    WalletError::InvalidResponse(format!(
+                        "Transaction {} has an invalid amount: {}",
+                        info.transaction_id, e
+                    ))
+                })?;
+                pricing.value_of(&amount, &timestamp)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            timestamp,
+            direction,
+            counterparty,
+            amount: info.amount.clone(),
+            fee: info.fee.clone(),
+            tx_id: info.transaction_id.clone(),
+            block_height: info.block_height,
+            category: TransactionCategory::classify(info),
+            fiat_value,
+        })
+    }
+}
+
+/// Writes history rows to any `Write` sink in CSV or JSON form.
+pub struct HistoryExporter<W: Write> {
+    writer: W,
+    account: String,
+    options: HistoryExportOptions,
+    rows_written: u64,
+}
+
+impl<W: Write> HistoryExporter<W> {
+    pub fn new(
+        mut writer: W,
+        account: impl Into<String>,
+        options: HistoryExportOptions,
+    ) -> WalletResult<Self> {
+        options.validate()?;
+        let account = account.into();
+
+        match options.format {
+            ExportFormat::Csv => {
+                let mut header = CSV_COLUMNS.join(",");
+                if let Some(fiat) = &options.fiat {
+                    header.push_str(&format!(
+                        ",fiat_value_{}",
+                        fiat.currency.trim().to_ascii_lowercase()
+                    ));
+                }
+                writeln!(writer, "{}", header)?;
+            }
+            ExportFormat::Json => {
+                write!(writer, "{{\"account\":")?;
+                serde_json::to_writer(&mut writer, &account)?;
+                if let Some(fiat) = &options.fiat {
+                    write!(writer, ",\"fiatCurrency\":")?;
+                    serde_json::to_writer(&mut writer, &fiat.currency.trim().to_uppercase())?;
+                }
+                write!(writer, ",\"transactions\":[")?;
+            }
+        }
+
+        Ok(Self {
+            writer,
+            account,
+            options,
+            rows_written: 0,
+        })
+    }
+
+    /// Append a transaction if it falls inside the requested range.
+    /// Returns whether a row was written.
+    pub fn write_transaction(&mut self, info: &TransactionInfo) -> WalletResult<bool> {
+        let row =
+            HistoryExportRow::from_transaction(&self.account, info, self.options.fiat.as_ref())?;
+        if !self.options.contains(&row.timestamp) {
+            return Ok(false);
+        }
+
+        match self.options.format {
+            ExportFormat::Csv => self.write_csv_row(&row)?,
+            ExportFormat::Json => {
+                if self.rows_written > 0 {
+                    write!(self.writer, ",")?;
+                }
+                serde_json::to_writer(&mut self.writer, &row)?;
+            }
+        }
+
+        self.rows_written += 1;
+        Ok(true)
+    }
+
+    /// Close the document and flush the sink, returning the number of rows written.
+    pub fn finish(mut self) -> WalletResult<u64> {
+        if self.options.format == ExportFormat::Json {
+            write!(self.writer, "]}}")?;
+        }
+        self.writer.flush()?;
+        Ok(self.rows_written)
+    }
+
+    fn write_csv_row(&mut self, row: &HistoryExportRow) -> WalletResult<()> {
+        let block_height = row
+            .block_height
+            .map(|height| height.to_string())
+            .unwrap_or_default();
+        let mut fields = vec![
+            row.timestamp.to_rfc3339(),
+            row.direction.as_str().to_string(),
+            csv_field(&row.counterparty),
+            csv_field(&row.amount),
+            csv_field(&row.fee),
+            csv_field(&row.tx_id),
+            block_height,
+            row.category.as_str().to_string(),
+        ];
+        if self.options.fiat.is_some() {
+            fields.push(
+                row.fiat_value
+                    .map(|value| format!("{:.2}", value))
+                    .unwrap_or_default(),
+            );
+        }
+        writeln!(self.writer, "{}", fields.join(","))?;
+        Ok(())
+    }
+}
+
+/// Page through the node's history for `account`, streaming matching rows into `writer`.
+pub async fn export_account_history<W: Write>(
+    client: &BlockchainClient,
+    account: &str,
+    options: HistoryExportOptions,
+    writer: W,
+) -> WalletResult<u64> {
+    let page_size = options.page_size.clamp(1, MAX_EXPORT_PAGE_SIZE);
+    let mut exporter = HistoryExporter::new(writer, account, options)?;
+    let mut offset: u32 = 0;
+
+    loop {
+        let page = client
+            .get_transaction_history(account, Some(page_size), Some(offset))
+            .await?;
+        let fetched = page.transactions.len() as u32;
+        for transaction in &page.transactions {
+            exporter.write_transaction(transaction)?;
+        }
+
+        offset = offset.saturating_add(fetched);
+        if fetched < page_size || u64::from(offset) >= page.total_count {
+            break;
+        }
+    }
+
+    exporter.finish()
+}
+
+/// Export into the file at `destination`. Rows go to a `.partial` sibling
+/// that replaces `destination` only once the export completes, so a failed
+/// export never leaves a truncated file behind.
+pub async fn export_account_history_to_file(
+    client: &BlockchainClient,
+    account: &str,
+    options: HistoryExportOptions,
+    destination: &Path,
+) -> WalletResult<u64> {
+    let partial_path =
+        destination.with_extension(format!("{}.partial", options.format.file_extension()));
+    let file = File::create(&partial_path)?;
+
+    match export_account_history(client, account, options, BufWriter::new(file)).await {
+        Ok(rows_written) => {
+            fs::rename(&partial_path, destination)?;
+            Ok(rows_written)
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&partial_path);
+            Err(err)
+        }
+    }
+}
+
+/// Parse a node timestamp given either as RFC 3339 or as Unix seconds.
+fn parse_timestamp(raw: &str) -> WalletResult<DateTime<Utc>> {
+    let trimmed = raw.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(parsed.with_timezone(&Utc));
+    }
+
+    trimmed
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+        .ok_or_else(|| {
+            WalletError::InvalidResponse(format!("Unrecognized transaction timestamp '{}'", raw))
+        })
+}
+
+fn csv_field(value: &str) -> String {
+    // Node-supplied text a spreadsheet would evaluate as a formula is kept literal.
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "0x1111111111111111111111111111111111111111";
+    const OTHER: &str = "0x2222222222222222222222222222222222222222";
+
+    fn transaction(id: &str, from: &str, to: &str, timestamp: &str) -> TransactionInfo {
+        TransactionInfo {
+            transaction_id: id.to_string(),
+            from_address: from.to_string(),
+            to_address: to.to_string(),
+            amount: "1.5".to_string(),
+            fee: "0.01".to_string(),
+            status: "confirmed".to_string(),
+            timestamp: timestamp.to_string(),
+            block_height: Some(42),
+            transaction_type: None,
+            explorer_url: None,
+        }
+    }
+
+    #[test]
+    fn csv_export_writes_header_and_rows() {
+        let mut output = Vec::new();
+        let mut exporter = HistoryExporter::new(
+            &mut output,
+            ACCOUNT,
+            HistoryExportOptions::new(ExportFormat::Csv),
+        )
+        .unwrap();
+        exporter
+            .write_transaction(&transaction("tx1", ACCOUNT, OTHER, "2024-03-01T12:00:00Z"))
+            .unwrap();
+        exporter
+            .write_transaction(&transaction("tx2", OTHER, ACCOUNT, "1709294400"))
+            .unwrap();
+        assert_eq!(exporter.finish().unwrap(), 2);
+
+        let text = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,direction,counterparty,amount,fee,tx_id,block_height,category"
+        );
+        assert!(lines[1].contains(",out,0x2222"));
+        assert!(lines[1].ends_with(",tx1,42,transfer"));
+        assert!(lines[2].contains(",in,0x2222"));
+    }
+
+    #[test]
+    fn date_range_filters_rows() {
+        let mut options = HistoryExportOptions::new(ExportFormat::Csv);
+        options.from = Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+        options.to = Some(Utc.with_ymd_and_hms(2024, 3, 31, 23, 59, 59).unwrap());
+
+        let mut output = Vec::new();
+        let mut exporter = HistoryExporter::new(&mut output, ACCOUNT, options).unwrap();
+        let inside = transaction("tx1", ACCOUNT, OTHER, "2024-03-15T00:00:00Z");
+        let outside = transaction("tx2", ACCOUNT, OTHER, "2024-04-02T00:00:00Z");
+        assert!(exporter.write_transaction(&inside).unwrap());
+        assert!(!exporter.write_transaction(&outside).unwrap());
+        assert_eq!(exporter.finish().unwrap(), 1);
+    }
+
+    #[test]
+    fn inverted_range_is_rejected() {
+        let mut options = HistoryExportOptions::new(ExportFormat::Json);
+        options.from = Some(Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap());
+        options.to = Some(Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap());
+        let result = HistoryExporter::new(Vec::new(), ACCOUNT, options);
+        assert!(matches!(result, Err(WalletError::ValidationError(_))));
+    }
+
+    #[test]
+    fn json_export_includes_fiat_and_category() {
+        let mut prices = BTreeMap::new();
+        prices.insert(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), 2.0);
+        let mut options = HistoryExportOptions::new(ExportFormat::Json);
+        options.fiat = Some(FiatPricing {
+            currency: "usd".to_string(),
+            daily_prices: prices,
+        });
+
+        let mut reward = transaction("tx1", OTHER, ACCOUNT, "2024-03-01T08:00:00Z");
+        reward.transaction_type = Some("staking_claim_rewards".to_string());
+        let mut output = Vec::new();
+        let mut exporter = HistoryExporter::new(&mut output, ACCOUNT, options).unwrap();
+        exporter.write_transaction(&reward).unwrap();
+        exporter.finish().unwrap();
+
+        let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(document["fiatCurrency"], "USD");
+        let row = &document["transactions"][0];
+        assert_eq!(row["category"], "reward");
+        assert_eq!(row["direction"], "in");
+        assert_eq!(row["fiatValue"], 3.0);
+    }
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        for formula in ["+1", "-1", "@SUM(A1)", "\tcmd"] {
+            assert_eq!(csv_field(formula), format!("'{}", formula));
+        }
+        assert_eq!(csv_field("\r1"), "\"'\r1\"");
+    }
+
+    #[tokio::test]
+    async fn failed_file_export_leaves_no_partial_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let destination = dir.path().join("history.csv");
+        let client = BlockchainClient::new("http://127.0.0.1:1".to_string()).unwrap();
+
+        let result = export_account_history_to_file(
+            &client,
+            ACCOUNT,
+            HistoryExportOptions::new(ExportFormat::Csv),
+            &destination,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
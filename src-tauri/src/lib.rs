@@ -4,13 +4,27 @@ pub mod api;
 pub mod app_state;
 pub mod blockchain;
 pub mod blockchain_client;
+pub mod commands;
 pub mod config_store;
+pub mod connector;
 pub mod crypto;
 pub mod errors;
+pub mod governance;
+pub mod history_export;
+pub mod light_client;
+pub mod network;
+pub mod proxy;
+pub mod quorum;
+pub mod realtime;
+pub mod rpc_batch;
+pub mod rpc_cache;
+pub mod rpc_pool;
 pub mod runtime;
 pub mod security;
 pub mod session;
+pub mod staking;
 pub mod storage;
+pub mod tls;
 pub mod validation;
 
 // Crypto module is exposed directly via `crate::crypto`
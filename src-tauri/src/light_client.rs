@@ -0,0 +1,570 @@
+/// Light-client verification of account state (experimental)
+///
+/// Balances and nonces are checked against a Merkle proof into the state root
+/// of a block header, and the header is only trusted when validators holding
+/// more than two thirds of the trusted voting power signed it. The trusted set
+/// starts as the pinned one and follows rotations announced in headers it
+/// signed. Proofs must be recent relative to the newest trusted header, so a
+/// node cannot pass off old state as current.
+///
+/// The header and leaf encodings and the `get_block_header` /
+/// `get_account_proof` methods are provisional: they are not yet confirmed
+/// against the protocol-core spec, so results are advisory and shown next to
+/// balances rather than blocking anything.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::types::BalanceResponse;
+use crate::config_store::{LightClientConfig, TrustedValidator};
+use crate::errors::{WalletError, WalletResult};
+
+/// Trusted headers kept for proof checks.
+const MAX_TRUSTED_HEADERS: usize = 256;
+/// How many blocks a proof may trail the newest trusted header.
+pub const MAX_PROOF_LAG: u64 = 16;
+
+const HEADER_DOMAIN: &[u8] = b"silica-header:v1:";
+const HEADER_SIGNING_DOMAIN: &[u8] = b"silica-header-commit:v1:";
+const ACCOUNT_LEAF_DOMAIN: &[u8] = b"silica-account:v1:";
+
+/// Block header with the validator signatures committing to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedHeader {
+    pub height: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    pub state_root: String,
+    pub timestamp: u64,
+    /// Validators signing this header, when the set has rotated away from
+    /// the trusted one. Covered by the header hash.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<TrustedValidator>,
+    pub signatures: Vec<HeaderSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderSignature {
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// An account's state with its Merkle path to the state root at `height`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: String,
+    pub balance: String,
+    pub nonce: u64,
+    pub height: u64,
+    pub leaf_index: u64,
+    /// Sibling hashes from the leaf up, hex-encoded.
+    pub proof: Vec<String>,
+}
+
+/// Outcome of checking a balance, shown next to it so unverifiable values
+/// can be flagged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceVerification {
+    pub verified: bool,
+    /// Height of the header the proof was checked against.
+    pub height: Option<u64>,
+    pub reason: Option<String>,
+}
+
+impl BalanceVerification {
+    pub fn verified(height: u64) -> Self {
+        Self {
+            verified: true,
+            height: Some(height),
+            reason: None,
+        }
+    }
+
+    pub fn unverified(error: &WalletError) -> Self {
+        Self {
+            verified: false,
+            height: None,
+            reason: Some(error.to_string()),
+        }
+    }
+}
+
+/// Hash committing to a header's fields; validators sign this value.
+pub fn header_hash(header: &SignedHeader) -> WalletResult<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(HEADER_DOMAIN);
+    hasher.update(header.height.to_be_bytes());
+    hasher.update(decode_hash(&header.parent_hash)?);
+    hasher.update(decode_hash(&header.state_root)?);
+    hasher.update(header.timestamp.to_be_bytes());
+    if !header.validators.is_empty() {
+        hasher.update((header.validators.len() as u64).to_be_bytes());
+        for validator in &header.validators {
+            hasher.update(decode_hash(&validator.public_key)?);
+            hasher.update(validator.voting_power.to_be_bytes());
+        }
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Leaf of the state tree for an account.
+pub fn account_leaf(address: &str, balance: &str, nonce: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ACCOUNT_LEAF_DOMAIN);
+    hasher.update(address.to_ascii_lowercase().as_bytes());
+    hasher.update([0]);
+    hasher.update(balance.as_bytes());
+    hasher.update([0]);
+    hasher.update(nonce.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Fold `path` onto `leaf`; bit `i` of `index` set means the sibling at
+/// level `i` is on the left. Same scheme as the frontend's NUW Merkle tasks.
+fn merkle_root(leaf: [u8; 32], path: &[[u8; 32]], index: u64) -> [u8; 32] {
+    path.iter()
+        .enumerate()
+        .fold(leaf, |current, (level, sibling)| {
+            let mut hasher = Sha256::new();
+            if (index >> level) & 1 == 1 {
+                hasher.update(sibling);
+                hasher.update(current);
+            } else {
+                hasher.update(current);
+                hasher.update(sibling);
+            }
+            hasher.finalize().into()
+        })
+}
+
+/// Check that `header` is internally consistent and signed by more than two
+/// thirds of the voting power of `trusted`. A header naming a different set
+/// needs more than two thirds of that set, vouched for by more than a third
+/// of the trusted power, as in Tendermint's skipping verification.
+pub fn verify_header(header: &SignedHeader, trusted: &[TrustedValidator]) -> WalletResult<()> {
+    let hash = header_hash(header)?;
+    if decode_hash(&header.hash)? != hash {
+        return Err(WalletError::InvalidResponse(format!(
+            "Header {} hash does not match its contents",
+            header.height
+        )));
+    }
+
+    let mut message = HEADER_SIGNING_DOMAIN.to_vec();
+    message.extend_from_slice(&hash);
+    if header.validators.is_empty() || same_validators(&header.validators, trusted) {
+        return require_signatures(header, trusted, &message, 2);
+    }
+    require_signatures(header, trusted, &message, 1)?;
+    require_signatures(header, &header.validators, &message, 2)
+}
+
+/// Fail unless more than `thirds`/3 of the voting power of `validators`
+/// signed `message`.
+fn require_signatures(
+    header: &SignedHeader,
+    validators: &[TrustedValidator],
+    message: &[u8],
+    thirds: u64,
+) -> WalletResult<()> {
+    let total: u64 = validators.iter().map(|v| v.voting_power).sum();
+    let mut signed = 0u64;
+    for validator in validators {
+        let key = normalize_hex(&validator.public_key);
+        let Some(signature) = header
+            .signatures
+            .iter()
+            .find(|s| normalize_hex(&s.public_key) == key)
+        else {
+            continue;
+        };
+        if signature_is_valid(&key, &signature.signature, message) {
+            signed = signed.saturating_add(validator.voting_power);
+        }
+    }
+
+    if total == 0 || signed.saturating_mul(3) <= total.saturating_mul(thirds) {
+        return Err(WalletError::InvalidResponse(format!(
+            "Header {} is signed by {} of {} trusted voting power",
+            header.height, signed, total
+        )));
+    }
+    Ok(())
+}
+
+fn same_validators(a: &[TrustedValidator], b: &[TrustedValidator]) -> bool {
+    let set = |validators: &[TrustedValidator]| {
+        validators
+            .iter()
+            .map(|v| (normalize_hex(&v.public_key), v.voting_power))
+            .collect::<BTreeMap<_, _>>()
+    };
+    set(a) == set(b)
+}
+
+/// Check `proof` against the state root of the trusted `header`.
+pub fn verify_account_proof(proof: &AccountProof, header: &SignedHeader) -> WalletResult<()> {
+    if proof.height != header.height {
+        return Err(WalletError::InvalidResponse(format!(
+            "Proof is for height {} but the header is {}",
+            proof.height, header.height
+        )));
+    }
+    let path = proof
+        .proof
+        .iter()
+        .map(|hash| decode_hash(hash))
+        .collect::<WalletResult<Vec<_>>>()?;
+    let leaf = account_leaf(&proof.address, &proof.balance, proof.nonce);
+    if merkle_root(leaf, &path, proof.leaf_index) != decode_hash(&header.state_root)? {
+        return Err(WalletError::InvalidResponse(format!(
+            "Account proof for {} does not match the state root at height {}",
+            proof.address, header.height
+        )));
+    }
+    Ok(())
+}
+
+/// Check that the node's answer for an account is the state `proof` proves.
+pub fn ensure_matches(balance: &BalanceResponse, proof: &AccountProof) -> WalletResult<()> {
+    if !balance.address.eq_ignore_ascii_case(&proof.address)
+        || balance.balance != proof.balance
+        || balance.nonce != proof.nonce
+    {
+        return Err(WalletError::InvalidResponse(format!(
+            "Reported state of {} differs from its proof",
+            balance.address
+        )));
+    }
+    Ok(())
+}
+
+/// Trusted validator set and headers verified against it, shared by clones
+/// of a client.
+#[derive(Debug, Clone, Default)]
+pub struct LightClient {
+    config: Arc<RwLock<LightClientConfig>>,
+    /// Set that signed the newest trusted header; the pinned set until a
+    /// rotation is seen.
+    validators: Arc<RwLock<Vec<TrustedValidator>>>,
+    headers: Arc<RwLock<BTreeMap<u64, SignedHeader>>>,
+}
+
+impl LightClient {
+    pub fn new(config: LightClientConfig) -> Self {
+        Self {
+            validators: Arc::new(RwLock::new(config.validators.clone())),
+            config: Arc::new(RwLock::new(config)),
+            headers: Arc::default(),
+        }
+    }
+
+    pub fn config(&self) -> LightClientConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace the settings; a new pinned set drops the tracked chain and
+    /// any rotations followed from the old one.
+    pub fn set_config(&self, config: LightClientConfig) {
+        let mut current = self.config.write();
+        if current.validators != config.validators {
+            self.headers.write().clear();
+            *self.validators.write() = config.validators.clone();
+        }
+        *current = config;
+    }
+
+    /// The validator set headers are currently verified against.
+    pub fn validators(&self) -> Vec<TrustedValidator> {
+        self.validators.read().clone()
+    }
+
+    pub fn trusted_header(&self, height: u64) -> Option<SignedHeader> {
+        self.headers.read().get(&height).cloned()
+    }
+
+    pub fn latest_header(&self) -> Option<SignedHeader> {
+        self.headers
+            .read()
+            .last_key_value()
+            .map(|(_, header)| header.clone())
+    }
+
+    /// Refuse a proof at `height` that trails the newest trusted header by
+    /// more than `MAX_PROOF_LAG` blocks.
+    pub fn ensure_fresh(&self, height: u64) -> WalletResult<()> {
+        let head = self
+            .headers
+            .read()
+            .last_key_value()
+            .map_or(0, |(head, _)| *head);
+        if height.saturating_add(MAX_PROOF_LAG) < head {
+            return Err(WalletError::InvalidResponse(format!(
+                "Proof at height {} is stale; the trusted head is {}",
+                height, head
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verify `header` and add it to the tracked chain. A header must link to
+    /// any already trusted neighbour, so a fork cannot be mixed in. A new
+    /// head that names its validators makes that set the trusted one.
+    pub fn accept_header(&self, header: SignedHeader) -> WalletResult<SignedHeader> {
+        verify_header(&header, &self.validators.read())?;
+
+        let mut headers = self.headers.write();
+        if let Some(existing) = headers.get(&header.height) {
+            if existing.hash != header.hash {
+                return Err(WalletError::InvalidResponse(format!(
+                    "Conflicting headers at height {}",
+                    header.height
+                )));
+            }
+            return Ok(existing.clone());
+        }
+        let links_to_parent = header
+            .height
+            .checked_sub(1)
+            .and_then(|parent| headers.get(&parent))
+            .is_none_or(|parent| parent.hash == header.parent_hash);
+        let links_to_child = headers
+            .get(&(header.height + 1))
+            .is_none_or(|child| child.parent_hash == header.hash);
+        if !links_to_parent || !links_to_child {
+            return Err(WalletError::InvalidResponse(format!(
+                "Header {} does not link to the trusted chain",
+                header.height
+            )));
+        }
+
+        let newest = headers
+            .last_key_value()
+            .is_none_or(|(head, _)| *head < header.height);
+        if newest && !header.validators.is_empty() {
+            *self.validators.write() = header.validators.clone();
+        }
+        headers.insert(header.height, header.clone());
+        while headers.len() > MAX_TRUSTED_HEADERS {
+            headers.pop_first();
+        }
+        Ok(header)
+    }
+}
+
+/// Reject a light-client configuration that could never verify a header.
+pub fn validate_config(config: &LightClientConfig) -> WalletResult<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+    if config.validators.is_empty() {
+        return Err(WalletError::ValidationError(
+            "The light client needs at least one trusted validator".to_string(),
+        ));
+    }
+    for validator in &config.validators {
+        let key = hex::decode(normalize_hex(&validator.public_key)).ok();
+        if key
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .is_none()
+        {
+            return Err(WalletError::ValidationError(format!(
+                "Invalid validator public key: {}",
+                validator.public_key
+            )));
+        }
+        if validator.voting_power == 0 {
+            return Err(WalletError::ValidationError(
+                "Validator voting power must be positive".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn signature_is_valid(public_key: &str, signature: &str, message: &[u8]) -> bool {
+    let key = hex::decode(public_key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok());
+    let signature = hex::decode(normalize_hex(signature))
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes));
+    match (key, signature) {
+        (Some(key), Some(signature)) => key.verify(message, &signature).is_ok(),
+        _ => false,
+    }
+}
+
+fn normalize_hex(value: &str) -> String {
+    value.trim_start_matches("0x").to_ascii_lowercase()
+}
+
+fn decode_hash(value: &str) -> WalletResult<[u8; 32]> {
+    hex::decode(normalize_hex(value))
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| WalletError::InvalidResponse(format!("Invalid 32-byte hash: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signed_header(
+        height: u64,
+        parent: &str,
+        root: [u8; 32],
+        keys: &[&SigningKey],
+    ) -> SignedHeader {
+        rotated_header(height, parent, root, keys, Vec::new())
+    }
+
+    fn rotated_header(
+        height: u64,
+        parent: &str,
+        root: [u8; 32],
+        keys: &[&SigningKey],
+        validators: Vec<TrustedValidator>,
+    ) -> SignedHeader {
+        let mut header = SignedHeader {
+            height,
+            hash: String::new(),
+            parent_hash: parent.to_string(),
+            state_root: hex::encode(root),
+            timestamp: 1_700_000_000 + height,
+            validators,
+            signatures: Vec::new(),
+        };
+        let hash = header_hash(&header).unwrap();
+        header.hash = hex::encode(hash);
+        let mut message = HEADER_SIGNING_DOMAIN.to_vec();
+        message.extend_from_slice(&hash);
+        header.signatures = keys
+            .iter()
+            .map(|key| HeaderSignature {
+                public_key: hex::encode(key.verifying_key().to_bytes()),
+                signature: hex::encode(key.sign(&message).to_bytes()),
+            })
+            .collect();
+        header
+    }
+
+    fn validators(keys: &[&SigningKey]) -> LightClientConfig {
+        LightClientConfig {
+            enabled: true,
+            validators: keys
+                .iter()
+                .map(|key| TrustedValidator {
+                    public_key: hex::encode(key.verifying_key().to_bytes()),
+                    voting_power: 10,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn header_needs_two_thirds_of_voting_power() {
+        let keys: Vec<SigningKey> = (1..=3).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+        let all: Vec<&SigningKey> = keys.iter().collect();
+        let config = validators(&all);
+        let parent = hex::encode([0u8; 32]);
+
+        let header = signed_header(5, &parent, [7; 32], &all);
+        assert!(verify_header(&header, &config.validators).is_ok());
+
+        let two_of_three = signed_header(5, &parent, [7; 32], &all[..2]);
+        assert!(verify_header(&two_of_three, &config.validators).is_err());
+
+        let mut tampered = header.clone();
+        tampered.state_root = hex::encode([8u8; 32]);
+        assert!(verify_header(&tampered, &config.validators).is_err());
+    }
+
+    #[test]
+    fn account_proof_resolves_to_the_state_root() {
+        let leaf = account_leaf("0xabc", "1000", 4);
+        let sibling_a = [1u8; 32];
+        let sibling_b = [2u8; 32];
+        let root = merkle_root(leaf, &[sibling_a, sibling_b], 0b10);
+
+        let key = SigningKey::from_bytes(&[9; 32]);
+        let header = signed_header(12, &hex::encode([0u8; 32]), root, &[&key]);
+        let mut proof = AccountProof {
+            address: "0xABC".to_string(),
+            balance: "1000".to_string(),
+            nonce: 4,
+            height: 12,
+            leaf_index: 0b10,
+            proof: vec![hex::encode(sibling_a), hex::encode(sibling_b)],
+        };
+        assert!(verify_account_proof(&proof, &header).is_ok());
+
+        proof.balance = "1000000".to_string();
+        assert!(verify_account_proof(&proof, &header).is_err());
+    }
+
+    #[test]
+    fn tracked_chain_rejects_unlinked_headers() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let client = LightClient::new(validators(&[&key]));
+        let first = signed_header(1, &hex::encode([0u8; 32]), [1; 32], &[&key]);
+        client.accept_header(first.clone()).unwrap();
+
+        let child = signed_header(2, &first.hash, [2; 32], &[&key]);
+        assert!(client.accept_header(child).is_ok());
+
+        let orphan = signed_header(2, &hex::encode([9u8; 32]), [3; 32], &[&key]);
+        assert!(client.accept_header(orphan).is_err());
+        assert_eq!(client.latest_header().unwrap().height, 2);
+
+        client.set_config(validators(&[&SigningKey::from_bytes(&[4; 32])]));
+        assert!(client.latest_header().is_none());
+    }
+
+    #[test]
+    fn proofs_must_keep_up_with_the_trusted_head() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let client = LightClient::new(validators(&[&key]));
+        let head = signed_header(100, &hex::encode([0u8; 32]), [1; 32], &[&key]);
+        client.accept_header(head).unwrap();
+
+        assert!(client.ensure_fresh(100 - MAX_PROOF_LAG).is_ok());
+        assert!(client.ensure_fresh(101).is_ok());
+        assert!(client.ensure_fresh(99 - MAX_PROOF_LAG).is_err());
+    }
+
+    #[test]
+    fn trusted_set_follows_rotations_it_vouches_for() {
+        let keys: Vec<SigningKey> = (1..=7).map(|i| SigningKey::from_bytes(&[i; 32])).collect();
+        let key = |i: usize| &keys[i - 1];
+        let client = LightClient::new(validators(&[key(1), key(2), key(3)]));
+        let parent = hex::encode([0u8; 32]);
+
+        // Two of the three pinned validators stay on and vouch for the new set.
+        let rotated = validators(&[key(2), key(3), key(4)]).validators;
+        let handover = rotated_header(10, &parent, [1; 32], &[key(2), key(3), key(4)], rotated);
+        client.accept_header(handover.clone()).unwrap();
+        assert_eq!(client.validators(), handover.validators);
+
+        let next = signed_header(11, &handover.hash, [2; 32], &[key(2), key(3), key(4)]);
+        assert!(client.accept_header(next).is_ok());
+        let old_set = signed_header(12, &parent, [3; 32], &[key(1)]);
+        assert!(client.accept_header(old_set).is_err());
+
+        let strangers = validators(&[key(5), key(6), key(7)]).validators;
+        let takeover = rotated_header(12, &parent, [3; 32], &[key(5), key(6), key(7)], strangers);
+        assert!(client.accept_header(takeover).is_err());
+    }
+}
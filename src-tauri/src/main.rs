@@ -6,46 +6,101 @@ mod api;
 mod app_state;
 mod blockchain;
 mod blockchain_client;
+mod commands;
 mod config_store;
+mod connector;
 mod crypto;
 mod errors;
+mod governance;
+mod history_export;
+mod light_client;
+mod network;
+mod proxy;
+mod quorum;
+mod realtime;
+mod rpc_batch;
+mod rpc_cache;
+mod rpc_pool;
 mod runtime;
 mod security;
 mod session;
+mod staking;
 mod storage;
+mod tls;
 mod validation;
 
 use crate::api::types::{
-    BalanceResponse, BiometricStatusResponse, BiometricUnlockRequest, BiometricUnlockResponse,
-    CastVoteRequest, CastVoteResponse, ChangePasswordRequest, ChangePasswordResponse,
-    ClaimStakingRewardsResponse, CreateLockboxStakeResponse, CreateWalletRequest,
-    CreateWalletResponse, DelegateRequest, DelegateResponse, DelegateTokensResponse,
+    AccountOverview, AddAccountRequest, AddAccountResponse, BalanceResponse, BatchDelegateRequest,
+    BatchDelegateResult, BatchVoteRequest, BatchVoteResult, BiometricStatusResponse,
+    BiometricUnlockRequest, BiometricUnlockResponse, CastVoteRequest, CastVoteResponse,
+    ChangePasswordRequest, ChangePasswordResponse, ClaimStakingRewardsResponse,
+    CompoundingAuditRequest, CreateLockboxStakeResponse, CreateProposalRequest,
+    CreateProposalResponse, CreateWalletRequest, CreateWalletResponse, DelegateRequest,
+    DelegateResponse, DelegateTokensResponse, ExecuteMaturityActionRequest,
+    ExecuteMaturityActionResponse, ExportHistoryRequest, ExportHistoryResponse,
     ExportWalletResponse, FormatAmountRequest, FormatAmountResponse, GetAutoStakeStatusResponse,
     GetDelegationsResponse, GetLockboxRecordsResponse, GetProposalResponse,
     GetProposalVotesResponse, GetProposalsRequest, GetProposalsResponse, GetStakingRewardsResponse,
     GetUserDelegationsResponse, GetValidatorsResponse, GetVotingPowerResponse, ImportWalletRequest,
-    ImportWalletResponse, LockWalletResponse, PasskeyAuthenticateRequest,
+    ImportWalletResponse, LockWalletResponse, NetworkProfilesResponse, PasskeyAuthenticateRequest,
     PasskeyAuthenticateResponse, PasskeyCreateRequest, PasskeyCreateResponse,
-    PushNotificationRegisterRequest, PushNotificationRegisterResponse,
-    PushNotificationStatusResponse, SignMessageRequest, SignMessageResponse,
-    ToggleAutoStakingResponse, TransactionHistoryResponse, UndelegateTokensResponse,
-    UnlockWalletRequest, UnlockWalletResponse, ValidateAddressRequest, ValidateAddressResponse,
-    VerifySignatureRequest, VerifySignatureResponse, WalletInfoResponse, WalletSummary,
+    PlanLockboxStakeRequest, PushNotificationRegisterRequest, PushNotificationRegisterResponse,
+    PushNotificationStatusResponse, RecommendDelegationRequest, RedelegateTokensRequest,
+    SetCompoundingPolicyRequest, SetKnownContractRequest, SignMessageRequest, SignMessageResponse,
+    StakingReportRequest, ToggleAutoStakingResponse, TransactionHistoryResponse, TransactionInfo,
+    UndelegateTokensResponse, UnlockWalletRequest, UnlockWalletResponse, ValidateAddressRequest,
+    ValidateAddressResponse, VerifySignatureRequest, VerifySignatureResponse, WalletInfoResponse,
+    WalletSummary,
 };
-use crate::app_state::{SharedWalletContext, WalletContext};
+use crate::app_state::{is_production, SharedWalletContext, WalletContext};
 use crate::blockchain::{Address, Amount};
 use crate::blockchain_client::BlockchainClient;
-use crate::crypto::{StealthKeyMaterial, WalletKeyPair};
+use crate::config_store::{
+    CompoundingPolicy, ConnectorConfig, EndpointTls, KnownContract, LightClientConfig,
+    NetworkConfig, ProxyConfig, ProxyRoute, QuorumConfig,
+};
+use crate::connector::{ConnectorStatus, DappConnector, CONNECTOR_EVENT};
 use crate::errors::WalletError;
-use crate::runtime::RuntimeSecurityState;
+use crate::governance::batch::{self, AccountDelegation};
+use crate::governance::watchlist::{
+    self, GovernanceNotification, WatchRules, GOVERNANCE_EVENT, WATCHLIST_POLL_INTERVAL,
+};
+use crate::governance::{DelegationGraph, WatchlistState};
+use crate::history_export::{export_account_history_to_file, HistoryExportOptions};
+use crate::light_client::SignedHeader;
+use crate::network::{ChainPin, NetworkProfile};
+use crate::proxy::ProxyRoutes;
+use crate::quorum::QuorumReport;
+use crate::realtime::{ChainSubscriptions, SubscriptionStatus, CHAIN_EVENT};
+use crate::rpc_batch::RpcBatch;
+use crate::rpc_cache::CacheStats;
+use crate::rpc_pool::{EndpointHealth, RpcPool, HEALTH_PROBE_INTERVAL};
+use crate::runtime::{AlertCategory, RuntimeSecurityState};
 use crate::security::init_security_config_from_env;
-use crate::storage::{VaultMetadata, VaultSecrets};
+use crate::staking::compounding::{self, COMPOUNDING_POLL_INTERVAL};
+use crate::staking::ledger::SCHEDULED_SNAPSHOT_INTERVAL;
+use crate::staking::lockbox::plan_lockbox;
+use crate::staking::maturity::{
+    self, MaturityNotification, MATURITY_EVENT, MATURITY_POLL_INTERVAL,
+};
+use crate::staking::redelegation::{self, REDELEGATION_POLL_INTERVAL};
+use crate::staking::{
+    CompoundingAuditEntry, DelegationPlan, LockboxPlan, MaturityEntry, MultiplierSchedule,
+    RedelegationPlan, SelectionCriteria, StakingReport, StakingSnapshot, ValidatorSelector,
+};
+use crate::tls::TlsStatus;
 use crate::validation::InputValidator;
 use ed25519_dalek::Signer;
 use secrecy::SecretString;
 use silica_models::crypto::{verify_signature_standalone, ChertSignature, SignatureAlgorithm};
-use tauri::Manager;
-use tauri::State;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Most accounts a dashboard overview may request at once.
+const MAX_OVERVIEW_ACCOUNTS: usize = 50;
 
 fn to_frontend_error(err: WalletError) -> String {
     err.to_string()
@@ -56,71 +111,9 @@ fn create_wallet(
     state: State<'_, SharedWalletContext>,
     request: CreateWalletRequest,
 ) -> Result<CreateWalletResponse, String> {
-    let CreateWalletRequest {
-        wallet_name,
-        password,
-        mnemonic_word_count,
-        use_post_quantum,
-    } = request;
-
-    let validator = InputValidator::default();
-    validator
-        .validate_wallet_name(&wallet_name)
-        .map_err(|e| e.to_string())?;
-    validator
-        .validate_password(&password)
-        .map_err(|e| e.to_string())?;
-
-    let password_secret = SecretString::from(password);
-    let response = state
-        .write(|ctx| {
-            if ctx.vault().exists() {
-                return Err(WalletError::AlreadyExists(
-                    ctx.vault().vault_path().display().to_string(),
-                ));
-            }
-
-            let (keypair, mnemonic) = WalletKeyPair::generate_with_mnemonic(
-                mnemonic_word_count,
-                None,
-                None,
-                use_post_quantum,
-            )?;
-
-            let stealth_keys =
-                StealthKeyMaterial::derive_from_seed(&keypair.core_keypair.private_key)?;
-
-            let secrets = VaultSecrets {
-                mnemonic_phrase: Some(mnemonic.clone()),
-                seed_bytes: keypair.core_keypair.private_key.clone(),
-                stealth_material: stealth_keys.encode(),
-                pq_material: Vec::new(),
-            };
-
-            let mut metadata = VaultMetadata::new(&wallet_name);
-            metadata.primary_address = Some(keypair.address());
-            metadata.public_key_hex = Some(keypair.public_key_hex());
-            metadata.signature_algorithm = Some(format!("{:?}", keypair.core_keypair.algorithm));
-            metadata.supports_post_quantum = Some(keypair.supports_pq);
-
-            ctx.create_vault(&password_secret, metadata, secrets)?;
-            ctx.unlock(&password_secret)?;
-
-            let metadata = ctx
-                .session()
-                .peek_unlocked(|metadata, _| Ok(metadata.clone()))?;
-            Ok(CreateWalletResponse {
-                summary: WalletSummary::from(metadata),
-                address: keypair.address(),
-                public_key: keypair.public_key_hex(),
-                mnemonic,
-                supports_post_quantum: keypair.supports_pq,
-                algorithm: format!("{:?}", keypair.core_keypair.algorithm),
-            })
-        })
-        .map_err(to_frontend_error)?;
-
-    Ok(response)
+    state
+        .write(|ctx| commands::wallet::create_wallet(ctx, request))
+        .map_err(to_frontend_error)
 }
 
 #[tauri::command]
@@ -128,60 +121,18 @@ fn import_wallet(
     state: State<'_, SharedWalletContext>,
     request: ImportWalletRequest,
 ) -> Result<ImportWalletResponse, String> {
-    let ImportWalletRequest {
-        wallet_name,
-        password,
-        mnemonic,
-        use_post_quantum,
-    } = request;
-
-    let validator = InputValidator::default();
-    validator
-        .validate_wallet_name(&wallet_name)
-        .map_err(|e| e.to_string())?;
-    validator
-        .validate_password(&password)
-        .map_err(|e| e.to_string())?;
-
-    let password_secret = SecretString::from(password);
     state
-        .write(|ctx| {
-            if ctx.vault().exists() {
-                return Err(WalletError::AlreadyExists(
-                    ctx.vault().vault_path().display().to_string(),
-                ));
-            }
-
-            let keypair = WalletKeyPair::from_mnemonic(&mnemonic, None, None, use_post_quantum)?;
-            let stealth_keys =
-                StealthKeyMaterial::derive_from_seed(&keypair.core_keypair.private_key)?;
-            let secrets = VaultSecrets {
-                mnemonic_phrase: Some(mnemonic.clone()),
-                seed_bytes: keypair.core_keypair.private_key.clone(),
-                stealth_material: stealth_keys.encode(),
-                pq_material: Vec::new(),
-            };
-
-            let mut metadata = VaultMetadata::new(&wallet_name);
-            metadata.primary_address = Some(keypair.address());
-            metadata.public_key_hex = Some(keypair.public_key_hex());
-            metadata.signature_algorithm = Some(format!("{:?}", keypair.core_keypair.algorithm));
-            metadata.supports_post_quantum = Some(keypair.supports_pq);
-
-            ctx.create_vault(&password_secret, metadata, secrets)?;
-            ctx.unlock(&password_secret)?;
+        .write(|ctx| commands::wallet::import_wallet(ctx, request))
+        .map_err(to_frontend_error)
+}
 
-            let metadata = ctx
-                .session()
-                .peek_unlocked(|metadata, _| Ok(metadata.clone()))?;
-            Ok(ImportWalletResponse {
-                summary: WalletSummary::from(metadata),
-                address: keypair.address(),
-                public_key: keypair.public_key_hex(),
-                supports_post_quantum: keypair.supports_pq,
-                algorithm: format!("{:?}", keypair.core_keypair.algorithm),
-            })
-        })
+#[tauri::command]
+fn add_wallet_account(
+    state: State<'_, SharedWalletContext>,
+    request: AddAccountRequest,
+) -> Result<AddAccountResponse, String> {
+    state
+        .write(|ctx| commands::wallet::add_wallet_account(ctx, request))
         .map_err(to_frontend_error)
 }
 
@@ -390,17 +341,32 @@ fn verify_message_signature(
     Ok(VerifySignatureResponse { valid })
 }
 
+/// Accepts hex addresses, and Bech32 addresses under the active network's
+/// prefix.
 #[tauri::command]
 fn validate_address(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     request: ValidateAddressRequest,
 ) -> Result<ValidateAddressResponse, String> {
-    let validator = InputValidator::default();
-    if validator.validate_address(&request.address).is_err() {
-        return Ok(ValidateAddressResponse { is_valid: false });
+    let address = request.address.trim();
+    if address.starts_with("0x") {
+        let validator = InputValidator::default();
+        if validator.validate_address(address).is_err() {
+            return Ok(ValidateAddressResponse { is_valid: false });
+        }
+        let valid = Address::from_string(address).is_ok();
+        return Ok(ValidateAddressResponse { is_valid: valid });
     }
 
-    let valid = Address::from_string(&request.address).is_ok();
+    let hrp = state
+        .read(|ctx| Ok(network::active_profile(&ctx.load_config()?)))
+        .map_err(to_frontend_error)?
+        .map(|profile| profile.bech32_hrp);
+    let valid = Address::from_string_with_hrp(
+        address,
+        hrp.as_deref().unwrap_or(blockchain::CHERT_BECH32_HRP),
+    )
+    .is_ok();
     Ok(ValidateAddressResponse { is_valid: valid })
 }
 
@@ -421,39 +387,34 @@ fn format_amount(
     })
 }
 
-fn resolve_rpc_endpoints(
-    state: &State<'_, SharedWalletContext>,
+/// RPC client for a command: the shared endpoint pool, or a one-off client
+/// when the caller overrides the node URL.
+fn rpc_client(
+    state: &SharedWalletContext,
     override_url: Option<String>,
-) -> Result<Vec<String>, String> {
-    if let Some(url) = override_url {
-        let trimmed = url.trim().to_string();
-        return if trimmed.is_empty() {
+) -> Result<BlockchainClient, String> {
+    match override_url {
+        Some(url) if url.trim().is_empty() => {
             Err("Override RPC endpoint cannot be empty".to_string())
-        } else {
-            Ok(vec![trimmed])
-        };
-    }
-
-    let mut endpoints = state
-        .read(|ctx| {
-            let config = ctx.load_config()?;
-            let mut resolved = Vec::with_capacity(1 + config.network.failover_endpoints.len());
-            resolved.push(config.network.primary_endpoint);
-            resolved.extend(config.network.failover_endpoints);
-            Ok::<Vec<String>, WalletError>(resolved)
-        })
-        .map_err(to_frontend_error)?;
-
-    endpoints.retain(|url| !url.trim().is_empty());
-    if endpoints.is_empty() {
-        return Err("No RPC endpoints configured".to_string());
-    }
-
-    for endpoint in &mut endpoints {
-        *endpoint = endpoint.trim().to_string();
+        }
+        // Overrides keep the configured certificate trust, proxy routes and
+        // chain pin. They bypass the response cache but their writes still
+        // invalidate it.
+        Some(url) => state
+            .read(|ctx| {
+                let pooled = ctx.rpc_client();
+                Ok(BlockchainClient::new(url.trim().to_string())?
+                    .with_tls(pooled.tls_config())?
+                    .with_proxy(ProxyRoutes::clone(&pooled.proxy_routes()))?
+                    .with_cache(pooled.cache().invalidation_only())
+                    .with_rate_limiter(pooled.rate_limiter().clone())
+                    .with_chain_pin(pooled.chain_pin().clone()))
+            })
+            .map_err(to_frontend_error),
+        None => state
+            .read(|ctx| Ok(ctx.rpc_client().clone()))
+            .map_err(to_frontend_error),
     }
-
-    Ok(endpoints)
 }
 
 #[tauri::command]
@@ -467,28 +428,31 @@ async fn get_balance(
         .validate_address(&address)
         .map_err(|e| e.to_string())?;
 
-    let endpoints = resolve_rpc_endpoints(&state, node_url)?;
-    assert!(!endpoints.is_empty(), "RPC endpoint list must not be empty");
-
-    let mut last_error: Option<String> = None;
-    for endpoint in endpoints {
-        let client = match BlockchainClient::new(endpoint) {
-            Ok(client) => client,
-            Err(err) => {
-                last_error = Some(to_frontend_error(err));
-                continue;
-            }
-        };
+    let client = rpc_client(&state, node_url)?;
+    client
+        .get_balance(&address)
+        .await
+        .map_err(to_frontend_error)
+}
 
-        match client.get_balance(&address).await {
-            Ok(result) => return Ok(result),
-            Err(err) => {
-                last_error = Some(to_frontend_error(err));
-            }
-        }
+#[tauri::command]
+async fn get_transaction(
+    state: State<'_, SharedWalletContext>,
+    tx_id: String,
+    node_url: Option<String>,
+) -> Result<TransactionInfo, String> {
+    let tx_id = tx_id.trim();
+    if tx_id.is_empty() {
+        return Err("Transaction ID is required".to_string());
     }
 
-    Err(last_error.unwrap_or_else(|| "Failed to fetch balance from all RPC endpoints".to_string()))
+    let client = rpc_client(&state, node_url)?;
+    let mut transaction = client
+        .get_transaction(tx_id)
+        .await
+        .map_err(to_frontend_error)?;
+    link_to_explorer(&state, std::slice::from_mut(&mut transaction))?;
+    Ok(transaction)
 }
 
 #[tauri::command]
@@ -504,212 +468,919 @@ async fn get_transaction_history(
         .validate_address(&address)
         .map_err(|e| e.to_string())?;
 
-    let endpoints = resolve_rpc_endpoints(&state, node_url)?;
-    assert!(!endpoints.is_empty(), "RPC endpoint list must not be empty");
-
-    let mut last_error: Option<String> = None;
-    for endpoint in endpoints {
-        let client = match BlockchainClient::new(endpoint) {
-            Ok(client) => client,
-            Err(err) => {
-                last_error = Some(to_frontend_error(err));
-                continue;
-            }
-        };
+    let client = rpc_client(&state, node_url)?;
+    let mut history = client
+        .get_transaction_history(&address, limit, offset)
+        .await
+        .map_err(to_frontend_error)?;
+    link_to_explorer(&state, &mut history.transactions)?;
+    Ok(history)
+}
 
-        match client
-            .get_transaction_history(&address, limit, offset)
-            .await
-        {
-            Ok(result) => return Ok(result),
-            Err(err) => {
-                last_error = Some(to_frontend_error(err));
-            }
+/// Fill in explorer links from the active network profile.
+fn link_to_explorer(
+    state: &SharedWalletContext,
+    transactions: &mut [TransactionInfo],
+) -> Result<(), String> {
+    let profile = state
+        .read(|ctx| Ok(network::active_profile(&ctx.load_config()?)))
+        .map_err(to_frontend_error)?;
+    if let Some(profile) = profile {
+        for transaction in transactions {
+            transaction.explorer_url = Some(profile.explorer_link(&transaction.transaction_id));
         }
     }
-
-    Err(last_error.unwrap_or_else(|| {
-        "Failed to fetch transaction history from all RPC endpoints".to_string()
-    }))
+    Ok(())
 }
 
-// Staking commands
 #[tauri::command]
-async fn get_validators(
-    _state: State<'_, SharedWalletContext>,
-) -> Result<GetValidatorsResponse, String> {
-    let client = BlockchainClient::default();
-    client.get_validators().await.map_err(|e| e.to_string())
-}
+async fn export_transaction_history(
+    state: State<'_, SharedWalletContext>,
+    request: ExportHistoryRequest,
+) -> Result<ExportHistoryResponse, String> {
+    let ExportHistoryRequest {
+        address,
+        format,
+        destination,
+        from,
+        to,
+        fiat,
+        node_url,
+    } = request;
 
-#[tauri::command]
-async fn get_user_delegations(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<GetUserDelegationsResponse, String> {
-    let user_address = request
-        .get("userAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing userAddress parameter")?;
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&address)
+        .map_err(|e| e.to_string())?;
 
-    let client = BlockchainClient::default();
-    client
-        .get_user_delegations(user_address)
+    let destination = PathBuf::from(destination.trim());
+    if !destination.is_absolute() {
+        return Err("Export destination must be an absolute path".to_string());
+    }
+    let client = rpc_client(&state, node_url)?;
+    let mut options = HistoryExportOptions::new(format);
+    options.from = from;
+    options.to = to;
+    options.fiat = fiat;
+
+    let rows_written = export_account_history_to_file(&client, &address, options, &destination)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(to_frontend_error)?;
+    Ok(ExportHistoryResponse {
+        path: destination.display().to_string(),
+        format,
+        rows_written,
+    })
 }
 
+/// Health of each configured RPC endpoint, for the settings page.
 #[tauri::command]
-async fn get_staking_rewards(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<GetStakingRewardsResponse, String> {
-    let user_address = request
-        .get("userAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing userAddress parameter")?;
+async fn get_rpc_health(
+    state: State<'_, SharedWalletContext>,
+) -> Result<Vec<EndpointHealth>, String> {
+    let client = rpc_client(&state, None)?;
+    Ok(client.pool().health())
+}
 
-    let client = BlockchainClient::default();
-    client
-        .get_staking_rewards(user_address)
-        .await
-        .map_err(|e| e.to_string())
+/// Response cache and per-endpoint throttling counters, for debugging.
+#[tauri::command]
+async fn get_rpc_cache_stats(state: State<'_, SharedWalletContext>) -> Result<CacheStats, String> {
+    let client = rpc_client(&state, None)?;
+    Ok(client.cache_stats())
 }
 
 #[tauri::command]
-async fn get_lockbox_records(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<GetLockboxRecordsResponse, String> {
-    let user_address = request
-        .get("userAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing userAddress parameter")?;
+async fn clear_rpc_cache(state: State<'_, SharedWalletContext>) -> Result<CacheStats, String> {
+    let client = rpc_client(&state, None)?;
+    client.cache().clear();
+    Ok(client.cache_stats())
+}
 
-    let client = BlockchainClient::default();
-    client
-        .get_lockbox_records(user_address)
-        .await
-        .map_err(|e| e.to_string())
+#[tauri::command]
+async fn get_quorum_config(state: State<'_, SharedWalletContext>) -> Result<QuorumConfig, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.network.quorum))
+        .map_err(to_frontend_error)
 }
 
 #[tauri::command]
-async fn get_auto_stake_status(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<GetAutoStakeStatusResponse, String> {
-    let user_address = request
-        .get("userAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing userAddress parameter")?;
+async fn set_quorum_config(
+    state: State<'_, SharedWalletContext>,
+    config: QuorumConfig,
+) -> Result<QuorumConfig, String> {
+    state
+        .read(|ctx| {
+            let updated =
+                ctx.config_store()
+                    .update(ctx.environment().to_string(), |wallet_config| {
+                        let endpoints = rpc_pool::endpoint_urls(&wallet_config.network);
+                        quorum::validate_config(&config, endpoints.len())?;
+                        wallet_config.network.quorum = config;
+                        Ok(())
+                    })?;
+            ctx.rpc_client()
+                .quorum()
+                .set_config(updated.network.quorum.clone());
+            Ok(updated.network.quorum)
+        })
+        .map_err(to_frontend_error)
+}
 
-    let client = BlockchainClient::default();
-    client
-        .get_auto_stake_status(user_address)
-        .await
-        .map_err(|e| e.to_string())
+/// Recent cross-checks where endpoints disagreed or too few answered.
+#[tauri::command]
+async fn get_quorum_reports(
+    state: State<'_, SharedWalletContext>,
+) -> Result<Vec<QuorumReport>, String> {
+    let client = rpc_client(&state, None)?;
+    Ok(client.quorum().reports())
 }
 
 #[tauri::command]
-async fn delegate_tokens(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<DelegateTokensResponse, String> {
-    let delegator_address = request
-        .get("delegatorAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing delegatorAddress parameter")?;
-    let validator_address = request
-        .get("validatorAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing validatorAddress parameter")?;
-    let amount = request
-        .get("amount")
-        .and_then(|v| v.as_u64())
-        .ok_or("Missing or invalid amount parameter")?;
+async fn get_light_client_config(
+    state: State<'_, SharedWalletContext>,
+) -> Result<LightClientConfig, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.network.light_client))
+        .map_err(to_frontend_error)
+}
 
-    let client = BlockchainClient::default();
-    client
-        .delegate_tokens(delegator_address, validator_address, amount)
-        .await
-        .map_err(|e| e.to_string())
+#[tauri::command]
+async fn set_light_client_config(
+    state: State<'_, SharedWalletContext>,
+    config: LightClientConfig,
+) -> Result<LightClientConfig, String> {
+    light_client::validate_config(&config).map_err(to_frontend_error)?;
+    if config.enabled {
+        log::warn!("Light-client verification is experimental; its results are advisory");
+    }
+    state
+        .read(|ctx| {
+            let updated =
+                ctx.config_store()
+                    .update(ctx.environment().to_string(), |wallet_config| {
+                        wallet_config.network.light_client = config;
+                        Ok(())
+                    })?;
+            ctx.rpc_client()
+                .light_client()
+                .set_config(updated.network.light_client.clone());
+            Ok(updated.network.light_client)
+        })
+        .map_err(to_frontend_error)
 }
 
+/// Newest header verified against the trusted validator set, if any.
 #[tauri::command]
-async fn undelegate_tokens(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<UndelegateTokensResponse, String> {
-    let delegator_address = request
-        .get("delegatorAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing delegatorAddress parameter")?;
-    let validator_address = request
-        .get("validatorAddress")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing validatorAddress parameter")?;
-    let amount = request
-        .get("amount")
-        .and_then(|v| v.as_u64())
-        .ok_or("Missing or invalid amount parameter")?;
+async fn get_trusted_header(
+    state: State<'_, SharedWalletContext>,
+) -> Result<Option<SignedHeader>, String> {
+    let client = rpc_client(&state, None)?;
+    Ok(client.light_client().latest_header())
+}
 
-    let client = BlockchainClient::default();
-    client
-        .undelegate_tokens(delegator_address, validator_address, amount)
-        .await
-        .map_err(|e| e.to_string())
+fn network_profiles(ctx: &WalletContext) -> Result<NetworkProfilesResponse, WalletError> {
+    let config = ctx.load_config()?;
+    Ok(NetworkProfilesResponse {
+        profiles: network::all_profiles(&config.custom_networks),
+        active: config.network.profile,
+        chain_id: network::pinned_chain(&config),
+    })
 }
 
 #[tauri::command]
-async fn create_lockbox_stake(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<CreateLockboxStakeResponse, String> {
-    let account = request
-        .get("account")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing account parameter")?;
-    let amount = request
-        .get("amount")
-        .and_then(|v| v.as_u64())
-        .ok_or("Missing or invalid amount parameter")?;
-    let term_months = request
-        .get("termMonths")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as u32)
-        .ok_or("Missing or invalid termMonths parameter")?;
-
-    let client = BlockchainClient::default();
-    client
-        .create_lockbox_stake(account, amount, term_months)
-        .await
-        .map_err(|e| e.to_string())
+async fn get_network_profiles(
+    state: State<'_, SharedWalletContext>,
+) -> Result<NetworkProfilesResponse, String> {
+    state.read(network_profiles).map_err(to_frontend_error)
 }
 
+/// Switch endpoints to the profile `name` and pin its chain id. A node that
+/// answers with another chain id blocks the switch; unreachable nodes do not,
+/// so a local network can be selected before it is started. Built-in
+/// profiles pin the chain their node reports, or stay unpinned until it can
+/// be reached.
 #[tauri::command]
-async fn toggle_auto_staking(
-    _state: State<'_, SharedWalletContext>,
-    request: serde_json::Value,
-) -> Result<ToggleAutoStakingResponse, String> {
-    let account = request
-        .get("account")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing account parameter")?;
+async fn select_network_profile(
+    state: State<'_, SharedWalletContext>,
+    name: String,
+) -> Result<NetworkProfilesResponse, String> {
+    let custom = state
+        .read(|ctx| Ok(ctx.load_config()?.custom_networks))
+        .map_err(to_frontend_error)?;
+    let profile = network::find_profile(&name, &custom).map_err(to_frontend_error)?;
+
+    let (tls_config, proxy) = state
+        .read(|ctx| {
+            Ok((
+                ctx.rpc_client().tls_config(),
+                ctx.rpc_client().proxy_routes(),
+            ))
+        })
+        .map_err(to_frontend_error)?;
+    let candidate = BlockchainClient::with_pool(
+        RpcPool::new(profile.endpoints.clone()).map_err(to_frontend_error)?,
+    )
+    .and_then(|client| client.with_tls(tls_config))
+    .and_then(|client| client.with_proxy(ProxyRoutes::clone(&proxy)))
+    .map_err(to_frontend_error)?
+    .with_chain_pin(ChainPin::new(profile.chain_id.clone()));
+    let chain_id = match candidate.verify_chain().await {
+        Ok(reported) => Some(reported),
+        Err(err @ WalletError::ChainMismatch(_)) => return Err(to_frontend_error(err)),
+        Err(err) => {
+            log::warn!("Could not confirm chain of '{}': {}", profile.name, err);
+            profile.chain_id.clone()
+        }
+    };
+
+    state
+        .read(|ctx| {
+            let updated =
+                ctx.config_store()
+                    .update(ctx.environment().to_string(), |wallet_config| {
+                        let (primary, failovers) =
+                            profile.endpoints.split_first().ok_or_else(|| {
+                                WalletError::ValidationError("Profile has no endpoints".to_string())
+                            })?;
+                        wallet_config.network.primary_endpoint = primary.clone();
+                        wallet_config.network.failover_endpoints = failovers.to_vec();
+                        wallet_config.network.profile = Some(profile.name.clone());
+                        wallet_config.network.chain_id = chain_id.clone();
+                        Ok(())
+                    })?;
+            ctx.rpc_client()
+                .pool()
+                .set_endpoints(rpc_pool::endpoint_urls(&updated.network))?;
+            ctx.rpc_client().cache().clear();
+            ctx.rpc_client()
+                .chain_pin()
+                .set_expected(updated.network.chain_id.clone());
+            network_profiles(ctx)
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Add or replace a user-defined network profile.
+#[tauri::command]
+async fn save_network_profile(
+    state: State<'_, SharedWalletContext>,
+    profile: NetworkProfile,
+) -> Result<NetworkProfilesResponse, String> {
+    network::validate_profile(&profile).map_err(to_frontend_error)?;
+    let profile = NetworkProfile {
+        name: profile.name.trim().to_string(),
+        builtin: false,
+        ..profile
+    };
+    state
+        .read(|ctx| {
+            ctx.config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    if config.network.profile.as_deref() == Some(profile.name.as_str()) {
+                        return Err(WalletError::ValidationError(
+                            "Select another network before editing the active one".to_string(),
+                        ));
+                    }
+                    config
+                        .custom_networks
+                        .retain(|existing| !existing.name.eq_ignore_ascii_case(&profile.name));
+                    config.custom_networks.push(profile);
+                    Ok(())
+                })?;
+            network_profiles(ctx)
+        })
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn remove_network_profile(
+    state: State<'_, SharedWalletContext>,
+    name: String,
+) -> Result<NetworkProfilesResponse, String> {
+    state
+        .read(|ctx| {
+            ctx.config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    if config
+                        .network
+                        .profile
+                        .as_deref()
+                        .is_some_and(|active| active.eq_ignore_ascii_case(&name))
+                    {
+                        return Err(WalletError::ValidationError(
+                            "Cannot remove the active network".to_string(),
+                        ));
+                    }
+                    config
+                        .custom_networks
+                        .retain(|profile| !profile.name.eq_ignore_ascii_case(&name));
+                    Ok(())
+                })?;
+            network_profiles(ctx)
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Ask the node which chain it is on; fails if it is not the pinned one.
+#[tauri::command]
+async fn verify_network(state: State<'_, SharedWalletContext>) -> Result<String, String> {
+    let client = rpc_client(&state, None)?;
+    client.verify_chain().await.map_err(to_frontend_error)
+}
+
+/// Queried by the app shell on load to show the TLS warning banner.
+#[tauri::command]
+async fn get_tls_status(state: State<'_, SharedWalletContext>) -> Result<TlsStatus, String> {
+    state
+        .read(|ctx| Ok(tls::status(&ctx.load_config()?.network, is_production())))
+        .map_err(to_frontend_error)
+}
+
+/// Save TLS settings through `apply` and rebuild the pooled client with them.
+fn update_tls_settings(
+    state: &SharedWalletContext,
+    apply: impl FnOnce(&mut NetworkConfig),
+) -> Result<TlsStatus, String> {
+    let production = is_production();
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    apply(&mut config.network);
+                    tls::validate(&config.network, production)
+                })?;
+            ctx.rpc_client()
+                .set_tls(tls::client_config(&updated.network, production)?)?;
+            Ok(tls::status(&updated.network, production))
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Pin keys or set a CA bundle for `endpoint`; `None` restores web PKI checks.
+#[tauri::command]
+async fn set_endpoint_tls(
+    state: State<'_, SharedWalletContext>,
+    endpoint: String,
+    settings: Option<EndpointTls>,
+) -> Result<TlsStatus, String> {
+    let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+    update_tls_settings(&state, |network| match settings {
+        Some(settings) => {
+            network.endpoint_tls.insert(endpoint, settings);
+        }
+        None => {
+            network.endpoint_tls.remove(&endpoint);
+        }
+    })
+}
+
+/// Disable certificate checks for every endpoint. Refused in production.
+#[tauri::command]
+async fn set_allow_untrusted_certs(
+    state: State<'_, SharedWalletContext>,
+    allow: bool,
+) -> Result<TlsStatus, String> {
+    update_tls_settings(&state, |network| network.allow_untrusted_certs = allow)
+}
+
+#[tauri::command]
+async fn get_proxy_config(state: State<'_, SharedWalletContext>) -> Result<ProxyConfig, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.network.proxy))
+        .map_err(to_frontend_error)
+}
+
+/// Save proxy settings through `apply` and reroute the pooled client.
+fn update_proxy_settings(
+    state: &SharedWalletContext,
+    apply: impl FnOnce(&mut ProxyConfig),
+) -> Result<ProxyConfig, String> {
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    apply(&mut config.network.proxy);
+                    ProxyRoutes::from_config(&config.network.proxy).map(|_| ())
+                })?;
+            ctx.rpc_client()
+                .set_proxy(ProxyRoutes::from_config(&updated.network.proxy)?)?;
+            Ok(updated.network.proxy)
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Route RPC and WebSocket traffic through `url` (e.g. a local Tor daemon at
+/// `socks5h://127.0.0.1:9050`); `None` connects directly. Strict mode refuses
+/// any connection that would not go through a proxy.
+#[tauri::command]
+async fn set_proxy(
+    state: State<'_, SharedWalletContext>,
+    url: Option<String>,
+    strict: bool,
+) -> Result<ProxyConfig, String> {
+    let url = url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    update_proxy_settings(&state, |proxy| {
+        proxy.url = url;
+        proxy.strict = strict;
+    })
+}
+
+/// Use `route` for `endpoint` instead of the default proxy; `None` removes
+/// the override.
+#[tauri::command]
+async fn set_endpoint_proxy(
+    state: State<'_, SharedWalletContext>,
+    endpoint: String,
+    route: Option<ProxyRoute>,
+) -> Result<ProxyConfig, String> {
+    let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+    update_proxy_settings(&state, |proxy| match route {
+        Some(route) => {
+            proxy.endpoints.insert(endpoint, route);
+        }
+        None => {
+            proxy.endpoints.remove(&endpoint);
+        }
+    })
+}
+
+/// Refresh block heights and reachability of every pooled endpoint.
+async fn probe_rpc_endpoints(app: &AppHandle) -> Result<(), String> {
+    let client = rpc_client(&app.state::<SharedWalletContext>(), None)?;
+    client.probe_endpoints().await;
+    if client.chain_pin().expected().is_some() {
+        if let Err(err) = client.verify_chain().await {
+            log::warn!("Pinned chain could not be confirmed: {}", err);
+        }
+    }
+    Ok(())
+}
+
+/// Push balance and nonce changes of `address` as chain events.
+#[tauri::command]
+async fn subscribe_account(
+    subscriptions: State<'_, ChainSubscriptions>,
+    address: String,
+) -> Result<SubscriptionStatus, String> {
+    InputValidator::default()
+        .validate_address(&address)
+        .map_err(|e| e.to_string())?;
+    subscriptions.add_account(&address);
+    Ok(subscriptions.status())
+}
+
+#[tauri::command]
+async fn unsubscribe_account(
+    subscriptions: State<'_, ChainSubscriptions>,
+    address: String,
+) -> Result<SubscriptionStatus, String> {
+    subscriptions.remove_account(&address);
+    Ok(subscriptions.status())
+}
+
+/// Report status changes of `tx_id` until it is included in a block.
+#[tauri::command]
+async fn watch_transaction(
+    subscriptions: State<'_, ChainSubscriptions>,
+    tx_id: String,
+) -> Result<SubscriptionStatus, String> {
+    if tx_id.trim().is_empty() {
+        return Err("Transaction id is required".to_string());
+    }
+    subscriptions.watch_transaction(tx_id.trim());
+    Ok(subscriptions.status())
+}
+
+#[tauri::command]
+async fn get_chain_subscriptions(
+    subscriptions: State<'_, ChainSubscriptions>,
+) -> Result<SubscriptionStatus, String> {
+    Ok(subscriptions.status())
+}
+
+#[tauri::command]
+async fn get_connector_status(
+    connector: State<'_, DappConnector>,
+) -> Result<ConnectorStatus, String> {
+    Ok(connector.status())
+}
+
+#[tauri::command]
+async fn get_connector_config(
+    state: State<'_, SharedWalletContext>,
+) -> Result<ConnectorConfig, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.connector))
+        .map_err(to_frontend_error)
+}
+
+/// Turn the local dApp connector on or off, or move it to another port.
+#[tauri::command]
+async fn set_connector_settings(
+    state: State<'_, SharedWalletContext>,
+    connector: State<'_, DappConnector>,
+    enabled: bool,
+    port: u16,
+) -> Result<ConnectorConfig, String> {
+    if port < 1024 {
+        return Err("Connector port must be 1024 or higher".to_string());
+    }
+    let updated = update_connector_config(&state, |settings| {
+        settings.enabled = enabled;
+        settings.port = port;
+    })?;
+    connector.reload();
+    Ok(updated)
+}
+
+/// Forget a connected site; its token stops working immediately.
+#[tauri::command]
+async fn revoke_connector_grant(
+    state: State<'_, SharedWalletContext>,
+    origin: String,
+) -> Result<ConnectorConfig, String> {
+    update_connector_config(&state, |settings| {
+        settings.grants.remove(origin.trim());
+    })
+}
+
+/// Answer a dApp request shown by the approval prompt.
+#[tauri::command]
+async fn resolve_connector_request(
+    connector: State<'_, DappConnector>,
+    id: String,
+    approve: bool,
+) -> Result<ConnectorStatus, String> {
+    connector.resolve(&id, approve).map_err(to_frontend_error)?;
+    Ok(connector.status())
+}
+
+/// Persist connector settings without resetting the session or RPC clients,
+/// so connecting a site never locks the wallet.
+fn update_connector_config(
+    state: &SharedWalletContext,
+    apply: impl FnOnce(&mut ConnectorConfig),
+) -> Result<ConnectorConfig, String> {
+    state
+        .read(|ctx| {
+            ctx.config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    apply(&mut config.connector);
+                    Ok(())
+                })
+        })
+        .map(|config| config.connector)
+        .map_err(to_frontend_error)
+}
+
+// Staking commands
+#[tauri::command]
+async fn get_validators(
+    state: State<'_, SharedWalletContext>,
+) -> Result<GetValidatorsResponse, String> {
+    let client = rpc_client(&state, None)?;
+    client.get_validators().await.map_err(|e| e.to_string())
+}
+
+/// Load balances and staking positions for several accounts in one round trip.
+#[tauri::command]
+async fn get_account_overviews(
+    state: State<'_, SharedWalletContext>,
+    accounts: Vec<String>,
+) -> Result<Vec<AccountOverview>, String> {
+    if accounts.is_empty() || accounts.len() > MAX_OVERVIEW_ACCOUNTS {
+        return Err(format!(
+            "Overviews need 1-{} accounts",
+            MAX_OVERVIEW_ACCOUNTS
+        ));
+    }
+    let validator = InputValidator::default();
+    for account in &accounts {
+        validator
+            .validate_address(account)
+            .map_err(|e| e.to_string())?;
+    }
+
+    fn keep<T>(errors: &mut Vec<String>, result: Result<T, WalletError>) -> Option<T> {
+        result.map_err(|err| errors.push(err.to_string())).ok()
+    }
+
+    let mut batch = RpcBatch::new();
+    let handles: Vec<_> = accounts
+        .iter()
+        .map(|account| {
+            (
+                batch.get_balance(account),
+                batch.get_user_delegations(account),
+                batch.get_staking_rewards(account),
+                batch.get_lockbox_records(account),
+                batch.get_auto_stake_status(account),
+            )
+        })
+        .collect();
+
+    let client = rpc_client(&state, None)?;
+    let response = client.send_batch(&batch).await.map_err(to_frontend_error)?;
+
+    Ok(accounts
+        .into_iter()
+        .zip(handles)
+        .map(
+            |(address, (balance, delegations, rewards, lockbox, auto_stake))| {
+                let mut errors = Vec::new();
+                let balance = keep(&mut errors, response.get(balance));
+                let delegations = keep(
+                    &mut errors,
+                    response.get(delegations).map(|r| r.delegations),
+                );
+                let rewards = keep(&mut errors, response.get(rewards).map(|r| r.rewards));
+                let lockbox_records = keep(&mut errors, response.get(lockbox).map(|r| r.records));
+                let auto_stake = keep(&mut errors, response.get(auto_stake).map(|r| r.status));
+                AccountOverview {
+                    address,
+                    balance,
+                    delegations: delegations.unwrap_or_default(),
+                    rewards,
+                    lockbox_records: lockbox_records.unwrap_or_default(),
+                    auto_stake,
+                    errors,
+                }
+            },
+        )
+        .collect())
+}
+
+#[tauri::command]
+async fn get_user_delegations(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<GetUserDelegationsResponse, String> {
+    let user_address = request
+        .get("userAddress")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing userAddress parameter")?;
+
+    let client = rpc_client(&state, None)?;
+    client
+        .get_user_delegations(user_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_staking_rewards(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<GetStakingRewardsResponse, String> {
+    let user_address = request
+        .get("userAddress")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing userAddress parameter")?;
+
+    let client = rpc_client(&state, None)?;
+    client
+        .get_staking_rewards(user_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_lockbox_records(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<GetLockboxRecordsResponse, String> {
+    let user_address = request
+        .get("userAddress")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing userAddress parameter")?;
+
+    let client = rpc_client(&state, None)?;
+    client
+        .get_lockbox_records(user_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_auto_stake_status(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<GetAutoStakeStatusResponse, String> {
+    let user_address = request
+        .get("userAddress")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing userAddress parameter")?;
+
+    let client = rpc_client(&state, None)?;
+    client
+        .get_auto_stake_status(user_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delegate_tokens(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<DelegateTokensResponse, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::staking::delegate_tokens(&ctx, &request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn recommend_delegation_split(
+    state: State<'_, SharedWalletContext>,
+    request: RecommendDelegationRequest,
+) -> Result<DelegationPlan, String> {
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&request.delegator_address)
+        .map_err(|e| e.to_string())?;
+
+    let defaults = SelectionCriteria::default();
+    let criteria = SelectionCriteria {
+        max_validators: request.max_validators.unwrap_or(defaults.max_validators),
+        max_commission: request.max_commission,
+        min_reputation: request.min_reputation,
+        ..defaults
+    };
+
+    let client = rpc_client(&state, None)?;
+    let (validators, delegations) = tokio::try_join!(
+        client.get_validators(),
+        client.get_user_delegations(&request.delegator_address),
+    )
+    .map_err(|e| e.to_string())?;
+
+    ValidatorSelector::new(criteria)
+        .recommend(
+            &request.delegator_address,
+            request.amount,
+            &validators.validators,
+            &delegations.delegations,
+            chrono::Utc::now(),
+        )
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn undelegate_tokens(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<UndelegateTokensResponse, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::staking::undelegate_tokens(&ctx, &request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn redelegate_tokens(
+    state: State<'_, SharedWalletContext>,
+    request: RedelegateTokensRequest,
+) -> Result<RedelegationPlan, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::staking::redelegate_tokens(&ctx, request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn resume_redelegation(
+    state: State<'_, SharedWalletContext>,
+    plan_id: String,
+) -> Result<RedelegationPlan, String> {
+    let store = state
+        .read(|ctx| Ok(ctx.redelegations().clone()))
+        .map_err(to_frontend_error)?;
+    let mut plan = store.load(&plan_id).map_err(to_frontend_error)?;
+
+    let client = rpc_client(&state, None)?;
+    redelegation::advance(&client, &store, &mut plan)
+        .await
+        .map_err(to_frontend_error)?;
+    Ok(plan)
+}
+
+#[tauri::command]
+async fn get_redelegation_plans(
+    state: State<'_, SharedWalletContext>,
+) -> Result<Vec<RedelegationPlan>, String> {
+    state
+        .read(|ctx| ctx.redelegations().list())
+        .map_err(to_frontend_error)
+}
+
+/// Advance redelegation plans that are waiting for unbonding to complete.
+/// Failed plans stay put until the user resumes them.
+async fn resume_pending_redelegations(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedWalletContext>();
+    let store = state
+        .read(|ctx| Ok(ctx.redelegations().clone()))
+        .map_err(to_frontend_error)?;
+    let plans = store.awaiting_unbonding().map_err(to_frontend_error)?;
+    if plans.is_empty() {
+        return Ok(());
+    }
+
+    let client = rpc_client(&state, None)?;
+    for mut plan in plans {
+        if let Err(err) = redelegation::advance(&client, &store, &mut plan).await {
+            log::warn!("Redelegation plan {} did not advance: {}", plan.id, err);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn create_lockbox_stake(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<CreateLockboxStakeResponse, String> {
+    let account = request
+        .get("account")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing account parameter")?;
+    let amount = request
+        .get("amount")
+        .and_then(|v| v.as_u64())
+        .ok_or("Missing or invalid amount parameter")?;
+    let term_months = request
+        .get("termMonths")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or("Missing or invalid termMonths parameter")?;
+
+    let client = rpc_client(&state, None)?;
+    client
+        .create_lockbox_stake(account, amount, term_months)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn plan_lockbox_stake(
+    state: State<'_, SharedWalletContext>,
+    request: PlanLockboxStakeRequest,
+) -> Result<LockboxPlan, String> {
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&request.account)
+        .map_err(|e| e.to_string())?;
+
+    let client = rpc_client(&state, None)?;
+    let (balance, rewards, lockbox, params) = tokio::try_join!(
+        client.get_balance(&request.account),
+        client.get_staking_rewards(&request.account),
+        client.get_lockbox_records(&request.account),
+        client.get_staking_params(),
+    )
+    .map_err(to_frontend_error)?;
+    let spendable = balance.spendable().map_err(to_frontend_error)?;
+    let published = params
+        .map(|response| response.params.lockbox_multipliers)
+        .unwrap_or_default();
+
+    plan_lockbox(
+        &request,
+        rewards.rewards.current_apy,
+        &spendable,
+        &MultiplierSchedule::from_chain(&published, &lockbox.records),
+        chrono::Utc::now(),
+    )
+    .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn toggle_auto_staking(
+    state: State<'_, SharedWalletContext>,
+    request: serde_json::Value,
+) -> Result<ToggleAutoStakingResponse, String> {
+    let account = request
+        .get("account")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing account parameter")?;
     let enable = request
         .get("enable")
         .and_then(|v| v.as_bool())
         .ok_or("Missing or invalid enable parameter")?;
 
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client
         .toggle_auto_staking(account, enable)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(to_frontend_error)
 }
 
 #[tauri::command]
 async fn claim_staking_rewards(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     request: serde_json::Value,
 ) -> Result<ClaimStakingRewardsResponse, String> {
     let account = request
@@ -717,11 +1388,244 @@ async fn claim_staking_rewards(
         .and_then(|v| v.as_str())
         .ok_or("Missing account parameter")?;
 
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client
         .claim_staking_rewards(account)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn get_staking_maturities(
+    state: State<'_, SharedWalletContext>,
+) -> Result<Vec<MaturityEntry>, String> {
+    state
+        .read(|ctx| ctx.maturity_tracker().entries())
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn execute_maturity_action(
+    state: State<'_, SharedWalletContext>,
+    request: ExecuteMaturityActionRequest,
+) -> Result<ExecuteMaturityActionResponse, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::staking::execute_maturity_action(&ctx, request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+/// Refresh maturity tracking for every staking account and notify on new maturities.
+async fn scan_staking_maturities(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedWalletContext>();
+    let (tracker, accounts) = state
+        .read(|ctx| Ok((ctx.maturity_tracker().clone(), ctx.staking_accounts()?)))
+        .map_err(to_frontend_error)?;
+
+    let client = rpc_client(&state, None)?;
+    for account in accounts {
+        let entries = match maturity::fetch_entries(&client, &account).await {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::warn!("Failed to fetch staking positions for {}: {}", account, err);
+                continue;
+            }
+        };
+        let matured = tracker
+            .sync_account(&account, entries, chrono::Utc::now())
+            .map_err(to_frontend_error)?;
+        if matured.is_empty() {
+            continue;
+        }
+
+        let push_registrations = app
+            .state::<RuntimeSecurityState>()
+            .push_subscribers(AlertCategory::Staking);
+        for entry in matured {
+            let notification = MaturityNotification {
+                entry,
+                push_registrations: push_registrations.clone(),
+            };
+            if let Err(err) = app.emit(MATURITY_EVENT, &notification) {
+                log::warn!("Failed to emit staking maturity event: {}", err);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_compounding_policies(
+    state: State<'_, SharedWalletContext>,
+) -> Result<BTreeMap<String, CompoundingPolicy>, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.staking.compounding))
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn set_compounding_policy(
+    state: State<'_, SharedWalletContext>,
+    request: SetCompoundingPolicyRequest,
+) -> Result<BTreeMap<String, CompoundingPolicy>, String> {
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&request.account)
+        .map_err(|e| e.to_string())?;
+    if let Some(target) = &request.policy.target_validator {
+        validator
+            .validate_address(target)
+            .map_err(|e| e.to_string())?;
+    }
+    request.policy.validate().map_err(to_frontend_error)?;
+
+    // Written through the store directly: `update_config` rebuilds the session
+    // manager, which would lock the wallet on every policy change.
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    config
+                        .staking
+                        .compounding
+                        .insert(request.account.to_ascii_lowercase(), request.policy);
+                    Ok(())
+                })?;
+            Ok(updated.staking.compounding)
+        })
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn remove_compounding_policy(
+    state: State<'_, SharedWalletContext>,
+    account: String,
+) -> Result<BTreeMap<String, CompoundingPolicy>, String> {
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    config
+                        .staking
+                        .compounding
+                        .remove(&account.to_ascii_lowercase());
+                    Ok(())
+                })?;
+            Ok(updated.staking.compounding)
+        })
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn get_compounding_audit(
+    state: State<'_, SharedWalletContext>,
+    request: CompoundingAuditRequest,
+) -> Result<Vec<CompoundingAuditEntry>, String> {
+    state
+        .read(|ctx| {
+            ctx.compounding_audit()
+                .entries(request.account.as_deref(), request.limit)
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Run due compounding policies. Does nothing while the wallet is locked.
+async fn run_compounding_policies(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedWalletContext>();
+    let (unlocked, policies, audit) = state
+        .read(|ctx| {
+            let unlocked = ctx.session().peek_unlocked(|_, _| Ok(())).is_ok();
+            Ok((
+                unlocked,
+                ctx.load_config()?.staking.compounding,
+                ctx.compounding_audit().clone(),
+            ))
+        })
+        .map_err(to_frontend_error)?;
+    if !unlocked {
+        return Ok(());
+    }
+
+    let client = rpc_client(&state, None)?;
+    for (account, policy) in policies.iter().filter(|(_, policy)| policy.enabled) {
+        match compounding::run_policy(&client, &audit, account, policy, chrono::Utc::now()).await {
+            Ok(entries) if !entries.is_empty() => {
+                log::info!(
+                    "Compounding policy for {} recorded {} action(s)",
+                    account,
+                    entries.len()
+                );
+            }
+            Ok(_) => {}
+            Err(err) => log::warn!("Compounding policy for {} failed: {}", account, err),
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn record_staking_snapshot(
+    state: State<'_, SharedWalletContext>,
+    address: String,
+    node_url: Option<String>,
+) -> Result<StakingSnapshot, String> {
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&address)
+        .map_err(|e| e.to_string())?;
+
+    let client = rpc_client(&state, node_url)?;
+    let snapshot = StakingSnapshot::capture(&client, &address)
+        .await
+        .map_err(to_frontend_error)?;
+    state
+        .read(|ctx| ctx.staking_ledger().record(&snapshot))
+        .map_err(to_frontend_error)?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+async fn get_staking_report(
+    state: State<'_, SharedWalletContext>,
+    request: StakingReportRequest,
+) -> Result<StakingReport, String> {
+    let validator = InputValidator::default();
+    validator
+        .validate_address(&request.address)
+        .map_err(|e| e.to_string())?;
+
+    state
+        .read(|ctx| {
+            ctx.staking_ledger()
+                .report(&request.address, request.from, request.to)
+        })
+        .map_err(to_frontend_error)
+}
+
+/// Record a staking snapshot for each of the wallet's staking accounts that
+/// is due one. One account failing does not stop the others.
+async fn record_scheduled_staking_snapshot(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedWalletContext>();
+    let accounts = state
+        .read(|ctx| ctx.staking_accounts())
+        .map_err(to_frontend_error)?;
+    if accounts.is_empty() {
+        return Ok(());
+    }
+
+    let client = rpc_client(&state, None)?;
+    for account in accounts {
+        let recorded = match StakingSnapshot::capture(&client, &account).await {
+            Ok(snapshot) => state.read(|ctx| ctx.staking_ledger().record_if_due(&snapshot)),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = recorded {
+            log::warn!("Staking snapshot for {} failed: {}", account, err);
+        }
+    }
+    Ok(())
 }
 
 // Biometric and push notification commands
@@ -782,10 +1686,10 @@ async fn authenticate_passkey(
 // Governance commands
 #[tauri::command]
 async fn get_proposals(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     request: Option<GetProposalsRequest>,
 ) -> Result<GetProposalsResponse, String> {
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     let request_value = request
         .map(|r| serde_json::to_value(r).unwrap())
         .unwrap_or(serde_json::Value::Null);
@@ -797,24 +1701,178 @@ async fn get_proposals(
 
 #[tauri::command]
 async fn get_proposal(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     proposal_id: i64,
 ) -> Result<GetProposalResponse, String> {
-    let client = BlockchainClient::default();
-    client
+    let known_contracts = state
+        .read(|ctx| Ok(ctx.load_config()?.governance.known_contracts))
+        .map_err(to_frontend_error)?;
+    let client = rpc_client(&state, None)?;
+    let mut response = client
         .get_proposal(proposal_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    response.decoded_actions =
+        governance::calldata::decode_proposal(&response.proposal, &known_contracts);
+    Ok(response)
+}
+
+#[tauri::command]
+async fn get_known_contracts(
+    state: State<'_, SharedWalletContext>,
+) -> Result<BTreeMap<String, KnownContract>, String> {
+    state
+        .read(|ctx| Ok(ctx.load_config()?.governance.known_contracts))
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn set_known_contract(
+    state: State<'_, SharedWalletContext>,
+    request: SetKnownContractRequest,
+) -> Result<BTreeMap<String, KnownContract>, String> {
+    InputValidator::default()
+        .validate_address(&request.address)
+        .map_err(|e| e.to_string())?;
+    if request.contract.name.trim().is_empty() {
+        return Err("Contract name is required".to_string());
+    }
+
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    config
+                        .governance
+                        .known_contracts
+                        .insert(request.address.to_ascii_lowercase(), request.contract);
+                    Ok(())
+                })?;
+            Ok(updated.governance.known_contracts)
+        })
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn remove_known_contract(
+    state: State<'_, SharedWalletContext>,
+    address: String,
+) -> Result<BTreeMap<String, KnownContract>, String> {
+    state
+        .read(|ctx| {
+            let updated = ctx
+                .config_store()
+                .update(ctx.environment().to_string(), |config| {
+                    config
+                        .governance
+                        .known_contracts
+                        .remove(&address.to_ascii_lowercase());
+                    Ok(())
+                })?;
+            Ok(updated.governance.known_contracts)
+        })
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn get_governance_watchlist(
+    state: State<'_, SharedWalletContext>,
+) -> Result<WatchlistState, String> {
+    state
+        .read(|ctx| ctx.governance_watchlist().state())
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn watch_proposal(
+    state: State<'_, SharedWalletContext>,
+    proposal_id: i64,
+) -> Result<WatchlistState, String> {
+    let watchlist = state
+        .read(|ctx| Ok(ctx.governance_watchlist().clone()))
+        .map_err(to_frontend_error)?;
+    let client = rpc_client(&state, None)?;
+    let proposal = client
+        .get_proposal(proposal_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .proposal;
+    watchlist
+        .watch(&proposal, chrono::Utc::now())
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn unwatch_proposal(
+    state: State<'_, SharedWalletContext>,
+    proposal_id: i64,
+) -> Result<WatchlistState, String> {
+    state
+        .read(|ctx| ctx.governance_watchlist().unwatch(proposal_id))
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn set_governance_watch_rules(
+    state: State<'_, SharedWalletContext>,
+    rules: WatchRules,
+) -> Result<WatchlistState, String> {
+    state
+        .read(|ctx| ctx.governance_watchlist().set_rules(rules))
+        .map_err(to_frontend_error)
+}
+
+/// Refresh the governance watchlist and deliver state changes and vote reminders.
+async fn refresh_governance_watchlist(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedWalletContext>();
+    let (watchlist, accounts, known_contracts) = state
+        .read(|ctx| {
+            Ok((
+                ctx.governance_watchlist().clone(),
+                ctx.staking_accounts()?,
+                ctx.load_config()?.governance.known_contracts,
+            ))
+        })
+        .map_err(to_frontend_error)?;
+
+    let client = rpc_client(&state, None)?;
+    let alerts = watchlist::refresh(
+        &client,
+        &watchlist,
+        &accounts,
+        &known_contracts,
+        chrono::Utc::now(),
+    )
+    .await
+    .map_err(to_frontend_error)?;
+    if alerts.is_empty() {
+        return Ok(());
+    }
+
+    let push_registrations = app
+        .state::<RuntimeSecurityState>()
+        .push_subscribers(AlertCategory::Governance);
+    for alert in alerts {
+        let notification = GovernanceNotification {
+            alert,
+            push_registrations: push_registrations.clone(),
+        };
+        if let Err(err) = app.emit(GOVERNANCE_EVENT, &notification) {
+            log::warn!("Failed to emit governance event: {}", err);
+        }
+    }
+    Ok(())
 }
 
 #[tauri::command]
 async fn get_proposal_votes(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     proposal_id: i64,
     limit: Option<u64>,
     offset: Option<u64>,
 ) -> Result<GetProposalVotesResponse, String> {
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client
         .get_proposal_votes(proposal_id, limit, offset)
         .await
@@ -823,10 +1881,10 @@ async fn get_proposal_votes(
 
 #[tauri::command]
 async fn get_voting_power(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     address: String,
 ) -> Result<GetVotingPowerResponse, String> {
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client
         .get_voting_power(&address)
         .await
@@ -835,10 +1893,10 @@ async fn get_voting_power(
 
 #[tauri::command]
 async fn get_delegations(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     address: String,
 ) -> Result<GetDelegationsResponse, String> {
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client
         .get_delegations(&address)
         .await
@@ -847,22 +1905,147 @@ async fn get_delegations(
 
 #[tauri::command]
 async fn cast_vote(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     request: CastVoteRequest,
 ) -> Result<CastVoteResponse, String> {
-    let client = BlockchainClient::default();
-    client.cast_vote(request).await.map_err(|e| e.to_string())
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::governance::cast_vote(&ctx, request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn create_proposal(
+    state: State<'_, SharedWalletContext>,
+    request: CreateProposalRequest,
+) -> Result<CreateProposalResponse, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::governance::create_proposal(&ctx, request)
+        .await
+        .map_err(to_frontend_error)
 }
 
 #[tauri::command]
 async fn delegate(
-    _state: State<'_, SharedWalletContext>,
+    state: State<'_, SharedWalletContext>,
     request: DelegateRequest,
 ) -> Result<DelegateResponse, String> {
-    let client = BlockchainClient::default();
+    let client = rpc_client(&state, None)?;
     client.delegate(request).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn batch_cast_vote(
+    state: State<'_, SharedWalletContext>,
+    request: BatchVoteRequest,
+) -> Result<Vec<BatchVoteResult>, String> {
+    let ctx = state.snapshot().map_err(to_frontend_error)?;
+    commands::governance::batch_cast_vote(&ctx, request)
+        .await
+        .map_err(to_frontend_error)
+}
+
+#[tauri::command]
+async fn batch_delegate(
+    state: State<'_, SharedWalletContext>,
+    request: BatchDelegateRequest,
+) -> Result<Vec<BatchDelegateResult>, String> {
+    InputValidator::default()
+        .validate_address(&request.delegatee)
+        .map_err(|e| e.to_string())?;
+    if request.amount.is_some_and(|amount| amount <= 0) {
+        return Err("Delegation amount must be greater than zero".to_string());
+    }
+
+    let signers = state
+        .read(|ctx| {
+            ctx.session().with_unlocked(|metadata, secrets| {
+                batch::resolve_signers(metadata, secrets, &request.accounts)
+            })
+        })
+        .map_err(to_frontend_error)?;
+
+    let client = rpc_client(&state, None)?;
+    let mut results = Vec::with_capacity(signers.len());
+    for (account, key) in signers {
+        let outcome = async {
+            let key = key?;
+            let amount = match request.amount {
+                Some(amount) => amount,
+                None => {
+                    client
+                        .get_voting_power(&account)
+                        .await?
+                        .voting_power
+                        .voting_power
+                }
+            };
+            if amount <= 0 {
+                return Err(WalletError::ValidationError(
+                    "Account has no voting power to delegate".to_string(),
+                ));
+            }
+            let delegation = AccountDelegation {
+                delegator: account.clone(),
+                delegatee: request.delegatee.clone(),
+                amount,
+            }
+            .sign(&key)?;
+            client.delegate_signed(&delegation).await
+        }
+        .await;
+        results.push(BatchDelegateResult {
+            account,
+            error: outcome.as_ref().err().map(|e| e.to_string()),
+            delegation: outcome.ok().map(|response| response.delegation),
+        });
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+async fn get_delegation_graph(
+    state: State<'_, SharedWalletContext>,
+    accounts: Option<Vec<String>>,
+) -> Result<DelegationGraph, String> {
+    let accounts = match accounts {
+        Some(accounts) => accounts,
+        None => state
+            .read(|ctx| ctx.staking_accounts())
+            .map_err(to_frontend_error)?,
+    };
+
+    let client = rpc_client(&state, None)?;
+    let mut delegations = Vec::new();
+    for account in &accounts {
+        let response = client
+            .get_delegations(account)
+            .await
+            .map_err(|e| e.to_string())?;
+        delegations.extend(response.delegations);
+    }
+    Ok(batch::build_delegation_graph(&accounts, delegations))
+}
+
+/// Run `job` every `every` for the life of the app, logging failures under
+/// `label`. The first run starts straight away.
+fn spawn_periodic<F, Fut>(app: &AppHandle, every: Duration, label: &'static str, job: F)
+where
+    F: Fn(AppHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            if let Err(err) = job(app.clone()).await {
+                log::warn!("{} failed: {}", label, err);
+            }
+        }
+    });
+}
+
 fn main() {
     let security_config =
         init_security_config_from_env().expect("Failed to initialize security configuration");
@@ -876,13 +2059,83 @@ fn main() {
                 .app_config_dir()
                 .map_err(|e| WalletError::Unknown(e.to_string()))?;
             let context = WalletContext::initialize(config_dir)?;
+            if let Some(warning) =
+                tls::status(&context.load_config()?.network, is_production()).warning
+            {
+                log::warn!("{}", warning);
+            }
             app.manage(SharedWalletContext::new(context));
             app.manage(security_state);
+
+            spawn_periodic(
+                app.handle(),
+                SCHEDULED_SNAPSHOT_INTERVAL,
+                "Scheduled staking snapshot",
+                |app| async move { record_scheduled_staking_snapshot(&app).await },
+            );
+            spawn_periodic(
+                app.handle(),
+                REDELEGATION_POLL_INTERVAL,
+                "Resuming redelegations",
+                |app| async move { resume_pending_redelegations(&app).await },
+            );
+            spawn_periodic(
+                app.handle(),
+                COMPOUNDING_POLL_INTERVAL,
+                "Compounding policy run",
+                |app| async move { run_compounding_policies(&app).await },
+            );
+            spawn_periodic(
+                app.handle(),
+                MATURITY_POLL_INTERVAL,
+                "Staking maturity scan",
+                |app| async move { scan_staking_maturities(&app).await },
+            );
+            spawn_periodic(
+                app.handle(),
+                WATCHLIST_POLL_INTERVAL,
+                "Governance watchlist refresh",
+                |app| async move { refresh_governance_watchlist(&app).await },
+            );
+            spawn_periodic(
+                app.handle(),
+                HEALTH_PROBE_INTERVAL,
+                "RPC endpoint probe",
+                |app| async move { probe_rpc_endpoints(&app).await },
+            );
+
+            let subscriptions = ChainSubscriptions::default();
+            app.manage(subscriptions.clone());
+            let client = rpc_client(&app.state::<SharedWalletContext>(), None)?;
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(realtime::subscriptions::run(
+                client,
+                subscriptions,
+                move |update| {
+                    if let Err(err) = app_handle.emit(CHAIN_EVENT, &update) {
+                        log::warn!("Failed to emit chain update: {}", err);
+                    }
+                },
+            ));
+
+            let connector = DappConnector::default();
+            app.manage(connector.clone());
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(connector::server::run(
+                app.state::<SharedWalletContext>().inner().clone(),
+                connector,
+                move |approval| {
+                    if let Err(err) = app_handle.emit(CONNECTOR_EVENT, approval) {
+                        log::warn!("Failed to emit connector request: {}", err);
+                    }
+                },
+            ));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             create_wallet,
             import_wallet,
+            add_wallet_account,
             unlock_wallet,
             lock_wallet,
             get_wallet_info,
@@ -893,17 +2146,62 @@ fn main() {
             validate_address,
             format_amount,
             get_balance,
+            get_transaction,
             get_transaction_history,
+            get_rpc_health,
+            get_rpc_cache_stats,
+            clear_rpc_cache,
+            get_quorum_config,
+            set_quorum_config,
+            get_quorum_reports,
+            get_light_client_config,
+            set_light_client_config,
+            get_trusted_header,
+            get_network_profiles,
+            select_network_profile,
+            save_network_profile,
+            remove_network_profile,
+            verify_network,
+            get_tls_status,
+            set_endpoint_tls,
+            set_allow_untrusted_certs,
+            get_proxy_config,
+            set_proxy,
+            set_endpoint_proxy,
+            subscribe_account,
+            unsubscribe_account,
+            watch_transaction,
+            get_chain_subscriptions,
+            get_connector_status,
+            get_connector_config,
+            set_connector_settings,
+            revoke_connector_grant,
+            resolve_connector_request,
+            export_transaction_history,
             get_validators,
+            get_account_overviews,
             get_user_delegations,
             get_staking_rewards,
             get_lockbox_records,
             get_auto_stake_status,
             delegate_tokens,
             undelegate_tokens,
+            redelegate_tokens,
+            plan_lockbox_stake,
+            resume_redelegation,
+            get_redelegation_plans,
             create_lockbox_stake,
             toggle_auto_staking,
             claim_staking_rewards,
+            record_staking_snapshot,
+            recommend_delegation_split,
+            get_staking_report,
+            get_staking_maturities,
+            execute_maturity_action,
+            get_compounding_policies,
+            set_compounding_policy,
+            remove_compounding_policy,
+            get_compounding_audit,
             get_biometric_status,
             authenticate_biometric,
             get_push_notification_status,
@@ -912,11 +2210,22 @@ fn main() {
             authenticate_passkey,
             get_proposals,
             get_proposal,
+            get_known_contracts,
+            set_known_contract,
+            remove_known_contract,
+            get_governance_watchlist,
+            watch_proposal,
+            unwatch_proposal,
+            set_governance_watch_rules,
             get_proposal_votes,
             get_voting_power,
             get_delegations,
             cast_vote,
-            delegate
+            create_proposal,
+            delegate,
+            batch_cast_vote,
+            batch_delegate,
+            get_delegation_graph
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,318 @@
+/// Named network profiles and chain-id pinning
+///
+/// A profile bundles everything that differs between networks: chain id,
+/// RPC endpoints, explorer links, address prefix and fee defaults. Selecting
+/// one pins its chain id, and signed submissions are refused by any endpoint
+/// that reports a different chain. Built-in profiles carry no chain id of
+/// their own; they pin whatever their node reports when selected.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::config_store::WalletConfig;
+use crate::errors::{WalletError, WalletResult};
+
+/// How long an endpoint's reported chain id is trusted before asking again.
+const CHAIN_ID_TTL: Duration = Duration::from_secs(10 * 60);
+const MAX_PROFILE_NAME_LENGTH: usize = 32;
+/// Placeholder replaced by the transaction id in explorer templates.
+const EXPLORER_TX_PLACEHOLDER: &str = "{txId}";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkProfile {
+    pub name: String,
+    /// Required for user-defined profiles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    /// Primary endpoint first, then failovers.
+    pub endpoints: Vec<String>,
+    /// Transaction page URL containing `{txId}`.
+    pub explorer_tx_url: String,
+    pub bech32_hrp: String,
+    /// Fee in base units suggested for a plain transfer.
+    pub default_fee: u64,
+    /// Shipped with the wallet; cannot be edited or removed.
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl NetworkProfile {
+    pub fn explorer_link(&self, tx_id: &str) -> String {
+        self.explorer_tx_url.replace(EXPLORER_TX_PLACEHOLDER, tx_id)
+    }
+}
+
+fn builtin(
+    name: &str,
+    endpoints: &[&str],
+    explorer: &str,
+    hrp: &str,
+    default_fee: u64,
+) -> NetworkProfile {
+    NetworkProfile {
+        name: name.to_string(),
+        chain_id: None,
+        endpoints: endpoints.iter().map(|url| url.to_string()).collect(),
+        explorer_tx_url: explorer.to_string(),
+        bech32_hrp: hrp.to_string(),
+        default_fee,
+        builtin: true,
+    }
+}
+
+/// Profiles shipped with the wallet.
+pub fn builtin_profiles() -> Vec<NetworkProfile> {
+    vec![
+        builtin(
+            "mainnet",
+            &[
+                "https://mainnet.chert.network",
+                "https://rpc-backup.chert.network",
+            ],
+            "https://explorer.chert.network/tx/{txId}",
+            "chert",
+            1_000,
+        ),
+        builtin(
+            "testnet",
+            &["https://testnet.chert.network"],
+            "https://testnet.explorer.chert.network/tx/{txId}",
+            "chert",
+            1_000,
+        ),
+        builtin(
+            "devnet",
+            &["https://devnet.chert.network"],
+            "https://devnet.explorer.chert.network/tx/{txId}",
+            "chert",
+            100,
+        ),
+        builtin(
+            "local",
+            &["http://localhost:8545"],
+            "http://localhost:3000/tx/{txId}",
+            "chert",
+            0,
+        ),
+    ]
+}
+
+/// Built-in profiles followed by the user's own.
+pub fn all_profiles(custom: &[NetworkProfile]) -> Vec<NetworkProfile> {
+    let mut profiles = builtin_profiles();
+    profiles.extend(custom.iter().cloned());
+    profiles
+}
+
+pub fn find_profile(name: &str, custom: &[NetworkProfile]) -> WalletResult<NetworkProfile> {
+    all_profiles(custom)
+        .into_iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| WalletError::NotFound(format!("Network profile '{}'", name)))
+}
+
+/// The profile the wallet last switched to, if it still exists.
+pub fn active_profile(config: &WalletConfig) -> Option<NetworkProfile> {
+    let name = config.network.profile.as_deref()?;
+    find_profile(name, &config.custom_networks).ok()
+}
+
+/// The chain id signed requests are pinned to. A user-defined profile pins
+/// its own chain; built-in ones pin what their node reported when selected.
+pub fn pinned_chain(config: &WalletConfig) -> Option<String> {
+    active_profile(config)
+        .and_then(|profile| profile.chain_id)
+        .or_else(|| config.network.chain_id.clone())
+}
+
+/// Check a user-defined profile before it is stored.
+pub fn validate_profile(profile: &NetworkProfile) -> WalletResult<()> {
+    let name = profile.name.trim();
+    if name.is_empty()
+        || name.len() > MAX_PROFILE_NAME_LENGTH
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(WalletError::ValidationError(format!(
+            "Profile names must be 1-{} letters, digits, '-' or '_'",
+            MAX_PROFILE_NAME_LENGTH
+        )));
+    }
+    if builtin_profiles()
+        .iter()
+        .any(|builtin| builtin.name.eq_ignore_ascii_case(name))
+    {
+        return Err(WalletError::AlreadyExists(format!(
+            "'{}' is a built-in network",
+            name
+        )));
+    }
+    if profile.chain_id.as_deref().unwrap_or("").trim().is_empty() {
+        return Err(WalletError::ValidationError(
+            "Chain id is required".to_string(),
+        ));
+    }
+    if profile.endpoints.is_empty() {
+        return Err(WalletError::ValidationError(
+            "At least one RPC endpoint is required".to_string(),
+        ));
+    }
+    for endpoint in &profile.endpoints {
+        let url = Url::parse(endpoint).map_err(|e| {
+            WalletError::ValidationError(format!("Invalid endpoint {}: {}", endpoint, e))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(WalletError::ValidationError(format!(
+                "Endpoint {} must use http or https",
+                endpoint
+            )));
+        }
+    }
+    if !profile.explorer_tx_url.contains(EXPLORER_TX_PLACEHOLDER) {
+        return Err(WalletError::ValidationError(format!(
+            "Explorer URL must contain {}",
+            EXPLORER_TX_PLACEHOLDER
+        )));
+    }
+    bech32::Hrp::parse(&profile.bech32_hrp)
+        .map_err(|e| WalletError::ValidationError(format!("Invalid bech32 prefix: {}", e)))?;
+    Ok(())
+}
+
+/// The chain id the wallet is pinned to and what each endpoint reported,
+/// shared by clones of a client.
+#[derive(Debug, Clone, Default)]
+pub struct ChainPin {
+    expected: Arc<RwLock<Option<String>>>,
+    reported: Arc<RwLock<HashMap<String, (String, Instant)>>>,
+}
+
+impl ChainPin {
+    pub fn new(expected: Option<String>) -> Self {
+        Self {
+            expected: Arc::new(RwLock::new(expected)),
+            reported: Arc::default(),
+        }
+    }
+
+    pub fn expected(&self) -> Option<String> {
+        self.expected.read().clone()
+    }
+
+    pub fn set_expected(&self, expected: Option<String>) {
+        let mut current = self.expected.write();
+        if *current != expected {
+            self.reported.write().clear();
+        }
+        *current = expected;
+    }
+
+    /// The chain id `url` reported recently, if still fresh.
+    pub fn reported(&self, url: &str) -> Option<String> {
+        self.reported
+            .read()
+            .get(url)
+            .filter(|(_, at)| at.elapsed() < CHAIN_ID_TTL)
+            .map(|(chain_id, _)| chain_id.clone())
+    }
+
+    pub fn record(&self, url: &str, chain_id: String) {
+        self.reported
+            .write()
+            .insert(url.to_string(), (chain_id, Instant::now()));
+    }
+
+    /// Refuse `reported` unless it is the pinned chain (or nothing is pinned).
+    pub fn check(&self, url: &str, reported: &str) -> WalletResult<()> {
+        match self.expected.read().as_deref() {
+            Some(expected) if expected != reported => Err(WalletError::ChainMismatch(format!(
+                "{} is on chain '{}', expected '{}'",
+                url, reported, expected
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str) -> NetworkProfile {
+        NetworkProfile {
+            name: name.to_string(),
+            chain_id: Some("chert-staging".to_string()),
+            endpoints: vec!["https://staging.example".to_string()],
+            explorer_tx_url: "https://staging.example/tx/{txId}".to_string(),
+            bech32_hrp: "chert".to_string(),
+            default_fee: 10,
+            builtin: false,
+        }
+    }
+
+    #[test]
+    fn custom_profiles_are_validated_and_found() {
+        let staging = custom("staging");
+        assert!(validate_profile(&staging).is_ok());
+        assert!(validate_profile(&custom("Mainnet")).is_err());
+
+        let mut broken = custom("broken");
+        broken.explorer_tx_url = "https://staging.example/tx/".to_string();
+        assert!(validate_profile(&broken).is_err());
+        let mut unpinned = custom("unpinned");
+        unpinned.chain_id = None;
+        assert!(validate_profile(&unpinned).is_err());
+
+        let found = find_profile("STAGING", std::slice::from_ref(&staging)).unwrap();
+        assert_eq!(
+            found.explorer_link("0xabc"),
+            "https://staging.example/tx/0xabc"
+        );
+        assert!(find_profile("mainnet", &[]).unwrap().builtin);
+
+        let mut config = WalletConfig::new("test");
+        assert!(active_profile(&config).is_none());
+        config.custom_networks.push(staging);
+        config.network.profile = Some("staging".to_string());
+        assert_eq!(active_profile(&config).unwrap().default_fee, 10);
+    }
+
+    #[test]
+    fn builtin_profiles_pin_the_chain_their_node_reported() {
+        assert!(builtin_profiles()
+            .iter()
+            .all(|profile| profile.chain_id.is_none()));
+
+        let mut config = WalletConfig::new("test");
+        config.network.profile = Some("mainnet".to_string());
+        assert_eq!(pinned_chain(&config), None);
+        config.network.chain_id = Some("1".to_string());
+        assert_eq!(pinned_chain(&config).as_deref(), Some("1"));
+
+        config.custom_networks.push(custom("staging"));
+        config.network.profile = Some("staging".to_string());
+        assert_eq!(pinned_chain(&config).as_deref(), Some("chert-staging"));
+    }
+
+    #[test]
+    fn pinned_chain_rejects_other_chains() {
+        let pin = ChainPin::new(Some("chert-mainnet-1".to_string()));
+        assert!(pin.check("https://a", "chert-mainnet-1").is_ok());
+        assert!(matches!(
+            pin.check("https://a", "chert-testnet-1"),
+            Err(WalletError::ChainMismatch(_))
+        ));
+
+        pin.record("https://a", "chert-testnet-1".to_string());
+        assert!(pin.reported("https://a").is_some());
+        pin.set_expected(None);
+        assert!(pin.reported("https://a").is_none());
+        assert!(pin.check("https://a", "anything").is_ok());
+    }
+}
@@ -0,0 +1,420 @@
+/// SOCKS5 routing for RPC and WebSocket connections
+///
+/// A default proxy, typically a local Tor daemon, carries every endpoint's
+/// traffic unless the network config overrides the endpoint's host with
+/// another proxy or a direct connection. In strict mode nothing may connect
+/// directly, and every proxy must be `socks5h://` so host names are resolved
+/// by the proxy; a broken setup fails closed instead of revealing the user's
+/// IP or the nodes it talks to.
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::config_store::{ProxyConfig, ProxyRoute};
+use crate::errors::{WalletError, WalletResult};
+use crate::tls::host_of;
+
+const SOCKS_VERSION: u8 = 5;
+const DEFAULT_SOCKS_PORT: u16 = 1080;
+const NO_AUTH: u8 = 0x00;
+const USER_PASS_AUTH: u8 = 0x02;
+const CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// Proxy settings resolved per host, shared by the HTTP and WebSocket
+/// transports.
+#[derive(Debug, Clone, Default)]
+pub struct ProxyRoutes {
+    default: Option<Url>,
+    /// Host overrides; `None` connects directly.
+    hosts: HashMap<String, Option<Url>>,
+    strict: bool,
+}
+
+impl ProxyRoutes {
+    pub fn from_config(config: &ProxyConfig) -> WalletResult<Self> {
+        let default = config.url.as_deref().map(parse_proxy_url).transpose()?;
+        let mut hosts = HashMap::new();
+        for (endpoint, route) in &config.endpoints {
+            let proxy = match route {
+                ProxyRoute::Direct => None,
+                ProxyRoute::Proxy { url } => Some(parse_proxy_url(url)?),
+            };
+            if hosts.insert(host_of(endpoint)?, proxy).is_some() {
+                return Err(WalletError::ValidationError(format!(
+                    "Several proxy routes apply to the host of {}",
+                    endpoint
+                )));
+            }
+        }
+
+        if config.strict {
+            if default.is_none() {
+                return Err(WalletError::ValidationError(
+                    "Strict proxy mode needs a default proxy".to_string(),
+                ));
+            }
+            if let Some(endpoint) = config
+                .endpoints
+                .iter()
+                .find_map(|(endpoint, route)| (*route == ProxyRoute::Direct).then_some(endpoint))
+            {
+                return Err(WalletError::ValidationError(format!(
+                    "Strict proxy mode does not allow a direct route to {}",
+                    endpoint
+                )));
+            }
+            // socks5:// resolves names locally, leaking them to the resolver.
+            if let Some(proxy) = default
+                .iter()
+                .chain(hosts.values().flatten())
+                .find(|proxy| proxy.scheme() != "socks5h")
+            {
+                return Err(WalletError::ValidationError(format!(
+                    "Strict proxy mode needs socks5h:// so the proxy resolves host names, not {}",
+                    proxy
+                )));
+            }
+        }
+        Ok(Self {
+            default,
+            hosts,
+            strict: config.strict,
+        })
+    }
+
+    /// Whether any connection goes through a proxy.
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || self.hosts.values().any(Option::is_some)
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// The proxy carrying connections to `host`, or `None` to connect directly.
+    pub fn route(&self, host: &str) -> Option<&Url> {
+        match self.hosts.get(&host.to_ascii_lowercase()) {
+            Some(route) => route.as_ref(),
+            None => self.default.as_ref(),
+        }
+    }
+
+    /// Like `route`, but a direct connection in strict mode is an error.
+    pub fn check(&self, host: &str) -> WalletResult<Option<&Url>> {
+        match self.route(host) {
+            None if self.strict => Err(WalletError::PermissionDenied(format!(
+                "Strict proxy mode refuses a direct connection to {}",
+                host
+            ))),
+            route => Ok(route),
+        }
+    }
+
+    /// Open a TCP connection to `host:port` along its route.
+    pub async fn connect(&self, host: &str, port: u16) -> WalletResult<TcpStream> {
+        match self.check(host)? {
+            Some(proxy) => socks5_connect(proxy, host, port).await,
+            None => TcpStream::connect((host, port)).await.map_err(|e| {
+                WalletError::NetworkError(format!("Connect to {} failed: {}", host, e))
+            }),
+        }
+    }
+}
+
+fn parse_proxy_url(url: &str) -> WalletResult<Url> {
+    let parsed = Url::parse(url.trim())
+        .map_err(|e| WalletError::ValidationError(format!("Invalid proxy URL {}: {}", url, e)))?;
+    if !matches!(parsed.scheme(), "socks5" | "socks5h") {
+        return Err(WalletError::ValidationError(format!(
+            "Proxy {} must use socks5:// or socks5h://",
+            url
+        )));
+    }
+    if parsed.host_str().is_none() {
+        return Err(WalletError::ValidationError(format!(
+            "Proxy {} has no host",
+            url
+        )));
+    }
+    Ok(parsed)
+}
+
+fn socks_error(message: impl std::fmt::Display) -> WalletError {
+    WalletError::NetworkError(format!("SOCKS proxy: {}", message))
+}
+
+/// CONNECT to `host:port` through the SOCKS5 proxy at `proxy`. With
+/// `socks5h` the proxy resolves the name, so no DNS query leaves the machine.
+async fn socks5_connect(proxy: &Url, host: &str, port: u16) -> WalletResult<TcpStream> {
+    let proxy_host = proxy.host_str().unwrap_or_default();
+    let mut stream = TcpStream::connect((proxy_host, proxy.port().unwrap_or(DEFAULT_SOCKS_PORT)))
+        .await
+        .map_err(|e| socks_error(format!("cannot reach {}: {}", proxy_host, e)))?;
+    let io = |e: std::io::Error| socks_error(e);
+
+    let credentials = (!proxy.username().is_empty()).then(|| {
+        (
+            proxy.username().to_string(),
+            proxy.password().unwrap_or_default().to_string(),
+        )
+    });
+    let method = if credentials.is_some() {
+        USER_PASS_AUTH
+    } else {
+        NO_AUTH
+    };
+    stream
+        .write_all(&[SOCKS_VERSION, 1, method])
+        .await
+        .map_err(io)?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await.map_err(io)?;
+    if choice[0] != SOCKS_VERSION || choice[1] != method {
+        return Err(socks_error("authentication method refused"));
+    }
+    if let Some((username, password)) = credentials {
+        let (username, password) = (username.as_bytes(), password.as_bytes());
+        if username.len() > 255 || password.len() > 255 {
+            return Err(socks_error("credentials are too long"));
+        }
+        let mut request = vec![1, username.len() as u8];
+        request.extend_from_slice(username);
+        request.push(password.len() as u8);
+        request.extend_from_slice(password);
+        stream.write_all(&request).await.map_err(io)?;
+        let mut status = [0u8; 2];
+        stream.read_exact(&mut status).await.map_err(io)?;
+        if status[1] != 0 {
+            return Err(socks_error("credentials rejected"));
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, CONNECT, 0];
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    let address = match literal.parse::<IpAddr>() {
+        Ok(ip) => Some(ip),
+        Err(_) if proxy.scheme() == "socks5" => Some(
+            tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|e| WalletError::NetworkError(format!("Cannot resolve {}: {}", host, e)))?
+                .next()
+                .ok_or_else(|| WalletError::NetworkError(format!("Cannot resolve {}", host)))?
+                .ip(),
+        ),
+        Err(_) => None,
+    };
+    match address {
+        Some(IpAddr::V4(ip)) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Some(IpAddr::V6(ip)) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        None => {
+            if host.len() > 255 {
+                return Err(socks_error("host name is too long"));
+            }
+            request.push(ATYP_DOMAIN);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await.map_err(io)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await.map_err(io)?;
+    if reply[1] != 0 {
+        return Err(socks_error(format!(
+            "connection to {} refused (code {})",
+            host, reply[1]
+        )));
+    }
+    // Skip the bound address and port.
+    let bound = match reply[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => stream.read_u8().await.map_err(io)? as usize,
+        other => return Err(socks_error(format!("unknown address type {}", other))),
+    };
+    let mut skipped = vec![0u8; bound + 2];
+    stream.read_exact(&mut skipped).await.map_err(io)?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Minimal no-auth SOCKS5 server: accepts one CONNECT, reports the target
+    /// it was asked for and hands the tunnel to `serve`.
+    async fn socks_stand_in<F, Fut>(serve: F) -> (Url, tokio::task::JoinHandle<String>)
+    where
+        F: FnOnce(TcpStream) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("socks5h://{}", listener.local_addr().unwrap())).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 2];
+            stream.read_exact(&mut greeting).await.unwrap();
+            let mut methods = vec![0u8; greeting[1] as usize];
+            stream.read_exact(&mut methods).await.unwrap();
+            assert!(methods.contains(&NO_AUTH));
+            stream.write_all(&[SOCKS_VERSION, NO_AUTH]).await.unwrap();
+
+            let mut head = [0u8; 4];
+            stream.read_exact(&mut head).await.unwrap();
+            assert_eq!(head[3], ATYP_DOMAIN, "names must be resolved by the proxy");
+            let mut name = vec![0u8; stream.read_u8().await.unwrap() as usize];
+            stream.read_exact(&mut name).await.unwrap();
+            let port = stream.read_u16().await.unwrap();
+            stream
+                .write_all(&[SOCKS_VERSION, 0, 0, ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            serve(stream).await;
+            format!("{}:{}", String::from_utf8(name).unwrap(), port)
+        });
+        (url, server)
+    }
+
+    fn config(url: Option<&str>, strict: bool, endpoints: &[(&str, ProxyRoute)]) -> ProxyConfig {
+        ProxyConfig {
+            url: url.map(str::to_string),
+            strict,
+            endpoints: endpoints
+                .iter()
+                .map(|(endpoint, route)| (endpoint.to_string(), route.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn overrides_and_strict_mode_shape_routes() {
+        let routes = ProxyRoutes::from_config(&config(
+            Some("socks5h://127.0.0.1:9050"),
+            false,
+            &[
+                ("http://localhost:8545", ProxyRoute::Direct),
+                (
+                    "https://rpc.example",
+                    ProxyRoute::Proxy {
+                        url: "socks5://10.0.0.2:1080".to_string(),
+                    },
+                ),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(routes.route("node.example").unwrap().port(), Some(9050));
+        assert_eq!(routes.route("RPC.example").unwrap().port(), Some(1080));
+        assert!(routes.route("localhost").is_none());
+        assert!(ProxyRoutes::default()
+            .check("node.example")
+            .unwrap()
+            .is_none());
+
+        assert!(ProxyRoutes::from_config(&config(None, true, &[])).is_err());
+        assert!(ProxyRoutes::from_config(&config(
+            Some("socks5h://127.0.0.1:9050"),
+            true,
+            &[("http://localhost:8545", ProxyRoute::Direct)],
+        ))
+        .is_err());
+        assert!(
+            ProxyRoutes::from_config(&config(Some("http://127.0.0.1:8080"), false, &[])).is_err()
+        );
+        assert!(
+            ProxyRoutes::from_config(&config(Some("socks5://127.0.0.1:9050"), true, &[])).is_err()
+        );
+        assert!(ProxyRoutes::from_config(&config(
+            Some("socks5h://127.0.0.1:9050"),
+            true,
+            &[(
+                "https://rpc.example",
+                ProxyRoute::Proxy {
+                    url: "socks5://10.0.0.2:1080".to_string(),
+                },
+            )],
+        ))
+        .is_err());
+
+        let strict = ProxyRoutes {
+            strict: true,
+            ..ProxyRoutes::default()
+        };
+        assert!(matches!(
+            strict.check("node.example"),
+            Err(WalletError::PermissionDenied(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn connections_are_tunnelled_through_the_proxy() {
+        let (proxy, server) = socks_stand_in(|mut tunnel| async move {
+            let mut ping = [0u8; 4];
+            tunnel.read_exact(&mut ping).await.unwrap();
+            tunnel.write_all(&ping).await.unwrap();
+        })
+        .await;
+        let routes = ProxyRoutes::from_config(&config(Some(proxy.as_str()), true, &[])).unwrap();
+
+        let mut stream = routes.connect("node.invalid", 8545).await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut echoed = [0u8; 4];
+        stream.read_exact(&mut echoed).await.unwrap();
+
+        assert_eq!(&echoed, b"ping");
+        assert_eq!(server.await.unwrap(), "node.invalid:8545");
+    }
+
+    #[tokio::test]
+    async fn rpc_requests_use_the_proxy() {
+        let (proxy, server) = socks_stand_in(|mut tunnel| async move {
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 1024];
+            let body = loop {
+                let read = tunnel.read(&mut chunk).await.unwrap();
+                request.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((_, body)) = text.split_once("\r\n\r\n") {
+                    if let Ok(body) = serde_json::from_str::<serde_json::Value>(body) {
+                        break body;
+                    }
+                }
+            };
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": { "chainId": "chert-local" },
+                "id": body["id"],
+            })
+            .to_string();
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            tunnel.write_all(reply.as_bytes()).await.unwrap();
+        })
+        .await;
+        let routes = ProxyRoutes::from_config(&config(Some(proxy.as_str()), true, &[])).unwrap();
+        let client =
+            crate::blockchain_client::BlockchainClient::new("http://node.invalid:8545".to_string())
+                .unwrap()
+                .with_proxy(routes)
+                .unwrap();
+
+        assert_eq!(client.verify_chain().await.unwrap(), "chert-local");
+        assert_eq!(server.await.unwrap(), "node.invalid:8545");
+    }
+}
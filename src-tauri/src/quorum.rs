@@ -0,0 +1,249 @@
+/// Cross-checking of critical reads across independent RPC endpoints
+///
+/// With quorum mode enabled, reads a single malicious node could falsify are
+/// sent to several endpoints and only trusted when every answer is identical.
+/// Failed checks are kept so the settings page can show which endpoint lied.
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::config_store::QuorumConfig;
+use crate::errors::{WalletError, WalletResult};
+
+/// Failed checks kept for display.
+const MAX_REPORTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuorumFailure {
+    /// Endpoints returned different results.
+    Disagreement,
+    /// Too few endpoints answered to reach the quorum.
+    InsufficientResponses,
+}
+
+/// One endpoint's answer to a cross-checked read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointAnswer {
+    pub url: String,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumReport {
+    pub method: String,
+    pub failure: QuorumFailure,
+    pub required: usize,
+    pub answers: Vec<EndpointAnswer>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl QuorumReport {
+    /// Endpoints whose answer differs from the most common one.
+    pub fn dissenters(&self) -> Vec<&str> {
+        let results: Vec<&serde_json::Value> = self
+            .answers
+            .iter()
+            .filter_map(|a| a.result.as_ref())
+            .collect();
+        let Some(majority) = results
+            .iter()
+            .max_by_key(|value| results.iter().filter(|other| other == value).count())
+        else {
+            return Vec::new();
+        };
+        self.answers
+            .iter()
+            .filter(|a| a.result.as_ref().is_some_and(|value| value != *majority))
+            .map(|a| a.url.as_str())
+            .collect()
+    }
+}
+
+impl From<&QuorumReport> for WalletError {
+    fn from(report: &QuorumReport) -> Self {
+        let answered = report.answers.iter().filter(|a| a.result.is_some()).count();
+        WalletError::QuorumMismatch(match report.failure {
+            QuorumFailure::Disagreement => format!(
+                "{} returned conflicting results (differing: {})",
+                report.method,
+                report.dissenters().join(", ")
+            ),
+            QuorumFailure::InsufficientResponses => format!(
+                "{} answered by {} of {} required endpoints",
+                report.method, answered, report.required
+            ),
+        })
+    }
+}
+
+/// Reduce the per-endpoint outcomes of `method` to a single trusted result.
+///
+/// If no endpoint answered, the first error is returned as-is so callers see
+/// the same failure as an unchecked read would produce.
+pub fn reconcile<T: Serialize>(
+    method: &str,
+    outcomes: Vec<(String, WalletResult<T>)>,
+    required: usize,
+) -> Result<WalletResult<T>, QuorumReport> {
+    let mut answers = Vec::with_capacity(outcomes.len());
+    let mut agreed: Option<(serde_json::Value, T)> = None;
+    let mut disagreement = false;
+    let mut first_error = None;
+
+    for (url, outcome) in outcomes {
+        match outcome {
+            Ok(value) => {
+                let json = serde_json::to_value(&value).unwrap_or(serde_json::Value::Null);
+                match &agreed {
+                    Some((expected, _)) => disagreement |= *expected != json,
+                    None => agreed = Some((json.clone(), value)),
+                }
+                answers.push(EndpointAnswer {
+                    url,
+                    result: Some(json),
+                    error: None,
+                });
+            }
+            Err(err) => {
+                answers.push(EndpointAnswer {
+                    url,
+                    result: None,
+                    error: Some(err.to_string()),
+                });
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    let answered = answers.iter().filter(|a| a.result.is_some()).count();
+    let failure = match agreed {
+        None => match first_error {
+            Some(err) => return Ok(Err(err)),
+            None => QuorumFailure::InsufficientResponses,
+        },
+        Some(_) if disagreement => QuorumFailure::Disagreement,
+        Some(_) if answered < required => QuorumFailure::InsufficientResponses,
+        Some((_, value)) => return Ok(Ok(value)),
+    };
+    Err(QuorumReport {
+        method: method.to_string(),
+        failure,
+        required,
+        answers,
+        checked_at: Utc::now(),
+    })
+}
+
+/// Quorum settings and recent failed checks, shared by clones of a client.
+#[derive(Debug, Clone, Default)]
+pub struct QuorumMonitor {
+    config: Arc<RwLock<QuorumConfig>>,
+    reports: Arc<RwLock<VecDeque<QuorumReport>>>,
+}
+
+impl QuorumMonitor {
+    pub fn new(config: QuorumConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            reports: Arc::default(),
+        }
+    }
+
+    pub fn config(&self) -> QuorumConfig {
+        self.config.read().clone()
+    }
+
+    pub fn set_config(&self, config: QuorumConfig) {
+        *self.config.write() = config;
+    }
+
+    pub fn record(&self, report: QuorumReport) {
+        let mut reports = self.reports.write();
+        if reports.len() == MAX_REPORTS {
+            reports.pop_front();
+        }
+        reports.push_back(report);
+    }
+
+    /// Failed checks, newest first.
+    pub fn reports(&self) -> Vec<QuorumReport> {
+        self.reports.read().iter().rev().cloned().collect()
+    }
+}
+
+/// Reject quorum settings that could never be met by `endpoint_count` endpoints.
+pub fn validate_config(config: &QuorumConfig, endpoint_count: usize) -> WalletResult<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+    if config.min_responses < 2 {
+        return Err(WalletError::ValidationError(
+            "Quorum mode needs at least 2 agreeing endpoints".to_string(),
+        ));
+    }
+    if config.min_responses > endpoint_count {
+        return Err(WalletError::ValidationError(format!(
+            "Quorum of {} exceeds the {} configured endpoints",
+            config.min_responses, endpoint_count
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(url: &str, nonce: u64) -> (String, WalletResult<serde_json::Value>) {
+        (
+            url.to_string(),
+            Ok(serde_json::json!({ "balance": "10", "nonce": nonce })),
+        )
+    }
+
+    fn down(url: &str) -> (String, WalletResult<serde_json::Value>) {
+        (url.to_string(), Err(WalletError::ConnectionTimeout))
+    }
+
+    #[test]
+    fn agreeing_endpoints_are_trusted() {
+        let result = reconcile("get_balance", vec![ok("a", 3), ok("b", 3), down("c")], 2);
+        assert_eq!(result.unwrap().unwrap()["nonce"], 3);
+    }
+
+    #[test]
+    fn disagreement_names_the_dissenting_endpoint() {
+        let report = reconcile("get_balance", vec![ok("a", 3), ok("b", 9), ok("c", 3)], 2)
+            .expect_err("nonces differ");
+        assert_eq!(report.failure, QuorumFailure::Disagreement);
+        assert_eq!(report.dissenters(), vec!["b"]);
+        assert!(matches!(
+            WalletError::from(&report),
+            WalletError::QuorumMismatch(_)
+        ));
+    }
+
+    #[test]
+    fn too_few_answers_or_none_at_all() {
+        let report = reconcile("get_balance", vec![ok("a", 3), down("b")], 2)
+            .expect_err("one answer is not a quorum");
+        assert_eq!(report.failure, QuorumFailure::InsufficientResponses);
+
+        let result = reconcile("get_balance", vec![down("a"), down("b")], 2);
+        assert!(matches!(result, Ok(Err(WalletError::ConnectionTimeout))));
+
+        let config = QuorumConfig {
+            enabled: true,
+            min_responses: 3,
+        };
+        assert!(validate_config(&config, 2).is_err());
+        assert!(validate_config(&config, 3).is_ok());
+    }
+}
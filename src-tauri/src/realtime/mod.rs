@@ -0,0 +1,9 @@
+/// Live chain updates pushed to the frontend
+///
+/// A WebSocket JSON-RPC connection to the healthiest endpoint that accepts one
+/// carries subscriptions for new blocks, watched accounts and pending
+/// transactions. While no socket is up the same topics are polled over HTTP.
+pub mod subscriptions;
+pub mod websocket;
+
+pub use subscriptions::{ChainSubscriptions, SubscriptionStatus, CHAIN_EVENT};
@@ -0,0 +1,779 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::api::types::{BalanceResponse, TransactionInfo};
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+use crate::realtime::websocket::{WsConnection, WsMessage};
+use crate::rpc_batch::RpcBatch;
+use crate::rpc_cache::CachedRead;
+
+/// Tauri event carrying every [`ChainUpdate`].
+pub const CHAIN_EVENT: &str = "chain://update";
+
+/// Polling cadence while the WebSocket is down or a topic is not supported by the node.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(120);
+/// Watched transactions still not included after this long are dropped,
+/// e.g. ones the node never accepted.
+pub const TRANSACTION_WATCH_LIMIT: Duration = Duration::from_secs(30 * 60);
+
+/// How updates are currently being received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransportMode {
+    Connecting,
+    WebSocket,
+    Polling,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ChainUpdate {
+    NewHead {
+        height: u64,
+        hash: Option<String>,
+    },
+    Account {
+        address: String,
+        balance: String,
+        nonce: u64,
+    },
+    Transaction {
+        transaction: TransactionInfo,
+        /// The transaction is in a block; it is no longer watched.
+        included: bool,
+    },
+    Transport {
+        mode: TransportMode,
+        endpoint: Option<String>,
+        error: Option<String>,
+    },
+}
+
+/// Something the node can push updates for.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Topic {
+    NewHeads,
+    Account(String),
+    Transaction(String),
+}
+
+impl Topic {
+    fn subscribe_params(&self) -> serde_json::Value {
+        match self {
+            Topic::NewHeads => serde_json::json!(["newHeads"]),
+            Topic::Account(address) => serde_json::json!(["account", { "address": address }]),
+            Topic::Transaction(tx_id) => serde_json::json!(["transaction", { "tx_id": tx_id }]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionStatus {
+    pub mode: TransportMode,
+    pub endpoint: Option<String>,
+    pub accounts: Vec<String>,
+    pub transactions: Vec<String>,
+    /// Consecutive failed connection attempts.
+    pub reconnect_attempts: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct SubscriptionState {
+    accounts: BTreeSet<String>,
+    /// Watched transaction ids and when watching started.
+    transactions: BTreeMap<String, Instant>,
+    mode: TransportMode,
+    endpoint: Option<String>,
+    reconnect_attempts: u32,
+    last_error: Option<String>,
+}
+
+/// Accounts and transactions the frontend wants live updates for, plus the
+/// transport's status. Shared between commands and the background runner.
+#[derive(Debug, Clone)]
+pub struct ChainSubscriptions {
+    state: Arc<Mutex<SubscriptionState>>,
+    changed: Arc<Notify>,
+}
+
+impl Default for ChainSubscriptions {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SubscriptionState {
+                accounts: BTreeSet::new(),
+                transactions: BTreeMap::new(),
+                mode: TransportMode::Connecting,
+                endpoint: None,
+                reconnect_attempts: 0,
+                last_error: None,
+            })),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+}
+
+impl ChainSubscriptions {
+    pub fn add_account(&self, address: &str) {
+        self.modify(|state| state.accounts.insert(address.to_ascii_lowercase()));
+    }
+
+    pub fn remove_account(&self, address: &str) {
+        self.modify(|state| state.accounts.remove(&address.to_ascii_lowercase()));
+    }
+
+    /// Watch `tx_id` until it is included in a block, or for at most
+    /// `TRANSACTION_WATCH_LIMIT`.
+    pub fn watch_transaction(&self, tx_id: &str) {
+        self.modify(|state| {
+            state
+                .transactions
+                .insert(tx_id.to_string(), Instant::now())
+                .is_none()
+        });
+    }
+
+    pub fn status(&self) -> SubscriptionStatus {
+        let state = self.state.lock();
+        SubscriptionStatus {
+            mode: state.mode,
+            endpoint: state.endpoint.clone(),
+            accounts: state.accounts.iter().cloned().collect(),
+            transactions: state.transactions.keys().cloned().collect(),
+            reconnect_attempts: state.reconnect_attempts,
+            last_error: state.last_error.clone(),
+        }
+    }
+
+    fn finish_transaction(&self, tx_id: &str) {
+        self.modify(|state| state.transactions.remove(tx_id).is_some());
+    }
+
+    /// Stop watching transactions that have waited past the limit at `now`.
+    fn expire_transactions(&self, now: Instant) {
+        self.modify(|state| {
+            let watched = state.transactions.len();
+            state
+                .transactions
+                .retain(|_, since| now.duration_since(*since) < TRANSACTION_WATCH_LIMIT);
+            state.transactions.len() != watched
+        });
+    }
+
+    fn topics(&self) -> BTreeSet<Topic> {
+        let state = self.state.lock();
+        std::iter::once(Topic::NewHeads)
+            .chain(state.accounts.iter().cloned().map(Topic::Account))
+            .chain(state.transactions.keys().cloned().map(Topic::Transaction))
+            .collect()
+    }
+
+    /// Record a transport change; returns the update to emit if the mode changed.
+    fn set_transport(
+        &self,
+        mode: TransportMode,
+        endpoint: Option<String>,
+        error: Option<String>,
+    ) -> Option<ChainUpdate> {
+        let mut state = self.state.lock();
+        match mode {
+            TransportMode::WebSocket => state.reconnect_attempts = 0,
+            TransportMode::Polling => state.reconnect_attempts += 1,
+            TransportMode::Connecting => {}
+        }
+        let changed = state.mode != mode;
+        state.mode = mode;
+        state.endpoint = endpoint.clone();
+        if error.is_some() {
+            state.last_error = error.clone();
+        }
+        changed.then_some(ChainUpdate::Transport {
+            mode,
+            endpoint,
+            error,
+        })
+    }
+
+    fn modify(&self, apply: impl FnOnce(&mut SubscriptionState) -> bool) {
+        if apply(&mut self.state.lock()) {
+            // `notify_one` keeps a permit, so a change made while the runner
+            // is busy is still seen on its next wait.
+            self.changed.notify_one();
+        }
+    }
+}
+
+/// Keep `subscriptions` fed with updates from `client`'s endpoints,
+/// preferring WebSocket push and polling otherwise. Each reconnect attempt
+/// tries the next endpoint, so one without `/ws` does not pin the wallet to
+/// polling.
+pub async fn run(
+    client: BlockchainClient,
+    subscriptions: ChainSubscriptions,
+    emit: impl Fn(ChainUpdate),
+) {
+    // A pushed balance change makes a cached balance stale.
+    let emit = |update: ChainUpdate| {
+        if matches!(update, ChainUpdate::Account { .. }) {
+            client.cache().invalidate(CachedRead::Balance);
+        }
+        emit(update)
+    };
+    let mut tracker = ChangeTracker::default();
+    // Endpoints whose WebSocket could not be opened since the last success.
+    let mut skipped = 0;
+    loop {
+        let candidates = client.pool().candidates();
+        let endpoint = match candidates.len() {
+            0 => None,
+            count => candidates.get(skipped % count),
+        };
+        let error = match endpoint.map(|url| websocket_url(url)) {
+            Some(Ok(url)) => {
+                match WsConnection::connect(&url, client.tls_config(), &client.proxy_routes()).await
+                {
+                    Ok(mut connection) => {
+                        skipped = 0;
+                        if let Some(update) = subscriptions.set_transport(
+                            TransportMode::WebSocket,
+                            Some(url.clone()),
+                            None,
+                        ) {
+                            emit(update);
+                        }
+                        let error = Session::default()
+                            .run(
+                                &mut connection,
+                                &client,
+                                &subscriptions,
+                                &mut tracker,
+                                &emit,
+                            )
+                            .await;
+                        let _ = connection.close().await;
+                        error
+                    }
+                    Err(err) => {
+                        skipped += 1;
+                        err
+                    }
+                }
+            }
+            Some(Err(err)) => {
+                skipped += 1;
+                err
+            }
+            None => WalletError::NetworkError("No RPC endpoints available".to_string()),
+        };
+
+        log::warn!("Chain subscription transport unavailable: {}", error);
+        if let Some(update) =
+            subscriptions.set_transport(TransportMode::Polling, None, Some(error.to_string()))
+        {
+            emit(update);
+        }
+
+        let attempts = subscriptions.status().reconnect_attempts;
+        let deadline = Instant::now() + reconnect_delay(attempts);
+        while Instant::now() < deadline {
+            subscriptions.expire_transactions(Instant::now());
+            let topics = subscriptions.topics();
+            if let Err(err) = tracker.poll(&client, &subscriptions, &topics, &emit).await {
+                log::warn!("Chain polling failed: {}", err);
+            }
+            tokio::time::sleep_until(deadline.min(Instant::now() + POLL_INTERVAL)).await;
+        }
+    }
+}
+
+/// The node's WebSocket endpoint for an HTTP RPC endpoint: same host, `ws`
+/// scheme, and `/ws` alongside the `/jsonrpc` path used for HTTP.
+fn websocket_url(http_url: &str) -> WalletResult<String> {
+    let mut url = Url::parse(http_url)
+        .map_err(|e| WalletError::ValidationError(format!("Invalid RPC endpoint: {}", e)))?;
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        "http" => "ws",
+        other => {
+            return Err(WalletError::ValidationError(format!(
+                "Unsupported RPC scheme: {}",
+                other
+            )))
+        }
+    };
+    url.set_scheme(scheme)
+        .map_err(|_| WalletError::ValidationError("Cannot derive WebSocket URL".to_string()))?;
+    let path = format!("{}/ws", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    Ok(url.to_string())
+}
+
+fn reconnect_delay(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(10);
+    (MIN_RECONNECT_DELAY * 2u32.pow(exponent)).min(MAX_RECONNECT_DELAY)
+}
+
+enum SessionEvent {
+    Message(WalletResult<WsMessage>),
+    TopicsChanged,
+    Ping,
+    Poll,
+}
+
+/// Subscription bookkeeping for one WebSocket connection.
+#[derive(Default)]
+struct Session {
+    next_id: u64,
+    /// Subscribe requests awaiting the node's subscription id.
+    pending: HashMap<u64, Topic>,
+    /// Subscription id to topic.
+    active: HashMap<String, Topic>,
+    /// Topics the node refused; these are polled instead.
+    unsupported: BTreeSet<Topic>,
+}
+
+impl Session {
+    /// Serve the connection until it fails, returning the reason.
+    async fn run(
+        mut self,
+        connection: &mut WsConnection,
+        client: &BlockchainClient,
+        subscriptions: &ChainSubscriptions,
+        tracker: &mut ChangeTracker,
+        emit: &impl Fn(ChainUpdate),
+    ) -> WalletError {
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        ping.tick().await;
+        poll.tick().await;
+        if let Err(err) = self.sync(connection, &subscriptions.topics()).await {
+            return err;
+        }
+
+        loop {
+            let event = tokio::select! {
+                message = connection.recv() => SessionEvent::Message(message),
+                _ = subscriptions.changed.notified() => SessionEvent::TopicsChanged,
+                _ = ping.tick() => SessionEvent::Ping,
+                _ = poll.tick(), if !self.unsupported.is_empty() => SessionEvent::Poll,
+            };
+
+            let outcome = match event {
+                SessionEvent::Message(Ok(WsMessage::Text(text))) => {
+                    self.handle_text(&text, connection, subscriptions, tracker, emit)
+                        .await
+                }
+                SessionEvent::Message(Ok(WsMessage::Close)) => Err(WalletError::NetworkError(
+                    "WebSocket closed by the node".to_string(),
+                )),
+                SessionEvent::Message(Err(err)) => Err(err),
+                SessionEvent::TopicsChanged => self.sync(connection, &subscriptions.topics()).await,
+                SessionEvent::Ping => {
+                    // Expiring a transaction wakes `TopicsChanged` to unsubscribe it.
+                    subscriptions.expire_transactions(Instant::now());
+                    connection.ping().await
+                }
+                SessionEvent::Poll => {
+                    let topics = subscriptions
+                        .topics()
+                        .intersection(&self.unsupported)
+                        .cloned()
+                        .collect();
+                    if let Err(err) = tracker.poll(client, subscriptions, &topics, emit).await {
+                        log::warn!("Polling unsupported topics failed: {}", err);
+                    }
+                    Ok(())
+                }
+            };
+            if let Err(err) = outcome {
+                return err;
+            }
+        }
+    }
+
+    /// Subscribe to new topics and drop subscriptions no longer wanted.
+    async fn sync(
+        &mut self,
+        connection: &mut WsConnection,
+        desired: &BTreeSet<Topic>,
+    ) -> WalletResult<()> {
+        self.unsupported.retain(|topic| desired.contains(topic));
+        let (subscribe, unsubscribe) = self.plan(desired);
+        for subscription in unsubscribe {
+            self.active.remove(&subscription);
+            let id = self.request_id();
+            self.send(
+                connection,
+                id,
+                "eth_unsubscribe",
+                serde_json::json!([subscription]),
+            )
+            .await?;
+        }
+        for topic in subscribe {
+            let id = self.request_id();
+            self.send(connection, id, "eth_subscribe", topic.subscribe_params())
+                .await?;
+            self.pending.insert(id, topic);
+        }
+        Ok(())
+    }
+
+    /// Topics to subscribe to and subscription ids to cancel so the
+    /// connection serves exactly `desired`.
+    fn plan(&self, desired: &BTreeSet<Topic>) -> (Vec<Topic>, Vec<String>) {
+        let covered: BTreeSet<&Topic> = self
+            .active
+            .values()
+            .chain(self.pending.values())
+            .chain(self.unsupported.iter())
+            .collect();
+        let subscribe = desired
+            .iter()
+            .filter(|topic| !covered.contains(topic))
+            .cloned()
+            .collect();
+        let unsubscribe = self
+            .active
+            .iter()
+            .filter(|(_, topic)| !desired.contains(topic))
+            .map(|(id, _)| id.clone())
+            .collect();
+        (subscribe, unsubscribe)
+    }
+
+    async fn handle_text(
+        &mut self,
+        text: &str,
+        connection: &mut WsConnection,
+        subscriptions: &ChainSubscriptions,
+        tracker: &mut ChangeTracker,
+        emit: &impl Fn(ChainUpdate),
+    ) -> WalletResult<()> {
+        let message: serde_json::Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(err) => {
+                log::warn!("Ignoring malformed WebSocket message: {}", err);
+                return Ok(());
+            }
+        };
+
+        if let Some(id) = message.get("id").and_then(|id| id.as_u64()) {
+            let Some(topic) = self.pending.remove(&id) else {
+                return Ok(());
+            };
+            match message.get("result").and_then(|result| result.as_str()) {
+                Some(subscription) => {
+                    self.active.insert(subscription.to_string(), topic);
+                    // The topic may have been dropped while the request was in flight.
+                    return self.sync(connection, &subscriptions.topics()).await;
+                }
+                None => {
+                    log::warn!(
+                        "Node refused subscription {:?}, polling instead: {}",
+                        topic,
+                        message.get("error").cloned().unwrap_or_default()
+                    );
+                    self.unsupported.insert(topic);
+                }
+            }
+            return Ok(());
+        }
+
+        let Some(params) = message
+            .get("params")
+            .filter(|_| message.get("method").and_then(|m| m.as_str()) == Some("eth_subscription"))
+        else {
+            return Ok(());
+        };
+        let topic = params
+            .get("subscription")
+            .and_then(|id| id.as_str())
+            .and_then(|id| self.active.get(id));
+        let (Some(topic), Some(result)) = (topic, params.get("result")) else {
+            return Ok(());
+        };
+        match tracker.observe(topic, result) {
+            Ok(Some(update)) => {
+                finish_if_included(&update, subscriptions);
+                emit(update);
+            }
+            Ok(None) => {}
+            Err(err) => log::warn!("Ignoring malformed {:?} notification: {}", topic, err),
+        }
+        Ok(())
+    }
+
+    fn request_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    async fn send(
+        &self,
+        connection: &mut WsConnection,
+        id: u64,
+        method: &str,
+        params: serde_json::Value,
+    ) -> WalletResult<()> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        connection.send_text(&request.to_string()).await
+    }
+}
+
+fn finish_if_included(update: &ChainUpdate, subscriptions: &ChainSubscriptions) {
+    if let ChainUpdate::Transaction {
+        transaction,
+        included: true,
+    } = update
+    {
+        subscriptions.finish_transaction(&transaction.transaction_id);
+    }
+}
+
+/// Last values seen per topic, so WebSocket and polling only report changes
+/// and switching between them does not repeat updates.
+#[derive(Default)]
+struct ChangeTracker {
+    head: Option<u64>,
+    accounts: HashMap<String, (String, u64)>,
+    transactions: HashMap<String, (String, Option<u64>)>,
+}
+
+impl ChangeTracker {
+    /// Interpret a pushed `result` for `topic`.
+    fn observe(
+        &mut self,
+        topic: &Topic,
+        result: &serde_json::Value,
+    ) -> WalletResult<Option<ChainUpdate>> {
+        Ok(match topic {
+            Topic::NewHeads => {
+                let height = result
+                    .get("number")
+                    .or_else(|| result.get("height"))
+                    .and_then(parse_height)
+                    .ok_or_else(|| {
+                        WalletError::InvalidResponse("Head without a block number".to_string())
+                    })?;
+                let hash = result
+                    .get("hash")
+                    .and_then(|hash| hash.as_str())
+                    .map(str::to_string);
+                self.head(height, hash)
+            }
+            Topic::Account(_) => self.account(serde_json::from_value(result.clone())?),
+            Topic::Transaction(_) => self.transaction(serde_json::from_value(result.clone())?),
+        })
+    }
+
+    fn head(&mut self, height: u64, hash: Option<String>) -> Option<ChainUpdate> {
+        if self.head.is_some_and(|seen| seen >= height) {
+            return None;
+        }
+        self.head = Some(height);
+        Some(ChainUpdate::NewHead { height, hash })
+    }
+
+    fn account(&mut self, balance: BalanceResponse) -> Option<ChainUpdate> {
+        let address = balance.address.to_ascii_lowercase();
+        let current = (balance.balance, balance.nonce);
+        if self.accounts.get(&address) == Some(&current) {
+            return None;
+        }
+        self.accounts.insert(address.clone(), current.clone());
+        Some(ChainUpdate::Account {
+            address,
+            balance: current.0,
+            nonce: current.1,
+        })
+    }
+
+    fn transaction(&mut self, transaction: TransactionInfo) -> Option<ChainUpdate> {
+        let current = (transaction.status.clone(), transaction.block_height);
+        if self.transactions.get(&transaction.transaction_id) == Some(&current) {
+            return None;
+        }
+        self.transactions
+            .insert(transaction.transaction_id.clone(), current);
+        Some(ChainUpdate::Transaction {
+            included: transaction.block_height.is_some(),
+            transaction,
+        })
+    }
+
+    /// Fetch `topics` over HTTP and emit whatever changed.
+    async fn poll(
+        &mut self,
+        client: &BlockchainClient,
+        subscriptions: &ChainSubscriptions,
+        topics: &BTreeSet<Topic>,
+        emit: &impl Fn(ChainUpdate),
+    ) -> WalletResult<()> {
+        if topics.contains(&Topic::NewHeads) {
+            let height = client.get_block_number().await?;
+            if let Some(update) = self.head(height, None) {
+                emit(update);
+            }
+        }
+
+        let mut batch = RpcBatch::new();
+        let balances: Vec<_> = topics
+            .iter()
+            .filter_map(|topic| match topic {
+                Topic::Account(address) => Some((address, batch.get_balance(address))),
+                _ => None,
+            })
+            .collect();
+        if !batch.is_empty() {
+            let response = client.send_batch(&batch).await?;
+            for (address, handle) in balances {
+                match response.get(handle) {
+                    Ok(balance) => {
+                        if let Some(update) = self.account(balance) {
+                            emit(update);
+                        }
+                    }
+                    Err(err) => log::warn!("Polling balance of {} failed: {}", address, err),
+                }
+            }
+        }
+
+        for topic in topics {
+            let Topic::Transaction(tx_id) = topic else {
+                continue;
+            };
+            // Unknown until the node has seen the transaction; keep waiting.
+            match client.get_transaction(tx_id).await {
+                Ok(transaction) => {
+                    if let Some(update) = self.transaction(transaction) {
+                        finish_if_included(&update, subscriptions);
+                        emit(update);
+                    }
+                }
+                Err(err) => log::debug!("Transaction {} not available yet: {}", tx_id, err),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_height(value: &serde_json::Value) -> Option<u64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_u64(),
+        serde_json::Value::String(text) => match text.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => text.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn websocket_url_follows_the_rpc_endpoint() {
+        assert_eq!(
+            websocket_url("https://rpc.example.org").unwrap(),
+            "wss://rpc.example.org/ws"
+        );
+        assert_eq!(
+            websocket_url("http://localhost:8545/node/").unwrap(),
+            "ws://localhost:8545/node/ws"
+        );
+        assert!(websocket_url("ftp://rpc.example.org").is_err());
+        assert_eq!(reconnect_delay(1), MIN_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(30), MAX_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn resubscription_covers_only_missing_topics() {
+        let mut session = Session::default();
+        session
+            .active
+            .insert("0xa".to_string(), Topic::Account("0x1".to_string()));
+        session
+            .active
+            .insert("0xb".to_string(), Topic::Account("0x2".to_string()));
+        session.pending.insert(1, Topic::NewHeads);
+
+        let desired = BTreeSet::from([
+            Topic::NewHeads,
+            Topic::Account("0x1".to_string()),
+            Topic::Transaction("tx".to_string()),
+        ]);
+        let (subscribe, unsubscribe) = session.plan(&desired);
+        assert_eq!(subscribe, vec![Topic::Transaction("tx".to_string())]);
+        assert_eq!(unsubscribe, vec!["0xb".to_string()]);
+    }
+
+    #[test]
+    fn tracker_reports_changes_once_and_finishes_included_transactions() {
+        let mut tracker = ChangeTracker::default();
+        let head = serde_json::json!({ "number": "0x10", "hash": "0xabc" });
+        assert!(matches!(
+            tracker.observe(&Topic::NewHeads, &head).unwrap(),
+            Some(ChainUpdate::NewHead { height: 16, .. })
+        ));
+        assert!(tracker.observe(&Topic::NewHeads, &head).unwrap().is_none());
+
+        let account = Topic::Account("0x1".to_string());
+        let balance = serde_json::json!({ "address": "0x1", "balance": "5", "nonce": 1 });
+        assert!(tracker.observe(&account, &balance).unwrap().is_some());
+        assert!(tracker.observe(&account, &balance).unwrap().is_none());
+
+        let subscriptions = ChainSubscriptions::default();
+        subscriptions.watch_transaction("tx1");
+        let mined = serde_json::json!({
+            "transactionId": "tx1",
+            "fromAddress": "0x1",
+            "toAddress": "0x2",
+            "amount": "1",
+            "fee": "0",
+            "status": "confirmed",
+            "timestamp": "0",
+            "blockHeight": 42,
+        });
+        let update = tracker
+            .observe(&Topic::Transaction("tx1".to_string()), &mined)
+            .unwrap()
+            .expect("inclusion is reported");
+        finish_if_included(&update, &subscriptions);
+        assert!(subscriptions.status().transactions.is_empty());
+    }
+
+    #[test]
+    fn transactions_that_never_show_up_stop_being_watched() {
+        let subscriptions = ChainSubscriptions::default();
+        subscriptions.watch_transaction("lost");
+        let now = Instant::now();
+        subscriptions.expire_transactions(now);
+        assert_eq!(
+            subscriptions.status().transactions,
+            vec!["lost".to_string()]
+        );
+
+        subscriptions.expire_transactions(now + TRANSACTION_WATCH_LIMIT);
+        assert!(subscriptions.status().transactions.is_empty());
+        assert_eq!(subscriptions.topics(), BTreeSet::from([Topic::NewHeads]));
+    }
+}
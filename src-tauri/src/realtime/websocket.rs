@@ -0,0 +1,194 @@
+/// WebSocket client for JSON-RPC subscriptions
+///
+/// A thin wrapper over `tokio-tungstenite` that opens the TCP connection
+/// along the client's proxy route and secures `wss://` with the same TLS
+/// settings as HTTP requests. `recv` is cancel-safe, so it can sit in a
+/// `select!` without losing partial messages.
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Url;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::ClientConfig;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::errors::{WalletError, WalletResult};
+use crate::proxy::ProxyRoutes;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest message accepted from the node.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Close,
+}
+
+pub struct WsConnection {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WsConnection {
+    /// Open a connection to a `ws://` or `wss://` URL along its route in
+    /// `proxy`, verifying `wss://` servers with `tls`.
+    pub async fn connect(
+        url: &str,
+        tls: Arc<ClientConfig>,
+        proxy: &ProxyRoutes,
+    ) -> WalletResult<Self> {
+        let parsed = Url::parse(url)
+            .map_err(|e| WalletError::ValidationError(format!("Invalid WebSocket URL: {}", e)))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| WalletError::ValidationError("WebSocket URL has no host".to_string()))?;
+        let connector = match parsed.scheme() {
+            "wss" => Connector::Rustls(tls),
+            "ws" => Connector::Plain,
+            other => {
+                return Err(WalletError::ValidationError(format!(
+                    "Unsupported WebSocket scheme: {}",
+                    other
+                )))
+            }
+        };
+        let port = parsed
+            .port_or_known_default()
+            .ok_or_else(|| WalletError::ValidationError("WebSocket URL has no port".to_string()))?;
+
+        let config = WebSocketConfig::default()
+            .max_message_size(Some(MAX_MESSAGE_BYTES))
+            .max_frame_size(Some(MAX_MESSAGE_BYTES));
+        let handshake = async {
+            let tcp = proxy.connect(host, port).await?;
+            tokio_tungstenite::client_async_tls_with_config(url, tcp, Some(config), Some(connector))
+                .await
+                .map_err(|e| ws_error("WebSocket handshake failed", e))
+        };
+        let (stream, _) = tokio::time::timeout(CONNECT_TIMEOUT, handshake)
+            .await
+            .map_err(|_| WalletError::ConnectionTimeout)??;
+        Ok(Self { stream })
+    }
+
+    pub async fn send_text(&mut self, text: &str) -> WalletResult<()> {
+        self.send(Message::text(text)).await
+    }
+
+    pub async fn ping(&mut self) -> WalletResult<()> {
+        self.send(Message::Ping(Default::default())).await
+    }
+
+    pub async fn close(&mut self) -> WalletResult<()> {
+        self.stream
+            .close(None)
+            .await
+            .map_err(|e| ws_error("WebSocket close failed", e))
+    }
+
+    /// Next text message, or `Close`. Pings are answered as they arrive.
+    pub async fn recv(&mut self) -> WalletResult<WsMessage> {
+        loop {
+            let message = self
+                .stream
+                .next()
+                .await
+                .ok_or_else(|| {
+                    WalletError::NetworkError("WebSocket closed by the node".to_string())
+                })?
+                .map_err(|e| ws_error("WebSocket read failed", e))?;
+            match message {
+                Message::Text(text) => return Ok(WsMessage::Text(text.to_string())),
+                Message::Binary(bytes) => {
+                    return String::from_utf8(bytes.to_vec())
+                        .map(WsMessage::Text)
+                        .map_err(|_| {
+                            WalletError::InvalidResponse(
+                                "WebSocket message is not UTF-8".to_string(),
+                            )
+                        })
+                }
+                // Flushing sends the replies tungstenite queued for these.
+                Message::Close(_) => {
+                    let _ = self.stream.flush().await;
+                    return Ok(WsMessage::Close);
+                }
+                Message::Ping(_) => self
+                    .stream
+                    .flush()
+                    .await
+                    .map_err(|e| ws_error("WebSocket write failed", e))?,
+                Message::Pong(_) | Message::Frame(_) => {}
+            }
+        }
+    }
+
+    async fn send(&mut self, message: Message) -> WalletResult<()> {
+        self.stream
+            .send(message)
+            .await
+            .map_err(|e| ws_error("WebSocket write failed", e))
+    }
+}
+
+fn ws_error(context: &str, error: tungstenite::Error) -> WalletError {
+    match error {
+        tungstenite::Error::Capacity(e) => {
+            WalletError::InvalidResponse(format!("{}: {}", context, e))
+        }
+        tungstenite::Error::Protocol(e) => {
+            WalletError::InvalidResponse(format!("{}: {}", context, e))
+        }
+        other => WalletError::NetworkError(format!("{}: {}", context, other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn exchanges_messages_with_a_websocket_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            socket
+                .send(Message::Ping(Default::default()))
+                .await
+                .unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                if message.is_text() {
+                    socket.send(message).await.unwrap();
+                    socket.close(None).await.unwrap();
+                }
+            }
+        });
+
+        let tls = crate::tls::default_config().unwrap();
+        let url = format!("ws://{}/ws", address);
+        let mut connection = WsConnection::connect(&url, tls, &ProxyRoutes::default())
+            .await
+            .unwrap();
+        connection.send_text("hello").await.unwrap();
+        assert_eq!(
+            connection.recv().await.unwrap(),
+            WsMessage::Text("hello".to_string())
+        );
+        assert_eq!(connection.recv().await.unwrap(), WsMessage::Close);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn refuses_unsupported_schemes() {
+        let tls = crate::tls::default_config().unwrap();
+        let result =
+            WsConnection::connect("http://127.0.0.1:1/ws", tls, &ProxyRoutes::default()).await;
+        assert!(matches!(result, Err(WalletError::ValidationError(_))));
+    }
+}
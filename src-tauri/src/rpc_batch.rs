@@ -0,0 +1,195 @@
+/// Typed builder for JSON-RPC 2.0 batch requests
+///
+/// Calls are queued on an `RpcBatch`, each returning a handle that remembers
+/// the response type. `BlockchainClient::send_batch` sends the whole batch in
+/// one round trip and the handles read the matching results back out.
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+
+use crate::api::types::{
+    BalanceResponse, GetAutoStakeStatusResponse, GetDelegationsResponse, GetLockboxRecordsResponse,
+    GetStakingRewardsResponse, GetUnbondingEntriesResponse, GetUserDelegationsResponse,
+    GetVotingPowerResponse,
+};
+use crate::errors::{WalletError, WalletResult};
+
+/// Most calls sent in a single HTTP request; larger batches are split.
+pub const MAX_BATCH_CALLS: usize = 50;
+
+pub(crate) struct BatchCall {
+    pub method: &'static str,
+    pub params: serde_json::Value,
+    /// A read a single lying node could exploit, as with `rpc_call_verified`.
+    pub critical: bool,
+}
+
+/// A queued call whose result deserializes to `T`.
+#[derive(Debug)]
+pub struct BatchHandle<T> {
+    index: usize,
+    _response: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for BatchHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BatchHandle<T> {}
+
+/// Read-only calls grouped into one request. Only reads are offered, so a
+/// batch may be resent to another endpoint if the first one fails. While
+/// quorum mode or the light client is enabled, critical reads (balances and
+/// voting power) are left out of the batch and checked like single calls.
+#[derive(Default)]
+pub struct RpcBatch {
+    calls: Vec<BatchCall>,
+}
+
+impl RpcBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    pub fn get_balance(&mut self, address: &str) -> BatchHandle<BalanceResponse> {
+        self.push_critical("get_balance", serde_json::json!({ "address": address }))
+    }
+
+    pub fn get_user_delegations(
+        &mut self,
+        address: &str,
+    ) -> BatchHandle<GetUserDelegationsResponse> {
+        self.push(
+            "staking_get_user_delegations",
+            serde_json::json!({ "address": address }),
+        )
+    }
+
+    pub fn get_staking_rewards(&mut self, address: &str) -> BatchHandle<GetStakingRewardsResponse> {
+        self.push(
+            "staking_get_rewards",
+            serde_json::json!({ "address": address }),
+        )
+    }
+
+    pub fn get_lockbox_records(&mut self, address: &str) -> BatchHandle<GetLockboxRecordsResponse> {
+        self.push(
+            "staking_get_lockbox_records",
+            serde_json::json!({ "account": address }),
+        )
+    }
+
+    pub fn get_auto_stake_status(
+        &mut self,
+        address: &str,
+    ) -> BatchHandle<GetAutoStakeStatusResponse> {
+        self.push(
+            "staking_get_auto_stake_status",
+            serde_json::json!({ "account": address }),
+        )
+    }
+
+    /// Read with `BatchResponse::get_optional`: older nodes lack the method.
+    pub fn get_unbonding_entries(
+        &mut self,
+        address: &str,
+    ) -> BatchHandle<GetUnbondingEntriesResponse> {
+        self.push(
+            "staking_get_unbonding_entries",
+            serde_json::json!({ "address": address }),
+        )
+    }
+
+    pub fn get_voting_power(&mut self, address: &str) -> BatchHandle<GetVotingPowerResponse> {
+        self.push_critical(
+            "governance_get_voting_power",
+            serde_json::json!({ "address": address }),
+        )
+    }
+
+    pub fn get_delegations(&mut self, address: &str) -> BatchHandle<GetDelegationsResponse> {
+        self.push(
+            "governance_get_delegations",
+            serde_json::json!({ "address": address }),
+        )
+    }
+
+    pub(crate) fn calls(&self) -> &[BatchCall] {
+        &self.calls
+    }
+
+    fn push<T>(&mut self, method: &'static str, params: serde_json::Value) -> BatchHandle<T> {
+        self.queue(method, params, false)
+    }
+
+    fn push_critical<T>(
+        &mut self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> BatchHandle<T> {
+        self.queue(method, params, true)
+    }
+
+    fn queue<T>(
+        &mut self,
+        method: &'static str,
+        params: serde_json::Value,
+        critical: bool,
+    ) -> BatchHandle<T> {
+        self.calls.push(BatchCall {
+            method,
+            params,
+            critical,
+        });
+        BatchHandle {
+            index: self.calls.len() - 1,
+            _response: PhantomData,
+        }
+    }
+}
+
+/// Per-call results of a sent batch, in the order the calls were queued.
+#[derive(Debug)]
+pub struct BatchResponse {
+    results: Vec<WalletResult<serde_json::Value>>,
+}
+
+impl BatchResponse {
+    pub(crate) fn new(results: Vec<WalletResult<serde_json::Value>>) -> Self {
+        Self { results }
+    }
+
+    pub fn get<T: for<'de> Deserialize<'de>>(&self, handle: BatchHandle<T>) -> WalletResult<T> {
+        match self.results.get(handle.index) {
+            Some(Ok(value)) => serde_json::from_value(value.clone()).map_err(|e| {
+                WalletError::InvalidResponse(format!("Unexpected batch result: {}", e))
+            }),
+            Some(Err(err)) => Err(err.clone()),
+            None => Err(WalletError::InvalidResponse(
+                "Handle does not belong to this batch".to_string(),
+            )),
+        }
+    }
+
+    /// Like `get`, but maps a method-not-found error to `Ok(None)`.
+    pub fn get_optional<T: for<'de> Deserialize<'de>>(
+        &self,
+        handle: BatchHandle<T>,
+    ) -> WalletResult<Option<T>> {
+        match self.get(handle) {
+            Ok(value) => Ok(Some(value)),
+            Err(WalletError::MethodNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
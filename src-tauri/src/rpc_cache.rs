@@ -0,0 +1,374 @@
+/// Response caching and per-endpoint throttling for RPC traffic
+///
+/// A few reads are refreshed far more often by the UI than their answers
+/// change. `ResponseCache` keeps them for a short, per-method time and drops
+/// them whenever the wallet writes to the chain. `RateLimiter` spaces out
+/// requests to each endpoint with a token bucket so quick refreshes do not get
+/// the wallet banned by public nodes.
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Most responses kept at once; the oldest are dropped first.
+const MAX_ENTRIES: usize = 512;
+/// Requests an endpoint may receive back to back.
+const BURST: f64 = 20.0;
+/// Sustained requests per second to one endpoint.
+const REFILL_PER_SEC: f64 = 10.0;
+
+/// A read whose responses may be served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CachedRead {
+    Balance,
+    Validators,
+    Proposals,
+}
+
+impl CachedRead {
+    pub const ALL: [CachedRead; 3] = [
+        CachedRead::Balance,
+        CachedRead::Validators,
+        CachedRead::Proposals,
+    ];
+
+    pub fn ttl(self) -> Duration {
+        match self {
+            CachedRead::Balance => Duration::from_secs(10),
+            CachedRead::Validators => Duration::from_secs(60),
+            CachedRead::Proposals => Duration::from_secs(30),
+        }
+    }
+
+    /// Whether submitting `write` may change this read's answer. Every write
+    /// pays a fee, so balances are always dropped.
+    pub fn invalidated_by(self, write: &str) -> bool {
+        match self {
+            CachedRead::Balance => true,
+            CachedRead::Validators => write.starts_with("staking_"),
+            CachedRead::Proposals => write.starts_with("governance_"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodCacheStats {
+    pub read: CachedRead,
+    pub ttl_secs: u64,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointThrottleStats {
+    pub url: String,
+    /// Requests that had to wait for a token.
+    pub throttled: u64,
+    pub waited_ms: u64,
+}
+
+/// Cache and throttle counters, for debugging from the settings page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub methods: Vec<MethodCacheStats>,
+    pub endpoints: Vec<EndpointThrottleStats>,
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    stored: Instant,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    hits: u64,
+    misses: u64,
+    invalidations: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<(CachedRead, String), Entry>,
+    counters: HashMap<CachedRead, Counters>,
+    /// Bumped on every invalidation so reads started before it are not stored.
+    generation: u64,
+}
+
+impl CacheState {
+    fn counters(&mut self, read: CachedRead) -> &mut Counters {
+        self.counters.entry(read).or_default()
+    }
+}
+
+/// Recent responses of `CachedRead` calls, shared by clones of a client.
+#[derive(Clone)]
+pub struct ResponseCache {
+    state: Arc<Mutex<CacheState>>,
+    serve_reads: bool,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self {
+            state: Arc::default(),
+            serve_reads: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("entries", &self.state.lock().entries.len())
+            .field("serve_reads", &self.serve_reads)
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    /// A handle that never answers reads but still invalidates this cache,
+    /// for clients pointed at another endpoint.
+    pub fn invalidation_only(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            serve_reads: false,
+        }
+    }
+
+    /// The cached answer to `read` with `key`, if still fresh.
+    pub fn get<T: Clone + 'static>(&self, read: CachedRead, key: &str) -> Option<T> {
+        if !self.serve_reads {
+            return None;
+        }
+        let mut state = self.state.lock();
+        let hit = state
+            .entries
+            .get(&(read, key.to_string()))
+            .filter(|entry| entry.stored.elapsed() < read.ttl())
+            .and_then(|entry| entry.value.downcast_ref::<T>().cloned());
+        let counters = state.counters(read);
+        if hit.is_some() {
+            counters.hits += 1;
+        } else {
+            counters.misses += 1;
+        }
+        hit
+    }
+
+    /// Current generation, taken before fetching a value to `insert`.
+    pub fn generation(&self) -> u64 {
+        self.state.lock().generation
+    }
+
+    /// Store a fetched answer unless the cache was invalidated since
+    /// `generation`, which would make it possibly stale.
+    pub fn insert<T: Send + Sync + 'static>(
+        &self,
+        read: CachedRead,
+        key: String,
+        value: T,
+        generation: u64,
+    ) {
+        if !self.serve_reads {
+            return;
+        }
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        if state.entries.len() >= MAX_ENTRIES {
+            state
+                .entries
+                .retain(|(read, _), entry| entry.stored.elapsed() < read.ttl());
+        }
+        if state.entries.len() >= MAX_ENTRIES {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(
+            (read, key),
+            Entry {
+                value: Arc::new(value),
+                stored: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop every answer of `read`.
+    pub fn invalidate(&self, read: CachedRead) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        state.entries.retain(|(cached, _), _| *cached != read);
+        state.counters(read).invalidations += 1;
+    }
+
+    /// Drop answers that submitting `write` may have changed.
+    pub fn invalidate_after(&self, write: &str) {
+        for read in CachedRead::ALL {
+            if read.invalidated_by(write) {
+                self.invalidate(read);
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        for read in CachedRead::ALL {
+            self.invalidate(read);
+        }
+    }
+
+    pub fn stats(&self) -> Vec<MethodCacheStats> {
+        let mut state = self.state.lock();
+        CachedRead::ALL
+            .into_iter()
+            .map(|read| {
+                let entries = state
+                    .entries
+                    .iter()
+                    .filter(|((cached, _), entry)| {
+                        *cached == read && entry.stored.elapsed() < read.ttl()
+                    })
+                    .count();
+                let counters = *state.counters(read);
+                MethodCacheStats {
+                    read,
+                    ttl_secs: read.ttl().as_secs(),
+                    entries,
+                    hits: counters.hits,
+                    misses: counters.misses,
+                    invalidations: counters.invalidations,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    throttled: u64,
+    waited: Duration,
+}
+
+/// Token bucket per endpoint URL, shared by clones of a client.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Wait until `url` may receive another request.
+    pub async fn acquire(&self, url: &str) {
+        let wait = self.reserve(url, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token for `url`, returning how long to wait before using it.
+    /// The bucket may go negative, so concurrent callers queue up in turn.
+    fn reserve(&self, url: &str, now: Instant) -> Duration {
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets.entry(url.to_string()).or_insert(Bucket {
+            tokens: BURST,
+            refilled: now,
+            throttled: 0,
+            waited: Duration::ZERO,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * REFILL_PER_SEC).min(BURST);
+        bucket.refilled = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+        let wait = Duration::from_secs_f64(-bucket.tokens / REFILL_PER_SEC);
+        bucket.throttled += 1;
+        bucket.waited += wait;
+        wait
+    }
+
+    pub fn stats(&self) -> Vec<EndpointThrottleStats> {
+        let mut stats: Vec<EndpointThrottleStats> = self
+            .buckets
+            .lock()
+            .iter()
+            .map(|(url, bucket)| EndpointThrottleStats {
+                url: url.clone(),
+                throttled: bucket.throttled,
+                waited_ms: bucket.waited.as_millis() as u64,
+            })
+            .collect();
+        stats.sort_by(|a, b| a.url.cmp(&b.url));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_invalidate_affected_reads_only() {
+        let cache = ResponseCache::default();
+        for read in CachedRead::ALL {
+            let generation = cache.generation();
+            cache.insert(read, "{}".to_string(), 7u64, generation);
+        }
+        assert_eq!(cache.get::<u64>(CachedRead::Validators, "{}"), Some(7));
+        assert_eq!(cache.get::<u64>(CachedRead::Balance, "other"), None);
+
+        cache.invalidate_after("governance_cast_vote");
+        assert_eq!(cache.get::<u64>(CachedRead::Proposals, "{}"), None);
+        assert_eq!(cache.get::<u64>(CachedRead::Balance, "{}"), None);
+        assert_eq!(cache.get::<u64>(CachedRead::Validators, "{}"), Some(7));
+
+        let validators = &cache.stats()[1];
+        assert_eq!((validators.hits, validators.misses), (2, 0));
+        assert!(cache
+            .invalidation_only()
+            .get::<u64>(CachedRead::Validators, "{}")
+            .is_none());
+    }
+
+    #[test]
+    fn reads_started_before_an_invalidation_are_not_stored() {
+        let cache = ResponseCache::default();
+        let generation = cache.generation();
+        cache.invalidate_after("send_transaction");
+        cache.insert(CachedRead::Balance, "a".to_string(), 1u64, generation);
+        assert_eq!(cache.get::<u64>(CachedRead::Balance, "a"), None);
+    }
+
+    #[test]
+    fn bucket_allows_a_burst_then_spaces_requests() {
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+        for _ in 0..BURST as usize {
+            assert!(limiter.reserve("https://a", start).is_zero());
+        }
+        let wait = limiter.reserve("https://a", start);
+        assert_eq!(wait, Duration::from_millis(100));
+        assert!(limiter.reserve("https://b", start).is_zero());
+        assert!(limiter
+            .reserve("https://a", start + Duration::from_secs(1))
+            .is_zero());
+        assert_eq!(limiter.stats()[0].throttled, 1);
+    }
+}
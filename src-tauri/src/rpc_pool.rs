@@ -0,0 +1,298 @@
+/// Shared set of RPC endpoints with per-endpoint health tracking
+///
+/// Every request made through a pooled `BlockchainClient` reports its outcome
+/// here. Endpoints are ordered by health so that failing, lagging or slow
+/// nodes are tried last, and repeatedly failing ones are backed off.
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::config_store::NetworkConfig;
+use crate::errors::{WalletError, WalletResult};
+
+/// How often endpoints are probed for block height and reachability.
+pub const HEALTH_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Blocks an endpoint may trail the best known height before it is treated as lagging.
+const MAX_BLOCK_LAG: u64 = 5;
+/// Weight of the newest sample in the latency and error-rate moving averages.
+const SMOOTHING: f64 = 0.3;
+const BASE_BACKOFF_SECS: i64 = 5;
+const MAX_BACKOFF_SECS: i64 = 300;
+/// Latency assumed for endpoints that have not answered yet.
+const UNKNOWN_LATENCY_MS: f64 = 500.0;
+
+/// Health of one endpoint, as shown on the settings page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointHealth {
+    pub url: String,
+    /// Smoothed round-trip latency of successful requests.
+    pub latency_ms: Option<f64>,
+    /// Smoothed share of recent requests that failed, between 0 and 1.
+    pub error_rate: f64,
+    pub requests: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub block_height: Option<u64>,
+    pub lagging: bool,
+    /// The endpoint is skipped until this time unless no other endpoint is available.
+    pub backoff_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_checked: Option<DateTime<Utc>>,
+}
+
+impl EndpointHealth {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            latency_ms: None,
+            error_rate: 0.0,
+            requests: 0,
+            failures: 0,
+            consecutive_failures: 0,
+            block_height: None,
+            lagging: false,
+            backoff_until: None,
+            last_error: None,
+            last_checked: None,
+        }
+    }
+
+    pub fn is_backed_off(&self, now: DateTime<Utc>) -> bool {
+        self.backoff_until.is_some_and(|until| until > now)
+    }
+
+    /// Lower is better: latency inflated by the recent error rate.
+    pub fn score(&self) -> f64 {
+        self.latency_ms.unwrap_or(UNKNOWN_LATENCY_MS) * (1.0 + 4.0 * self.error_rate)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcPool {
+    endpoints: Arc<RwLock<Vec<EndpointHealth>>>,
+}
+
+impl RpcPool {
+    pub fn new(urls: Vec<String>) -> WalletResult<Self> {
+        let pool = Self {
+            endpoints: Arc::new(RwLock::new(Vec::new())),
+        };
+        pool.set_endpoints(urls)?;
+        Ok(pool)
+    }
+
+    /// Pool over the primary and failover endpoints of `config`.
+    pub fn from_config(config: &NetworkConfig) -> WalletResult<Self> {
+        Self::new(endpoint_urls(config))
+    }
+
+    /// Replace the endpoint set, keeping the history of endpoints that remain.
+    pub fn set_endpoints(&self, urls: Vec<String>) -> WalletResult<()> {
+        let mut normalized: Vec<String> = Vec::with_capacity(urls.len());
+        for url in urls {
+            let url = url.trim().trim_end_matches('/').to_string();
+            if !url.is_empty() && !normalized.contains(&url) {
+                normalized.push(url);
+            }
+        }
+        if normalized.is_empty() {
+            return Err(WalletError::ValidationError(
+                "No RPC endpoints configured".to_string(),
+            ));
+        }
+
+        let mut endpoints = self.endpoints.write();
+        let mut previous = std::mem::take(&mut *endpoints);
+        *endpoints = normalized
+            .into_iter()
+            .map(|url| match previous.iter().position(|e| e.url == url) {
+                Some(index) => previous.swap_remove(index),
+                None => EndpointHealth::new(url),
+            })
+            .collect();
+        Ok(())
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.endpoints
+            .read()
+            .iter()
+            .map(|e| e.url.clone())
+            .collect()
+    }
+
+    /// Endpoints in the order requests should try them: healthy before
+    /// lagging, backed-off endpoints last, and by score within each group.
+    pub fn candidates(&self) -> Vec<String> {
+        let now = Utc::now();
+        let mut endpoints = self.endpoints.read().clone();
+        endpoints.sort_by(|a, b| {
+            (a.is_backed_off(now), a.lagging)
+                .cmp(&(b.is_backed_off(now), b.lagging))
+                .then(a.score().total_cmp(&b.score()))
+        });
+        endpoints.into_iter().map(|e| e.url).collect()
+    }
+
+    /// Candidates that are not known to trail the rest of the pool.
+    pub fn in_sync(&self) -> Vec<String> {
+        let lagging: Vec<String> = self
+            .endpoints
+            .read()
+            .iter()
+            .filter(|e| e.lagging)
+            .map(|e| e.url.clone())
+            .collect();
+        self.candidates()
+            .into_iter()
+            .filter(|url| !lagging.contains(url))
+            .collect()
+    }
+
+    pub fn record_success(&self, url: &str, latency: Duration) {
+        self.update(url, |endpoint| {
+            let sample = latency.as_secs_f64() * 1000.0;
+            endpoint.latency_ms = Some(match endpoint.latency_ms {
+                Some(current) => current + SMOOTHING * (sample - current),
+                None => sample,
+            });
+            endpoint.error_rate *= 1.0 - SMOOTHING;
+            endpoint.requests += 1;
+            endpoint.consecutive_failures = 0;
+            endpoint.backoff_until = None;
+            endpoint.last_checked = Some(Utc::now());
+        });
+    }
+
+    /// Record a failed request. Errors that are not the endpoint's fault
+    /// (e.g. invalid parameters) count as a successful round trip instead.
+    pub fn record_failure(&self, url: &str, error: &WalletError, latency: Duration) {
+        if !error.is_retryable() {
+            self.record_success(url, latency);
+            return;
+        }
+        self.update(url, |endpoint| {
+            let now = Utc::now();
+            endpoint.error_rate += SMOOTHING * (1.0 - endpoint.error_rate);
+            endpoint.requests += 1;
+            endpoint.failures += 1;
+            endpoint.consecutive_failures += 1;
+            endpoint.last_error = Some(error.to_string());
+            endpoint.last_checked = Some(now);
+
+            let exponent = endpoint.consecutive_failures.saturating_sub(1).min(16);
+            let backoff = (BASE_BACKOFF_SECS << exponent).min(MAX_BACKOFF_SECS);
+            endpoint.backoff_until = Some(now + chrono::Duration::seconds(backoff));
+        });
+    }
+
+    /// Record the block height reported by `url` and re-evaluate lag across the pool.
+    pub fn record_height(&self, url: &str, height: u64) {
+        let mut endpoints = self.endpoints.write();
+        if let Some(endpoint) = endpoints.iter_mut().find(|e| e.url == url) {
+            endpoint.block_height = Some(height);
+        }
+        let best = endpoints.iter().filter_map(|e| e.block_height).max();
+        for endpoint in endpoints.iter_mut() {
+            endpoint.lagging = match (endpoint.block_height, best) {
+                (Some(height), Some(best)) => height + MAX_BLOCK_LAG < best,
+                _ => false,
+            };
+        }
+    }
+
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.endpoints.read().clone()
+    }
+
+    fn update(&self, url: &str, apply: impl FnOnce(&mut EndpointHealth)) {
+        if let Some(endpoint) = self.endpoints.write().iter_mut().find(|e| e.url == url) {
+            apply(endpoint);
+        }
+    }
+}
+
+/// Primary endpoint followed by the failover endpoints.
+pub fn endpoint_urls(config: &NetworkConfig) -> Vec<String> {
+    let mut urls = Vec::with_capacity(1 + config.failover_endpoints.len());
+    urls.push(config.primary_endpoint.clone());
+    urls.extend(config.failover_endpoints.iter().cloned());
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "https://a.example";
+    const B: &str = "https://b.example";
+    const C: &str = "https://c.example";
+
+    fn pool() -> RpcPool {
+        RpcPool::new(vec![A.to_string(), B.to_string(), C.to_string()]).unwrap()
+    }
+
+    #[test]
+    fn prefers_fast_healthy_endpoints() {
+        let pool = pool();
+        pool.record_success(A, Duration::from_millis(400));
+        pool.record_success(B, Duration::from_millis(50));
+        pool.record_success(C, Duration::from_millis(100));
+        assert_eq!(pool.candidates(), vec![B, C, A]);
+
+        pool.record_failure(
+            B,
+            &WalletError::NetworkError("reset".to_string()),
+            Duration::from_millis(50),
+        );
+        let candidates = pool.candidates();
+        assert_eq!(candidates.last().map(String::as_str), Some(B));
+        let health = pool.health();
+        let b = health.iter().find(|e| e.url == B).unwrap();
+        assert!(b.backoff_until.is_some());
+        assert_eq!(b.failures, 1);
+    }
+
+    #[test]
+    fn request_errors_do_not_penalise_the_endpoint() {
+        let pool = pool();
+        pool.record_failure(
+            A,
+            &WalletError::InvalidParams("bad".to_string()),
+            Duration::from_millis(10),
+        );
+        let health = pool.health();
+        assert_eq!(health[0].failures, 0);
+        assert!(health[0].backoff_until.is_none());
+    }
+
+    #[test]
+    fn lagging_endpoints_are_tried_after_current_ones() {
+        let pool = pool();
+        pool.record_height(A, 100);
+        pool.record_height(B, 120);
+        pool.record_height(C, 118);
+
+        assert_eq!(pool.candidates()[2], A);
+        assert!(pool.health()[0].lagging);
+        assert!(!pool.health()[2].lagging);
+    }
+
+    #[test]
+    fn replacing_endpoints_keeps_history_of_retained_ones() {
+        let pool = pool();
+        pool.record_success(B, Duration::from_millis(10));
+        pool.set_endpoints(vec![format!("{}/", B), "https://d.example".to_string()])
+            .unwrap();
+
+        let health = pool.health();
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].requests, 1);
+        assert!(pool.set_endpoints(vec![" ".to_string()]).is_err());
+    }
+}
@@ -93,6 +93,14 @@ impl RuntimeSecurityState {
         self.push.register(request)
     }
 
+    /// Registration ids of devices subscribed to `category`.
+    pub fn push_subscribers(&self, category: AlertCategory) -> Vec<String> {
+        if !self.policy.push_enabled {
+            return Vec::new();
+        }
+        self.push.subscribers(AlertFlags::for_category(category))
+    }
+
     pub fn create_passkey(
         &self,
         request: &PasskeyCreateRequest,
@@ -337,8 +345,15 @@ impl BiometricRegistry {
     }
 }
 
+/// Push notification categories a device can opt into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertCategory {
+    Transaction,
+    Staking,
+    Governance,
+}
+
 #[derive(Clone, Copy, Debug)]
-#[allow(dead_code)]
 struct AlertFlags(u8);
 
 impl AlertFlags {
@@ -346,6 +361,18 @@ impl AlertFlags {
     const STAKING: u8 = 0b010;
     const GOVERNANCE: u8 = 0b100;
 
+    fn for_category(category: AlertCategory) -> u8 {
+        match category {
+            AlertCategory::Transaction => Self::TX,
+            AlertCategory::Staking => Self::STAKING,
+            AlertCategory::Governance => Self::GOVERNANCE,
+        }
+    }
+
+    fn contains(self, flag: u8) -> bool {
+        self.0 & flag != 0
+    }
+
     fn from_request(request: &PushNotificationRegisterRequest) -> WalletResult<Self> {
         let mut flags = 0u8;
         if request.enable_transaction_alerts {
@@ -479,6 +506,16 @@ impl PushRegistry {
             registration_id: Some(hex::encode(registration_id)),
         })
     }
+
+    fn subscribers(&self, flag: u8) -> Vec<String> {
+        let entries = self.entries.lock();
+        assert!(entries.len() <= MAX_PUSH_REGISTRATIONS);
+        entries
+            .iter()
+            .filter(|entry| entry.alerts.contains(flag))
+            .map(|entry| hex::encode(entry.registration_id))
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -747,6 +784,36 @@ mod tests {
         assert!(after.permission_granted, "permission remains granted");
     }
 
+    #[test]
+    fn push_subscribers_filter_by_alert_category() {
+        let state = runtime_state();
+        let staking = state
+            .register_push(&PushNotificationRegisterRequest {
+                token: "staking-device".to_string(),
+                enable_transaction_alerts: false,
+                enable_staking_alerts: true,
+                enable_governance_alerts: false,
+            })
+            .expect("staking registration")
+            .registration_id
+            .expect("registration identifier");
+        state
+            .register_push(&PushNotificationRegisterRequest {
+                token: "tx-device".to_string(),
+                enable_transaction_alerts: true,
+                enable_staking_alerts: false,
+                enable_governance_alerts: false,
+            })
+            .expect("transaction registration");
+
+        assert_eq!(
+            state.push_subscribers(AlertCategory::Staking),
+            vec![staking]
+        );
+        assert_eq!(state.push_subscribers(AlertCategory::Transaction).len(), 1);
+        assert!(state.push_subscribers(AlertCategory::Governance).is_empty());
+    }
+
     #[test]
     fn passkey_create_and_authenticate_flow() {
         let state = runtime_state();
@@ -0,0 +1,480 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::DelegationInfo;
+use crate::blockchain_client::BlockchainClient;
+use crate::config_store::CompoundingPolicy;
+use crate::errors::{WalletError, WalletResult};
+use crate::storage::WalletPaths;
+
+const AUDIT_FILE: &str = "staking/compounding_audit.jsonl";
+
+/// How often compounding policies are evaluated while the wallet is unlocked.
+pub const COMPOUNDING_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+/// Wait after an unsuccessful claim; doubles with each one in a row.
+const RETRY_BASE_MINUTES: i64 = 15;
+const RETRY_MAX_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompoundingTrigger {
+    Threshold,
+    Schedule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompoundingStep {
+    Claim,
+    Restake,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    Success,
+    Skipped,
+    Failed,
+}
+
+/// One automated action taken on behalf of a compounding policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompoundingAuditEntry {
+    pub at: DateTime<Utc>,
+    pub account: String,
+    pub trigger: CompoundingTrigger,
+    pub step: CompoundingStep,
+    pub amount: u64,
+    pub validator: Option<String>,
+    pub transaction_id: Option<String>,
+    pub outcome: AuditOutcome,
+    pub detail: Option<String>,
+}
+
+/// Decide whether `policy` calls for a claim right now. Rewards that would
+/// not clear the fee reserve plus the minimum net gain are left to accrue.
+pub fn evaluate(
+    policy: &CompoundingPolicy,
+    pending_rewards: u64,
+    last_claim: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<CompoundingTrigger> {
+    let net_gain = pending_rewards.saturating_sub(policy.fee_reserve);
+    if !policy.enabled || net_gain == 0 || net_gain < policy.min_net_gain.unwrap_or(0) {
+        return None;
+    }
+    if policy
+        .min_pending_rewards
+        .is_some_and(|threshold| pending_rewards >= threshold)
+    {
+        return Some(CompoundingTrigger::Threshold);
+    }
+    if let Some(hours) = policy.interval_hours {
+        let due = last_claim.is_none_or(|last| now - last >= Duration::hours(hours as i64));
+        if due {
+            return Some(CompoundingTrigger::Schedule);
+        }
+    }
+    None
+}
+
+/// Earliest time to try again after `unsuccessful` claims in a row, the
+/// latest at `last`.
+pub fn retry_at(unsuccessful: u32, last: DateTime<Utc>) -> DateTime<Utc> {
+    let minutes = RETRY_BASE_MINUTES.saturating_mul(1 << unsuccessful.saturating_sub(1).min(10));
+    last + Duration::minutes(minutes).min(Duration::hours(RETRY_MAX_HOURS))
+}
+
+/// Validator to restake into: the configured target, else the largest existing delegation.
+pub fn restake_target(
+    policy: &CompoundingPolicy,
+    delegations: &[DelegationInfo],
+) -> Option<String> {
+    policy.target_validator.clone().or_else(|| {
+        delegations
+            .iter()
+            .filter(|delegation| delegation.amount > 0)
+            .max_by_key(|delegation| delegation.amount)
+            .map(|delegation| delegation.delegatee.clone())
+    })
+}
+
+/// Evaluate and, if due, execute `policy` for `account`. Every action taken is
+/// appended to `audit` and returned. After a failed or skipped claim the
+/// policy backs off instead of retrying on every poll.
+pub async fn run_policy(
+    client: &BlockchainClient,
+    audit: &CompoundingAuditLog,
+    account: &str,
+    policy: &CompoundingPolicy,
+    now: DateTime<Utc>,
+) -> WalletResult<Vec<CompoundingAuditEntry>> {
+    if let Some((unsuccessful, last)) = audit.unsuccessful_claims(account)? {
+        if now < retry_at(unsuccessful, last) {
+            return Ok(Vec::new());
+        }
+    }
+
+    let (rewards, delegations, balance) = tokio::try_join!(
+        client.get_staking_rewards(account),
+        client.get_user_delegations(account),
+        client.get_balance(account),
+    )?;
+    let last_claim = audit.last_claim(account)?;
+    let Some(trigger) = evaluate(policy, rewards.rewards.pending_rewards, last_claim, now) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries = Vec::new();
+    let record = |entries: &mut Vec<CompoundingAuditEntry>, entry: CompoundingAuditEntry| {
+        audit.append(&entry)?;
+        entries.push(entry);
+        Ok::<(), WalletError>(())
+    };
+    let entry = |step, amount, outcome| CompoundingAuditEntry {
+        at: Utc::now(),
+        account: account.to_string(),
+        trigger,
+        step,
+        amount,
+        validator: None,
+        transaction_id: None,
+        outcome,
+        detail: None,
+    };
+
+    // The claim itself costs a fee, paid from the spendable balance.
+    let spendable = balance.spendable()?.base_units();
+    if spendable < policy.fee_reserve as u128 {
+        let mut claim = entry(
+            CompoundingStep::Claim,
+            rewards.rewards.pending_rewards,
+            AuditOutcome::Skipped,
+        );
+        claim.detail = Some(format!(
+            "Spendable balance {} is below the fee reserve",
+            balance.balance.trim()
+        ));
+        record(&mut entries, claim)?;
+        return Ok(entries);
+    }
+
+    let claimed = match client.claim_staking_rewards(account).await {
+        Ok(response) => {
+            let mut claim = entry(
+                CompoundingStep::Claim,
+                response.claimed_amount,
+                AuditOutcome::Success,
+            );
+            claim.transaction_id = Some(response.transaction_id);
+            record(&mut entries, claim)?;
+            response.claimed_amount
+        }
+        Err(err) => {
+            let mut claim = entry(
+                CompoundingStep::Claim,
+                rewards.rewards.pending_rewards,
+                AuditOutcome::Failed,
+            );
+            claim.detail = Some(err.to_string());
+            record(&mut entries, claim)?;
+            return Err(err);
+        }
+    };
+
+    let restake_amount = claimed.saturating_sub(policy.fee_reserve);
+    let validator = restake_target(policy, &delegations.delegations);
+    let mut restake = entry(
+        CompoundingStep::Restake,
+        restake_amount,
+        AuditOutcome::Skipped,
+    );
+    restake.validator = validator.clone();
+
+    match validator {
+        _ if restake_amount == 0 => {
+            restake.detail = Some("Claimed amount does not exceed the fee reserve".to_string());
+        }
+        None => {
+            restake.detail = Some("No validator to restake into".to_string());
+        }
+        Some(validator) => {
+            match client
+                .delegate_tokens(account, &validator, restake_amount)
+                .await
+            {
+                Ok(response) => {
+                    restake.outcome = AuditOutcome::Success;
+                    restake.transaction_id = Some(response.transaction_id);
+                }
+                Err(err) => {
+                    restake.outcome = AuditOutcome::Failed;
+                    restake.detail = Some(err.to_string());
+                }
+            }
+        }
+    }
+    record(&mut entries, restake)?;
+
+    Ok(entries)
+}
+
+/// Append-only log of automated compounding actions.
+#[derive(Debug, Clone)]
+pub struct CompoundingAuditLog {
+    path: PathBuf,
+}
+
+impl CompoundingAuditLog {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn from_paths(paths: &WalletPaths) -> Self {
+        Self::new(paths.cache_dir().join(AUDIT_FILE))
+    }
+
+    pub fn append(&self, entry: &CompoundingAuditEntry) -> WalletResult<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Entries for `account` (or all accounts), newest first.
+    pub fn entries(
+        &self,
+        account: Option<&str>,
+        limit: Option<usize>,
+    ) -> WalletResult<Vec<CompoundingAuditEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut entries = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: CompoundingAuditEntry = serde_json::from_str(&line).map_err(|e| {
+                WalletError::StorageError(format!(
+                    "Corrupt compounding audit entry at line {}: {}",
+                    index + 1,
+                    e
+                ))
+            })?;
+            if account.is_none_or(|account| entry.account.eq_ignore_ascii_case(account)) {
+                entries.push(entry);
+            }
+        }
+
+        entries.reverse();
+        if let Some(limit) = limit {
+            entries.truncate(limit);
+        }
+        Ok(entries)
+    }
+
+    /// Claims for `account` that failed or were skipped since the last
+    /// successful one, and when the latest of them happened.
+    pub fn unsuccessful_claims(&self, account: &str) -> WalletResult<Option<(u32, DateTime<Utc>)>> {
+        let claims = self
+            .entries(Some(account), None)?
+            .into_iter()
+            .filter(|entry| entry.step == CompoundingStep::Claim)
+            .take_while(|entry| entry.outcome != AuditOutcome::Success)
+            .collect::<Vec<_>>();
+        Ok(claims
+            .first()
+            .map(|latest| (claims.len() as u32, latest.at)))
+    }
+
+    /// Time of the last successful claim for `account`.
+    pub fn last_claim(&self, account: &str) -> WalletResult<Option<DateTime<Utc>>> {
+        Ok(self
+            .entries(Some(account), None)?
+            .into_iter()
+            .find(|entry| {
+                entry.step == CompoundingStep::Claim && entry.outcome == AuditOutcome::Success
+            })
+            .map(|entry| entry.at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::BalanceResponse;
+    use tempfile::TempDir;
+
+    fn policy() -> CompoundingPolicy {
+        CompoundingPolicy {
+            enabled: true,
+            min_pending_rewards: Some(1_000),
+            interval_hours: Some(24),
+            target_validator: None,
+            fee_reserve: 5,
+            min_net_gain: None,
+        }
+    }
+
+    fn delegation(delegatee: &str, amount: i64) -> DelegationInfo {
+        DelegationInfo {
+            delegator: "me".to_string(),
+            delegatee: delegatee.to_string(),
+            amount,
+            delegated_at: 0,
+        }
+    }
+
+    #[test]
+    fn evaluate_honours_threshold_and_schedule() {
+        let now = Utc::now();
+        let policy = policy();
+
+        assert_eq!(
+            evaluate(&policy, 1_500, Some(now), now),
+            Some(CompoundingTrigger::Threshold)
+        );
+        assert_eq!(
+            evaluate(&policy, 10, Some(now - Duration::hours(1)), now),
+            None
+        );
+        assert_eq!(
+            evaluate(&policy, 10, Some(now - Duration::hours(25)), now),
+            Some(CompoundingTrigger::Schedule)
+        );
+        assert_eq!(
+            evaluate(&policy, 10, None, now),
+            Some(CompoundingTrigger::Schedule)
+        );
+        assert_eq!(evaluate(&policy, 0, None, now), None);
+        // Dust that would not pay for its own claim is left alone.
+        assert_eq!(evaluate(&policy, 5, None, now), None);
+        let picky = CompoundingPolicy {
+            min_net_gain: Some(100),
+            ..policy.clone()
+        };
+        assert_eq!(evaluate(&picky, 50, None, now), None);
+        assert_eq!(
+            evaluate(&picky, 1_500, Some(now), now),
+            Some(CompoundingTrigger::Threshold)
+        );
+
+        let disabled = CompoundingPolicy {
+            enabled: false,
+            ..policy
+        };
+        assert_eq!(evaluate(&disabled, 5_000, None, now), None);
+    }
+
+    #[test]
+    fn restake_target_prefers_configured_validator() {
+        let delegations = vec![delegation("small", 10), delegation("large", 500)];
+        assert_eq!(
+            restake_target(&policy(), &delegations).as_deref(),
+            Some("large")
+        );
+
+        let pinned = CompoundingPolicy {
+            target_validator: Some("pinned".to_string()),
+            ..policy()
+        };
+        assert_eq!(
+            restake_target(&pinned, &delegations).as_deref(),
+            Some("pinned")
+        );
+        assert_eq!(restake_target(&policy(), &[]), None);
+    }
+
+    #[test]
+    fn audit_log_tracks_last_successful_claim() {
+        let dir = TempDir::new().expect("temp dir");
+        let log = CompoundingAuditLog::new(dir.path().join("audit.jsonl"));
+        let at = Utc::now();
+        let entry = |account: &str, step, outcome, at| CompoundingAuditEntry {
+            at,
+            account: account.to_string(),
+            trigger: CompoundingTrigger::Threshold,
+            step,
+            amount: 100,
+            validator: None,
+            transaction_id: None,
+            outcome,
+            detail: None,
+        };
+
+        log.append(&entry(
+            "0xa",
+            CompoundingStep::Claim,
+            AuditOutcome::Success,
+            at,
+        ))
+        .unwrap();
+        log.append(&entry(
+            "0xa",
+            CompoundingStep::Claim,
+            AuditOutcome::Failed,
+            at + Duration::hours(1),
+        ))
+        .unwrap();
+        log.append(&entry(
+            "0xb",
+            CompoundingStep::Restake,
+            AuditOutcome::Success,
+            at,
+        ))
+        .unwrap();
+
+        assert_eq!(log.last_claim("0xA").unwrap(), Some(at));
+        assert_eq!(log.last_claim("0xb").unwrap(), None);
+        assert_eq!(
+            log.unsuccessful_claims("0xa").unwrap(),
+            Some((1, at + Duration::hours(1)))
+        );
+        assert_eq!(log.unsuccessful_claims("0xb").unwrap(), None);
+        assert_eq!(log.entries(None, None).unwrap().len(), 3);
+        let latest = log.entries(Some("0xa"), Some(1)).unwrap();
+        assert_eq!(latest[0].outcome, AuditOutcome::Failed);
+    }
+
+    #[test]
+    fn retries_back_off_after_unsuccessful_claims() {
+        let last = Utc::now();
+        assert_eq!(retry_at(1, last), last + Duration::minutes(15));
+        assert_eq!(retry_at(3, last), last + Duration::hours(1));
+        assert_eq!(retry_at(40, last), last + Duration::hours(24));
+    }
+
+    #[test]
+    fn spendable_balance_is_read_in_base_units() {
+        let balance = |balance: &str| BalanceResponse {
+            address: "me".to_string(),
+            balance: balance.to_string(),
+            nonce: 0,
+            verification: None,
+        };
+        let policy = policy();
+        // A few base units stay below the reserve instead of reading as whole CHERT.
+        assert!(balance("4").spendable().unwrap().base_units() < policy.fee_reserve as u128);
+        assert_eq!(balance(" 5 ").spendable().unwrap().base_units(), 5);
+        assert!(balance("1.5").spendable().is_err());
+    }
+}
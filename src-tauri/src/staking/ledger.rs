@@ -0,0 +1,473 @@
+//! Staking snapshots recorded over time and the realized-yield reports built
+//! from them.
+//!
+//! Each account has one append-only file keyed by its 0x address, so the same
+//! account given in Bech32 or mixed-case hex lands in the same history.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{DelegationInfo, LockBoxRecord, StakingRewards};
+use crate::blockchain::{Address, AddressType};
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+use crate::rpc_batch::RpcBatch;
+use crate::storage::WalletPaths;
+
+const LEDGER_DIR: &str = "staking";
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+/// Minimum spacing between automatically recorded snapshots of the same account.
+pub const MIN_SNAPSHOT_INTERVAL_MINUTES: i64 = 60;
+/// How often the background task captures snapshots of the wallet's staking accounts.
+pub const SCHEDULED_SNAPSHOT_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(6 * 60 * 60);
+
+/// Point-in-time capture of an account's staking position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakingSnapshot {
+    pub account: String,
+    pub captured_at: DateTime<Utc>,
+    pub delegations: Vec<DelegationInfo>,
+    pub rewards: StakingRewards,
+    pub lockbox_records: Vec<LockBoxRecord>,
+}
+
+impl StakingSnapshot {
+    /// Fetch delegations, rewards and lockbox records for `account` from the node.
+    pub async fn capture(client: &BlockchainClient, account: &str) -> WalletResult<Self> {
+        let mut batch = RpcBatch::new();
+        let delegations = batch.get_user_delegations(account);
+        let rewards = batch.get_staking_rewards(account);
+        let lockbox = batch.get_lockbox_records(account);
+        let response = client.send_batch(&batch).await?;
+
+        Ok(Self {
+            account: account.to_string(),
+            captured_at: Utc::now(),
+            delegations: response.get(delegations)?.delegations,
+            rewards: response.get(rewards)?.rewards,
+            lockbox_records: response.get(lockbox)?.records,
+        })
+    }
+
+    /// Amount currently delegated to each validator.
+    pub fn delegated_by_validator(&self) -> BTreeMap<String, u128> {
+        let mut totals = BTreeMap::new();
+        for delegation in &self.delegations {
+            *totals.entry(delegation.delegatee.clone()).or_insert(0u128) +=
+                delegation.amount.max(0) as u128;
+        }
+        totals
+    }
+
+    /// Active lockbox principal.
+    pub fn locked_amount(&self) -> u128 {
+        self.lockbox_records
+            .iter()
+            .filter(|record| record.is_active)
+            .map(|record| record.amount as u128)
+            .sum()
+    }
+
+    /// Total stake earning rewards: delegations plus active lockboxes.
+    pub fn staked_amount(&self) -> u128 {
+        self.delegated_by_validator().values().sum::<u128>() + self.locked_amount()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardTimelinePoint {
+    pub captured_at: DateTime<Utc>,
+    pub total_earned: u64,
+    pub pending_rewards: u64,
+    /// Rewards earned since the previous point.
+    pub reward_delta: u64,
+    pub staked_amount: u128,
+    /// APY reported by the node at capture time.
+    pub reported_apy: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorYield {
+    pub validator: String,
+    /// Rewards attributed to this validator in proportion to its share of staked funds.
+    pub attributed_rewards: f64,
+    /// Time-weighted average amount delegated over the report window.
+    pub average_stake: f64,
+    /// Annualized yield realized on this delegation, as a percentage.
+    pub realized_apy: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakingReport {
+    pub account: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub snapshot_count: usize,
+    pub total_rewards: u64,
+    /// Annualized yield realized across all stake, as a percentage.
+    pub effective_apy: f64,
+    pub latest_reported_apy: Option<f64>,
+    pub timeline: Vec<RewardTimelinePoint>,
+    pub validators: Vec<ValidatorYield>,
+}
+
+impl StakingReport {
+    /// Build a report from snapshots ordered by capture time.
+    pub fn from_snapshots(
+        account: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        snapshots: &[StakingSnapshot],
+    ) -> Self {
+        let mut timeline = Vec::with_capacity(snapshots.len());
+        let mut total_rewards: u64 = 0;
+        let mut stake_seconds: f64 = 0.0;
+        let mut validator_rewards: BTreeMap<String, f64> = BTreeMap::new();
+        let mut validator_stake_seconds: BTreeMap<String, f64> = BTreeMap::new();
+
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            let reward_delta = match index.checked_sub(1).map(|i| &snapshots[i]) {
+                Some(previous) => {
+                    let delta = snapshot
+                        .rewards
+                        .total_earned
+                        .saturating_sub(previous.rewards.total_earned);
+                    let elapsed = (snapshot.captured_at - previous.captured_at)
+                        .num_seconds()
+                        .max(0) as f64;
+                    let staked = previous.staked_amount() as f64;
+                    stake_seconds += staked * elapsed;
+
+                    for (validator, amount) in previous.delegated_by_validator() {
+                        let amount = amount as f64;
+                        *validator_stake_seconds
+                            .entry(validator.clone())
+                            .or_insert(0.0) += amount * elapsed;
+                        if staked > 0.0 {
+                            *validator_rewards.entry(validator).or_insert(0.0) +=
+                                delta as f64 * amount / staked;
+                        }
+                    }
+                    delta
+                }
+                None => 0,
+            };
+            total_rewards = total_rewards.saturating_add(reward_delta);
+
+            timeline.push(RewardTimelinePoint {
+                captured_at: snapshot.captured_at,
+                total_earned: snapshot.rewards.total_earned,
+                pending_rewards: snapshot.rewards.pending_rewards,
+                reward_delta,
+                staked_amount: snapshot.staked_amount(),
+                reported_apy: snapshot.rewards.current_apy,
+            });
+        }
+
+        let window_seconds = match (snapshots.first(), snapshots.last()) {
+            (Some(first), Some(last)) => {
+                (last.captured_at - first.captured_at).num_seconds().max(0) as f64
+            }
+            _ => 0.0,
+        };
+
+        let validators = validator_stake_seconds
+            .into_iter()
+            .map(|(validator, weighted)| {
+                let attributed_rewards = validator_rewards.get(&validator).copied().unwrap_or(0.0);
+                ValidatorYield {
+                    average_stake: if window_seconds > 0.0 {
+                        weighted / window_seconds
+                    } else {
+                        0.0
+                    },
+                    realized_apy: annualize(attributed_rewards, weighted),
+                    attributed_rewards,
+                    validator,
+                }
+            })
+            .collect();
+
+        Self {
+            account: account.to_string(),
+            from,
+            to,
+            snapshot_count: snapshots.len(),
+            total_rewards,
+            effective_apy: annualize(total_rewards as f64, stake_seconds),
+            latest_reported_apy: snapshots.last().map(|s| s.rewards.current_apy),
+            timeline,
+            validators,
+        }
+    }
+}
+
+/// Append-only per-account log of staking snapshots kept in the wallet cache.
+#[derive(Debug, Clone)]
+pub struct StakingLedger {
+    dir: PathBuf,
+}
+
+impl StakingLedger {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn from_paths(paths: &WalletPaths) -> Self {
+        Self::new(paths.cache_dir().join(LEDGER_DIR))
+    }
+
+    /// Append a snapshot to the account's ledger file.
+    pub fn record(&self, snapshot: &StakingSnapshot) -> WalletResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.ledger_file(&snapshot.account)?)?;
+        let mut line = serde_json::to_vec(snapshot)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Append a snapshot unless one was recorded within the minimum interval.
+    /// Returns whether the snapshot was written.
+    pub fn record_if_due(&self, snapshot: &StakingSnapshot) -> WalletResult<bool> {
+        if let Some(last) = self.latest(&snapshot.account)? {
+            let due_at = last.captured_at + Duration::minutes(MIN_SNAPSHOT_INTERVAL_MINUTES);
+            if snapshot.captured_at < due_at {
+                return Ok(false);
+            }
+        }
+        self.record(snapshot)?;
+        Ok(true)
+    }
+
+    /// Load snapshots for `account` within the optional range, oldest first.
+    pub fn snapshots(
+        &self,
+        account: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> WalletResult<Vec<StakingSnapshot>> {
+        let path = self.ledger_file(account)?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(File::open(&path)?);
+        let mut snapshots = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let snapshot: StakingSnapshot = serde_json::from_str(&line).map_err(|e| {
+                WalletError::StorageError(format!(
+                    "Corrupt staking ledger entry at line {}: {}",
+                    index + 1,
+                    e
+                ))
+            })?;
+            let after_start = from.is_none_or(|from| snapshot.captured_at >= from);
+            let before_end = to.is_none_or(|to| snapshot.captured_at <= to);
+            if after_start && before_end {
+                snapshots.push(snapshot);
+            }
+        }
+
+        snapshots.sort_by_key(|snapshot| snapshot.captured_at);
+        Ok(snapshots)
+    }
+
+    /// Accounts that have at least one recorded snapshot.
+    pub fn accounts(&self) -> WalletResult<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut accounts = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                accounts.push(stem.to_string());
+            }
+        }
+        accounts.sort();
+        Ok(accounts)
+    }
+
+    pub fn latest(&self, account: &str) -> WalletResult<Option<StakingSnapshot>> {
+        Ok(self.snapshots(account, None, None)?.pop())
+    }
+
+    pub fn report(
+        &self,
+        account: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> WalletResult<StakingReport> {
+        let snapshots = self.snapshots(account, from, to)?;
+        Ok(StakingReport::from_snapshots(account, from, to, &snapshots))
+    }
+
+    fn ledger_file(&self, account: &str) -> WalletResult<PathBuf> {
+        Ok(self.dir.join(format!("{}.jsonl", ledger_account(account)?)))
+    }
+}
+
+/// The lowercase 0x form of `account`, given in hex or in Bech32 under any
+/// network's prefix.
+pub fn ledger_account(account: &str) -> WalletResult<String> {
+    let account = account.trim();
+    let address = match account.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("0x") => {
+            Address::from_string(&format!("0x{}", &account[2..]))
+        }
+        _ => {
+            let (_, bytes) = bech32::decode(account)
+                .map_err(|_| WalletError::InvalidAddress(account.to_string()))?;
+            Address::from_bytes(bytes, AddressType::Account)
+        }
+    };
+    address
+        .map(|address| address.as_string().to_string())
+        .map_err(|_| WalletError::InvalidAddress(account.to_string()))
+}
+
+fn annualize(rewards: f64, stake_seconds: f64) -> f64 {
+    if stake_seconds <= 0.0 {
+        return 0.0;
+    }
+    rewards / stake_seconds * SECONDS_PER_YEAR * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    const ACCOUNT: &str = "0x1111111111111111111111111111111111111111";
+
+    fn snapshot(day: u32, total_earned: u64, delegations: &[(&str, i64)]) -> StakingSnapshot {
+        StakingSnapshot {
+            account: ACCOUNT.to_string(),
+            captured_at: Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap(),
+            delegations: delegations
+                .iter()
+                .map(|(validator, amount)| DelegationInfo {
+                    delegator: ACCOUNT.to_string(),
+                    delegatee: validator.to_string(),
+                    amount: *amount,
+                    delegated_at: 0,
+                })
+                .collect(),
+            rewards: StakingRewards {
+                total_earned,
+                pending_rewards: 0,
+                current_apy: 5.0,
+            },
+            lockbox_records: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ledger_round_trip_and_range_filter() {
+        let temp = TempDir::new().unwrap();
+        let ledger = StakingLedger::new(temp.path());
+        ledger.record(&snapshot(1, 0, &[("val-a", 1_000)])).unwrap();
+        ledger
+            .record(&snapshot(2, 10, &[("val-a", 1_000)]))
+            .unwrap();
+        ledger
+            .record(&snapshot(3, 20, &[("val-a", 1_000)]))
+            .unwrap();
+
+        let all = ledger.snapshots(ACCOUNT, None, None).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let from = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        let ranged = ledger.snapshots(ACCOUNT, Some(from), None).unwrap();
+        assert_eq!(ranged.len(), 2);
+        assert_eq!(ranged[0].rewards.total_earned, 10);
+    }
+
+    #[test]
+    fn record_if_due_skips_recent_snapshots() {
+        let temp = TempDir::new().unwrap();
+        let ledger = StakingLedger::new(temp.path());
+        let first = snapshot(1, 0, &[]);
+        assert!(ledger.record_if_due(&first).unwrap());
+
+        let mut soon = first.clone();
+        soon.captured_at += Duration::minutes(5);
+        assert!(!ledger.record_if_due(&soon).unwrap());
+    }
+
+    #[test]
+    fn report_attributes_rewards_by_validator_share() {
+        let snapshots = vec![
+            snapshot(1, 0, &[("val-a", 3_000), ("val-b", 1_000)]),
+            snapshot(2, 400, &[("val-a", 3_000), ("val-b", 1_000)]),
+        ];
+        let report = StakingReport::from_snapshots(ACCOUNT, None, None, &snapshots);
+
+        assert_eq!(report.total_rewards, 400);
+        assert_eq!(report.timeline[1].reward_delta, 400);
+        let val_a = &report.validators[0];
+        let val_b = &report.validators[1];
+        assert_eq!(val_a.validator, "val-a");
+        assert!((val_a.attributed_rewards - 300.0).abs() < 1e-9);
+        assert!((val_b.attributed_rewards - 100.0).abs() < 1e-9);
+        // 10% per day on the whole stake, annualized.
+        assert!((report.effective_apy - 365.25 * 10.0).abs() < 1e-6);
+        assert!((val_a.realized_apy - report.effective_apy).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hex_and_bech32_forms_share_one_ledger() {
+        let temp = TempDir::new().unwrap();
+        let ledger = StakingLedger::new(temp.path());
+        ledger.record(&snapshot(1, 0, &[])).unwrap();
+
+        let bytes = hex::decode(&ACCOUNT[2..]).unwrap();
+        let bech32 = Address::from_bytes(bytes, AddressType::Account)
+            .unwrap()
+            .as_bech32_string_with_hrp("stg")
+            .unwrap();
+        let mut other_form = snapshot(2, 10, &[]);
+        other_form.account = bech32.clone();
+        ledger.record(&other_form).unwrap();
+
+        assert_eq!(ledger.accounts().unwrap(), vec![ACCOUNT.to_string()]);
+        assert_eq!(ledger.snapshots(&bech32, None, None).unwrap().len(), 2);
+        let upper = format!("0X{}", ACCOUNT[2..].to_ascii_uppercase());
+        assert_eq!(ledger.snapshots(&upper, None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn account_names_cannot_escape_ledger_dir() {
+        let temp = TempDir::new().unwrap();
+        let ledger = StakingLedger::new(temp.path());
+        let result = ledger.snapshots("../etc/passwd", None, None);
+        assert!(matches!(result, Err(WalletError::InvalidAddress(_))));
+    }
+}
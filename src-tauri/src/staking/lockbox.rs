@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{
+    CreateLockboxStakeRequest, LockBoxRecord, LockboxMultiplier, PlanLockboxStakeRequest,
+};
+use crate::blockchain::Amount;
+use crate::errors::{WalletError, WalletResult};
+
+pub const MIN_TERM_MONTHS: u32 = 1;
+pub const MAX_TERM_MONTHS: u32 = 48;
+
+/// Multipliers the chain grants per lockbox term. Terms it has not priced
+/// have none; nothing is interpolated or assumed.
+#[derive(Debug, Clone, Default)]
+pub struct MultiplierSchedule {
+    points: BTreeMap<u32, f64>,
+}
+
+impl MultiplierSchedule {
+    /// Multipliers published in the chain's staking parameters, overridden by
+    /// those the chain has actually granted this account.
+    pub fn from_chain(published: &[LockboxMultiplier], records: &[LockBoxRecord]) -> Self {
+        let published = published
+            .iter()
+            .map(|entry| (entry.term_months, entry.multiplier));
+        let granted = records
+            .iter()
+            .map(|record| (record.term_months, record.multiplier));
+        Self {
+            points: published
+                .chain(granted)
+                .filter(|(_, multiplier)| multiplier.is_finite() && *multiplier > 0.0)
+                .collect(),
+        }
+    }
+
+    pub fn multiplier_for(&self, term_months: u32) -> Option<f64> {
+        self.points.get(&term_months).copied()
+    }
+}
+
+/// Projected outcome of locking an amount for one candidate term.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockboxProjection {
+    pub term_months: u32,
+    /// `None` if the date is out of range.
+    pub unlock_at: Option<DateTime<Utc>>,
+    pub multiplier: f64,
+    /// Rewards expected over the full term at the boosted rate.
+    pub expected_rewards: f64,
+    /// Rewards the same amount would earn staked without a lock, i.e. the
+    /// alternative given up by locking.
+    pub opportunity_cost: f64,
+    pub net_gain: f64,
+    pub effective_apy: f64,
+    /// Ready-to-sign `create_lockbox_stake` request for this term.
+    pub action: CreateLockboxStakeRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockboxPlan {
+    pub account: String,
+    pub amount: u64,
+    pub spendable_balance: String,
+    pub base_apy: f64,
+    pub projections: Vec<LockboxProjection>,
+    /// Requested terms the chain publishes no multiplier for, left unprojected.
+    pub unpriced_terms: Vec<u32>,
+    pub liquidity_horizon_months: Option<u32>,
+    /// Term earning the most by the liquidity horizon, counting unlocked
+    /// staking for the months after it unlocks. None without a horizon or
+    /// when no projected term unlocks within it.
+    pub recommended_term: Option<u32>,
+}
+
+/// Project each requested term for locking `request.amount` base units at
+/// `base_apy` percent. Rewards are projected in base units too.
+pub fn plan_lockbox(
+    request: &PlanLockboxStakeRequest,
+    base_apy: f64,
+    spendable: &Amount,
+    schedule: &MultiplierSchedule,
+    now: DateTime<Utc>,
+) -> WalletResult<LockboxPlan> {
+    let amount = request.amount;
+    if amount == 0 {
+        return Err(WalletError::InvalidAmount(
+            "Lockbox amount must be greater than zero".to_string(),
+        ));
+    }
+    let locked = Amount::from_base_units(amount as u128)?;
+    if locked.base_units() > spendable.base_units() {
+        return Err(WalletError::InvalidAmount(format!(
+            "Lockbox amount {} exceeds spendable balance {}",
+            locked.as_string(),
+            spendable.as_string()
+        )));
+    }
+    let terms = &request.term_months;
+    if terms.is_empty() {
+        return Err(WalletError::ValidationError(
+            "At least one lockbox term is required".to_string(),
+        ));
+    }
+    if let Some(term) = terms
+        .iter()
+        .find(|term| !(MIN_TERM_MONTHS..=MAX_TERM_MONTHS).contains(*term))
+    {
+        return Err(WalletError::ValidationError(format!(
+            "Lockbox term {} is outside {}-{} months",
+            term, MIN_TERM_MONTHS, MAX_TERM_MONTHS
+        )));
+    }
+
+    let mut terms = terms.clone();
+    terms.sort_unstable();
+    terms.dedup();
+
+    let base_rate = base_apy.max(0.0) / 100.0;
+    let mut projections = Vec::with_capacity(terms.len());
+    let mut unpriced_terms = Vec::new();
+    for term_months in terms {
+        let Some(multiplier) = schedule.multiplier_for(term_months) else {
+            unpriced_terms.push(term_months);
+            continue;
+        };
+        let years = term_months as f64 / 12.0;
+        let opportunity_cost = amount as f64 * base_rate * years;
+        let expected_rewards = opportunity_cost * multiplier;
+
+        projections.push(LockboxProjection {
+            term_months,
+            unlock_at: now.checked_add_months(Months::new(term_months)),
+            multiplier,
+            expected_rewards,
+            opportunity_cost,
+            net_gain: expected_rewards - opportunity_cost,
+            effective_apy: base_apy.max(0.0) * multiplier,
+            action: CreateLockboxStakeRequest {
+                account: request.account.clone(),
+                amount,
+                term_months,
+            },
+        });
+    }
+
+    // Over the horizon, a shorter lock is followed by unlocked staking.
+    let recommended_term = request.liquidity_horizon_months.and_then(|horizon| {
+        let unlocked_monthly = amount as f64 * base_rate / 12.0;
+        projections
+            .iter()
+            .filter(|projection| projection.term_months <= horizon)
+            .map(|projection| {
+                let after_unlock = (horizon - projection.term_months) as f64 * unlocked_monthly;
+                (
+                    projection.term_months,
+                    projection.expected_rewards + after_unlock,
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(term_months, _)| term_months)
+    });
+
+    Ok(LockboxPlan {
+        account: request.account.clone(),
+        amount,
+        spendable_balance: spendable.as_string(),
+        base_apy,
+        projections,
+        unpriced_terms,
+        liquidity_horizon_months: request.liquidity_horizon_months,
+        recommended_term,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spendable(base_units: u128) -> Amount {
+        Amount::from_base_units(base_units).unwrap()
+    }
+
+    fn request(amount: u64, terms: &[u32], horizon: Option<u32>) -> PlanLockboxStakeRequest {
+        PlanLockboxStakeRequest {
+            account: "0xabc".to_string(),
+            amount,
+            term_months: terms.to_vec(),
+            liquidity_horizon_months: horizon,
+        }
+    }
+
+    fn schedule() -> MultiplierSchedule {
+        let published =
+            [(6, 1.2), (12, 1.5), (24, 2.0)].map(|(term_months, multiplier)| LockboxMultiplier {
+                term_months,
+                multiplier,
+            });
+        MultiplierSchedule::from_chain(&published, &[])
+    }
+
+    #[test]
+    fn schedule_only_knows_terms_the_chain_priced() {
+        let schedule = schedule();
+        assert_eq!(schedule.multiplier_for(12), Some(1.5));
+        assert_eq!(schedule.multiplier_for(18), None);
+
+        let granted = MultiplierSchedule::from_chain(
+            &[LockboxMultiplier {
+                term_months: 12,
+                multiplier: 1.5,
+            }],
+            &[LockBoxRecord {
+                account: "0xabc".to_string(),
+                amount: 10,
+                term_months: 12,
+                locked_at: Utc::now(),
+                unlock_at: Utc::now(),
+                multiplier: 1.8,
+                is_active: true,
+            }],
+        );
+        assert_eq!(granted.multiplier_for(12), Some(1.8));
+        assert_eq!(MultiplierSchedule::default().multiplier_for(12), None);
+    }
+
+    #[test]
+    fn projections_cover_each_priced_term_once() {
+        let now = Utc::now();
+        let plan = plan_lockbox(
+            &request(1_000, &[12, 6, 12, 18], None),
+            10.0,
+            &spendable(5_000),
+            &schedule(),
+            now,
+        )
+        .expect("plan");
+
+        assert_eq!(plan.projections.len(), 2);
+        assert_eq!(plan.unpriced_terms, vec![18]);
+        let year = &plan.projections[1];
+        assert_eq!(year.term_months, 12);
+        assert!((year.opportunity_cost - 100.0).abs() < 1e-9);
+        assert!((year.expected_rewards - 150.0).abs() < 1e-9);
+        assert_eq!(year.action.term_months, 12);
+        assert_eq!(year.unlock_at, now.checked_add_months(Months::new(12)));
+        assert_eq!(plan.recommended_term, None, "no horizon, no recommendation");
+    }
+
+    #[test]
+    fn recommendation_fits_the_liquidity_horizon() {
+        let plan_for = |horizon| {
+            plan_lockbox(
+                &request(1_000, &[6, 12, 24], Some(horizon)),
+                10.0,
+                &spendable(5_000),
+                &schedule(),
+                Utc::now(),
+            )
+            .expect("plan")
+            .recommended_term
+        };
+        assert_eq!(plan_for(3), None);
+        assert_eq!(plan_for(6), Some(6));
+        // 12 months at 1.5x beats 6 at 1.2x followed by 6 unlocked.
+        assert_eq!(plan_for(18), Some(12));
+        assert_eq!(plan_for(36), Some(24));
+    }
+
+    #[test]
+    fn rejects_amounts_above_spendable_balance() {
+        let result = plan_lockbox(
+            &request(1_000, &[6], None),
+            10.0,
+            &spendable(999),
+            &schedule(),
+            Utc::now(),
+        );
+        assert!(matches!(result, Err(WalletError::InvalidAmount(_))));
+    }
+
+    #[test]
+    fn amounts_are_base_units_like_the_stake_request() {
+        // 2.5 CHERT spendable: locking 2.4 CHERT fits, 2.6 CHERT does not.
+        let balance = Amount::from_string("2.5").unwrap();
+        let fits = 2_400_000_000_000_000_000;
+        let plan = plan_lockbox(
+            &request(fits, &[12], None),
+            10.0,
+            &balance,
+            &schedule(),
+            Utc::now(),
+        )
+        .expect("plan");
+        assert_eq!(plan.projections[0].action.amount, fits);
+        assert!((plan.projections[0].opportunity_cost - 2.4e17).abs() < 1e3);
+
+        let result = plan_lockbox(
+            &request(2_600_000_000_000_000_000, &[12], None),
+            10.0,
+            &balance,
+            &schedule(),
+            Utc::now(),
+        );
+        assert!(matches!(result, Err(WalletError::InvalidAmount(_))));
+    }
+
+    #[test]
+    fn rejects_terms_out_of_range() {
+        let result = plan_lockbox(
+            &request(1, &[0, 6], None),
+            10.0,
+            &spendable(10),
+            &schedule(),
+            Utc::now(),
+        );
+        assert!(matches!(result, Err(WalletError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{AutoStakeRecord, LockBoxRecord, UnbondingEntry};
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+use crate::rpc_batch::RpcBatch;
+use crate::storage::WalletPaths;
+
+const TRACKER_FILE: &str = "staking/maturity.json";
+
+/// Tauri event emitted when a tracked position matures.
+pub const MATURITY_EVENT: &str = "staking://maturity";
+/// How often accounts are scanned for maturing positions.
+pub const MATURITY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaturityKind {
+    Unbonding,
+    Lockbox,
+    AutoStake,
+}
+
+impl MaturityKind {
+    /// Actions offered once a position of this kind has matured.
+    pub fn actions(&self) -> &'static [MaturityAction] {
+        match self {
+            MaturityKind::Unbonding => &[MaturityAction::Withdraw, MaturityAction::Restake],
+            MaturityKind::Lockbox => &[MaturityAction::Withdraw, MaturityAction::Restake],
+            MaturityKind::AutoStake => &[MaturityAction::Claim, MaturityAction::Withdraw],
+        }
+    }
+
+    fn withdraw_source(&self) -> &'static str {
+        match self {
+            MaturityKind::Unbonding => "unbonding",
+            MaturityKind::Lockbox => "lockbox",
+            MaturityKind::AutoStake => "autoStake",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaturityAction {
+    Claim,
+    Restake,
+    Withdraw,
+}
+
+/// A time-locked staking position and when it becomes available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaturityEntry {
+    pub id: String,
+    pub account: String,
+    pub kind: MaturityKind,
+    pub amount: u64,
+    pub matures_at: DateTime<Utc>,
+    /// Validator the funds are unbonding from, for unbonding entries.
+    pub validator: Option<String>,
+    /// Original lockbox term, reused when restaking.
+    pub term_months: Option<u32>,
+    pub actions: Vec<MaturityAction>,
+    /// Set once a maturity notification has been emitted.
+    pub notified_at: Option<DateTime<Utc>>,
+}
+
+impl MaturityEntry {
+    fn new(
+        id: String,
+        account: &str,
+        kind: MaturityKind,
+        amount: u64,
+        matures_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            account: account.to_string(),
+            kind,
+            amount,
+            matures_at,
+            validator: None,
+            term_months: None,
+            actions: kind.actions().to_vec(),
+            notified_at: None,
+        }
+    }
+
+    pub fn is_matured(&self, now: DateTime<Utc>) -> bool {
+        now >= self.matures_at
+    }
+}
+
+/// Payload of [`MATURITY_EVENT`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaturityNotification {
+    pub entry: MaturityEntry,
+    /// Push registrations subscribed to staking alerts.
+    pub push_registrations: Vec<String>,
+}
+
+/// Build the tracked entries for `account` from the node's current view.
+pub fn collect_entries(
+    account: &str,
+    lockbox_records: &[LockBoxRecord],
+    auto_stake: Option<&AutoStakeRecord>,
+    unbonding: &[UnbondingEntry],
+) -> Vec<MaturityEntry> {
+    let mut entries = Vec::new();
+
+    for record in lockbox_records.iter().filter(|record| record.is_active) {
+        let mut entry = MaturityEntry::new(
+            format!("lockbox:{}:{}", account, record.locked_at.timestamp()),
+            account,
+            MaturityKind::Lockbox,
+            record.amount,
+            record.unlock_at,
+        );
+        entry.term_months = Some(record.term_months);
+        entries.push(entry);
+    }
+
+    if let Some(record) = auto_stake.filter(|record| record.is_active && record.balance > 0) {
+        entries.push(MaturityEntry::new(
+            format!(
+                "autostake:{}:{}",
+                account,
+                record.maturity_timestamp.timestamp()
+            ),
+            account,
+            MaturityKind::AutoStake,
+            record.balance,
+            record.maturity_timestamp,
+        ));
+    }
+
+    for unbonding in unbonding {
+        let mut entry = MaturityEntry::new(
+            format!(
+                "unbonding:{}:{}:{}",
+                account,
+                unbonding.validator,
+                unbonding.completes_at.timestamp()
+            ),
+            account,
+            MaturityKind::Unbonding,
+            unbonding.amount,
+            unbonding.completes_at,
+        );
+        entry.validator = Some(unbonding.validator.clone());
+        entries.push(entry);
+    }
+
+    entries
+}
+
+/// Fetch the positions for `account` that can mature.
+pub async fn fetch_entries(
+    client: &BlockchainClient,
+    account: &str,
+) -> WalletResult<Vec<MaturityEntry>> {
+    let mut batch = RpcBatch::new();
+    let lockbox = batch.get_lockbox_records(account);
+    let auto_stake = batch.get_auto_stake_status(account);
+    let unbonding = batch.get_unbonding_entries(account);
+    let response = client.send_batch(&batch).await?;
+
+    let lockbox = response.get(lockbox)?;
+    let auto_stake = response.get(auto_stake)?;
+    let unbonding = response
+        .get_optional(unbonding)?
+        .map(|response| response.entries)
+        .unwrap_or_default();
+
+    Ok(collect_entries(
+        account,
+        &lockbox.records,
+        Some(&auto_stake.status),
+        &unbonding,
+    ))
+}
+
+/// Run a one-click action on a matured entry and return the transaction id.
+pub async fn execute_action(
+    client: &BlockchainClient,
+    entry: &MaturityEntry,
+    action: MaturityAction,
+) -> WalletResult<String> {
+    if !entry.actions.contains(&action) {
+        return Err(WalletError::ValidationError(format!(
+            "{:?} is not available for {:?} positions",
+            action, entry.kind
+        )));
+    }
+    if !entry.is_matured(Utc::now()) {
+        return Err(WalletError::ValidationError(format!(
+            "Position {} matures at {}",
+            entry.id, entry.matures_at
+        )));
+    }
+
+    match (action, entry.kind) {
+        (MaturityAction::Withdraw, kind) => client
+            .withdraw_stake(&entry.account, kind.withdraw_source(), entry.amount)
+            .await
+            .map(|response| response.transaction_id),
+        (MaturityAction::Claim, _) => client
+            .claim_staking_rewards(&entry.account)
+            .await
+            .map(|response| response.transaction_id),
+        (MaturityAction::Restake, MaturityKind::Lockbox) => {
+            let term_months = entry.term_months.ok_or_else(|| {
+                WalletError::ValidationError("Lockbox term is unknown".to_string())
+            })?;
+            client
+                .create_lockbox_stake(&entry.account, entry.amount, term_months)
+                .await
+                .map(|response| response.transaction_id)
+        }
+        (MaturityAction::Restake, _) => {
+            let validator = entry.validator.as_deref().ok_or_else(|| {
+                WalletError::ValidationError("Unbonding validator is unknown".to_string())
+            })?;
+            client
+                .delegate_tokens(&entry.account, validator, entry.amount)
+                .await
+                .map(|response| response.transaction_id)
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TrackerState {
+    entries: Vec<MaturityEntry>,
+}
+
+/// Persisted set of tracked positions, used to notify once per maturity.
+/// Clones share a lock so background syncs and commands update the file one
+/// at a time.
+#[derive(Debug, Clone)]
+pub struct MaturityTracker {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl MaturityTracker {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Arc::default(),
+        }
+    }
+
+    pub fn from_paths(paths: &WalletPaths) -> Self {
+        Self::new(paths.cache_dir().join(TRACKER_FILE))
+    }
+
+    /// All tracked entries, soonest maturity first.
+    pub fn entries(&self) -> WalletResult<Vec<MaturityEntry>> {
+        let _lock = self.lock.lock();
+        let mut entries = self.load()?.entries;
+        entries.sort_by_key(|entry| entry.matures_at);
+        Ok(entries)
+    }
+
+    pub fn find(&self, id: &str) -> WalletResult<MaturityEntry> {
+        let _lock = self.lock.lock();
+        self.load()?
+            .entries
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| {
+                WalletError::ValidationError(format!("Unknown staking position: {}", id))
+            })
+    }
+
+    /// Replace the tracked entries for `account` and return the ones that
+    /// matured since the last sync. Those are marked as notified.
+    pub fn sync_account(
+        &self,
+        account: &str,
+        fresh: Vec<MaturityEntry>,
+        now: DateTime<Utc>,
+    ) -> WalletResult<Vec<MaturityEntry>> {
+        let _lock = self.lock.lock();
+        let mut state = self.load()?;
+        let previous: BTreeMap<String, MaturityEntry> = state
+            .entries
+            .iter()
+            .filter(|entry| entry.account == account)
+            .map(|entry| (entry.id.clone(), entry.clone()))
+            .collect();
+        state.entries.retain(|entry| entry.account != account);
+
+        let mut newly_matured = Vec::new();
+        for mut entry in fresh {
+            entry.notified_at = previous.get(&entry.id).and_then(|prev| prev.notified_at);
+            if entry.notified_at.is_none() && entry.is_matured(now) {
+                entry.notified_at = Some(now);
+                newly_matured.push(entry.clone());
+            }
+            state.entries.push(entry);
+        }
+
+        self.save(&state)?;
+        Ok(newly_matured)
+    }
+
+    /// Stop tracking an entry, e.g. after it has been withdrawn or restaked.
+    pub fn remove(&self, id: &str) -> WalletResult<()> {
+        let _lock = self.lock.lock();
+        let mut state = self.load()?;
+        state.entries.retain(|entry| entry.id != id);
+        self.save(&state)
+    }
+
+    fn load(&self) -> WalletResult<TrackerState> {
+        if !self.path.exists() {
+            return Ok(TrackerState::default());
+        }
+        let data = fs::read(&self.path)?;
+        serde_json::from_slice(&data).map_err(|e| {
+            WalletError::StorageError(format!("Corrupt staking maturity tracker: {}", e))
+        })
+    }
+
+    fn save(&self, state: &TrackerState) -> WalletResult<()> {
+        let dir = self
+            .path
+            .parent()
+            .ok_or_else(|| WalletError::StorageError("Invalid tracker path".to_string()))?;
+        fs::create_dir_all(dir)?;
+        let tmp_path = self.path.with_extension("new");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(state)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use tempfile::TempDir;
+
+    const ACCOUNT: &str = "0x1111111111111111111111111111111111111111";
+
+    fn lockbox(unlock_at: DateTime<Utc>) -> LockBoxRecord {
+        LockBoxRecord {
+            account: ACCOUNT.to_string(),
+            amount: 500,
+            term_months: 6,
+            locked_at: unlock_at - Duration::days(180),
+            unlock_at,
+            multiplier: 1.5,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn collects_active_positions_with_their_actions() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let mut inactive = lockbox(now);
+        inactive.is_active = false;
+        let auto_stake = AutoStakeRecord {
+            account: ACCOUNT.to_string(),
+            balance: 42,
+            maturity_timestamp: now + Duration::days(1),
+            is_active: true,
+        };
+        let unbonding = UnbondingEntry {
+            validator: "0x2222222222222222222222222222222222222222".to_string(),
+            amount: 100,
+            completes_at: now,
+        };
+
+        let entries = collect_entries(
+            ACCOUNT,
+            &[lockbox(now), inactive],
+            Some(&auto_stake),
+            &[unbonding],
+        );
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].kind, MaturityKind::Lockbox);
+        assert_eq!(entries[0].term_months, Some(6));
+        assert_eq!(
+            entries[1].actions,
+            vec![MaturityAction::Claim, MaturityAction::Withdraw]
+        );
+        assert!(entries[2].validator.is_some());
+    }
+
+    #[test]
+    fn sync_reports_each_maturity_once() {
+        let dir = TempDir::new().expect("temp dir");
+        let tracker = MaturityTracker::new(dir.path().join("maturity.json"));
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let entries = collect_entries(
+            ACCOUNT,
+            &[
+                lockbox(now - Duration::hours(1)),
+                lockbox(now + Duration::days(30)),
+            ],
+            None,
+            &[],
+        );
+
+        let first = tracker
+            .sync_account(ACCOUNT, entries.clone(), now)
+            .expect("first sync");
+        assert_eq!(first.len(), 1);
+
+        let second = tracker
+            .sync_account(ACCOUNT, entries.clone(), now)
+            .expect("second sync");
+        assert!(second.is_empty());
+
+        let later = tracker
+            .sync_account(ACCOUNT, entries, now + Duration::days(31))
+            .expect("later sync");
+        assert_eq!(later.len(), 1);
+        assert_eq!(tracker.entries().expect("entries").len(), 2);
+    }
+
+    #[test]
+    fn removed_entries_are_no_longer_found() {
+        let dir = TempDir::new().expect("temp dir");
+        let tracker = MaturityTracker::new(dir.path().join("maturity.json"));
+        let now = Utc::now();
+        let entries = collect_entries(ACCOUNT, &[lockbox(now)], None, &[]);
+        let id = entries[0].id.clone();
+        tracker.sync_account(ACCOUNT, entries, now).expect("sync");
+
+        assert!(tracker.find(&id).is_ok());
+        tracker.remove(&id).expect("remove");
+        assert!(matches!(
+            tracker.find(&id),
+            Err(WalletError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn removals_survive_concurrent_syncs() {
+        let dir = TempDir::new().expect("temp dir");
+        let tracker = MaturityTracker::new(dir.path().join("maturity.json"));
+        let now = Utc::now();
+        let entries = collect_entries(ACCOUNT, &[lockbox(now)], None, &[]);
+        let id = entries[0].id.clone();
+        tracker.sync_account(ACCOUNT, entries, now).expect("sync");
+
+        let syncing = {
+            let tracker = tracker.clone();
+            std::thread::spawn(move || {
+                for _ in 0..50 {
+                    let other = collect_entries("0xother", &[lockbox(now)], None, &[]);
+                    tracker
+                        .sync_account("0xother", other, now)
+                        .expect("sync other");
+                }
+            })
+        };
+        tracker.remove(&id).expect("remove");
+        syncing.join().expect("sync thread");
+
+        assert!(tracker.find(&id).is_err());
+        assert_eq!(tracker.entries().expect("entries").len(), 1);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod compounding;
+pub mod ledger;
+pub mod lockbox;
+pub mod maturity;
+pub mod redelegation;
+pub mod selection;
+
+pub use compounding::{CompoundingAuditEntry, CompoundingAuditLog};
+pub use ledger::{StakingLedger, StakingReport, StakingSnapshot};
+pub use lockbox::{LockboxPlan, MultiplierSchedule};
+pub use maturity::{MaturityEntry, MaturityTracker};
+pub use redelegation::{RedelegationPlan, RedelegationStore};
+pub use selection::{DelegationPlan, SelectionCriteria, ValidatorSelector};
@@ -0,0 +1,589 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::blockchain_client::BlockchainClient;
+use crate::errors::{WalletError, WalletResult};
+use crate::storage::WalletPaths;
+
+const PLAN_DIR: &str = "staking/redelegations";
+
+/// How often plans waiting on unbonding are resumed in the background.
+pub const REDELEGATION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// How the move is carried out on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedelegationMode {
+    /// Single `staking_redelegate_tokens` transaction.
+    Native,
+    /// Undelegate, wait for unbonding, then delegate.
+    Orchestrated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RedelegationStepKind {
+    Redelegate,
+    Undelegate,
+    AwaitUnbonding,
+    Delegate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepStatus {
+    Pending,
+    Waiting,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RedelegationStatus {
+    Pending,
+    InProgress,
+    Unbonding,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedelegationStep {
+    pub kind: RedelegationStepKind,
+    pub status: StepStatus,
+    pub transaction_id: Option<String>,
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RedelegationStep {
+    fn new(kind: RedelegationStepKind, now: DateTime<Utc>) -> Self {
+        Self {
+            kind,
+            status: StepStatus::Pending,
+            transaction_id: None,
+            error: None,
+            updated_at: now,
+        }
+    }
+}
+
+/// Persisted state of a single stake move from one validator to another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedelegationPlan {
+    pub id: String,
+    pub delegator: String,
+    pub from_validator: String,
+    pub to_validator: String,
+    pub amount: u64,
+    /// Chosen on the first attempt, once native support has been probed.
+    pub mode: Option<RedelegationMode>,
+    pub steps: Vec<RedelegationStep>,
+    pub unbonding_completes_at: Option<DateTime<Utc>>,
+    pub status: RedelegationStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RedelegationPlan {
+    pub fn new(delegator: &str, from_validator: &str, to_validator: &str, amount: u64) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            delegator: delegator.to_string(),
+            from_validator: from_validator.to_string(),
+            to_validator: to_validator.to_string(),
+            amount,
+            mode: None,
+            steps: Vec::new(),
+            unbonding_completes_at: None,
+            status: RedelegationStatus::Pending,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn derive_status(&self) -> RedelegationStatus {
+        if self.steps.is_empty() {
+            return RedelegationStatus::Pending;
+        }
+        if self
+            .steps
+            .iter()
+            .any(|step| step.status == StepStatus::Failed)
+        {
+            return RedelegationStatus::Failed;
+        }
+        if self
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Completed)
+        {
+            return RedelegationStatus::Completed;
+        }
+        if self
+            .steps
+            .iter()
+            .any(|step| step.status == StepStatus::Waiting)
+        {
+            return RedelegationStatus::Unbonding;
+        }
+        RedelegationStatus::InProgress
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.status == RedelegationStatus::Completed
+    }
+
+    /// Refresh the summary status and timestamp after a change.
+    fn touch(&mut self, now: DateTime<Utc>) {
+        self.status = self.derive_status();
+        self.updated_at = now;
+    }
+
+    fn select_mode(&mut self, mode: RedelegationMode, now: DateTime<Utc>) {
+        self.mode = Some(mode);
+        self.steps = match mode {
+            RedelegationMode::Native => {
+                vec![RedelegationStep::new(RedelegationStepKind::Redelegate, now)]
+            }
+            RedelegationMode::Orchestrated => vec![
+                RedelegationStep::new(RedelegationStepKind::Undelegate, now),
+                RedelegationStep::new(RedelegationStepKind::AwaitUnbonding, now),
+                RedelegationStep::new(RedelegationStepKind::Delegate, now),
+            ],
+        };
+    }
+
+    /// Index of the first step that still needs work, including a failed
+    /// step the user has chosen to retry.
+    fn next_step(&self) -> Option<usize> {
+        self.steps
+            .iter()
+            .position(|step| step.status != StepStatus::Completed)
+    }
+
+    /// Whether the plan is only waiting for unbonding to finish, so it can be
+    /// resumed without the user. Anything that would resend a transaction
+    /// after a failure needs an explicit resume instead: the node may have
+    /// accepted the first attempt even though the wallet saw an error.
+    pub fn awaits_unbonding(&self) -> bool {
+        self.status != RedelegationStatus::Failed
+            && self
+                .next_step()
+                .is_some_and(|index| self.steps[index].kind == RedelegationStepKind::AwaitUnbonding)
+    }
+
+    fn complete_step(&mut self, index: usize, transaction_id: Option<String>, now: DateTime<Utc>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Completed;
+        step.transaction_id = transaction_id;
+        step.error = None;
+        step.updated_at = now;
+    }
+
+    fn fail_step(&mut self, index: usize, error: &WalletError, now: DateTime<Utc>) {
+        let step = &mut self.steps[index];
+        step.status = StepStatus::Failed;
+        step.error = Some(error.to_string());
+        step.updated_at = now;
+    }
+}
+
+/// Drive `plan` as far as it can go right now, persisting after every step.
+///
+/// A failed step is retried on the next call, so this is how the user resumes
+/// a failed plan; the background task only calls it for plans that
+/// [`RedelegationPlan::awaits_unbonding`]. A plan that is already being
+/// advanced elsewhere is refused rather than submitted twice.
+pub async fn advance(
+    client: &BlockchainClient,
+    store: &RedelegationStore,
+    plan: &mut RedelegationPlan,
+) -> WalletResult<()> {
+    let _claim = store.claim(&plan.id)?;
+    // Another caller may have moved the plan on since `plan` was read.
+    if store.plan_file(&plan.id)?.exists() {
+        *plan = store.load(&plan.id)?;
+    }
+
+    if plan.mode.is_none() {
+        let outcome = client
+            .redelegate_tokens(
+                &plan.delegator,
+                &plan.from_validator,
+                &plan.to_validator,
+                plan.amount,
+            )
+            .await?;
+        let now = Utc::now();
+        match outcome {
+            Some(response) => {
+                plan.select_mode(RedelegationMode::Native, now);
+                plan.complete_step(0, Some(response.transaction_id), now);
+            }
+            None => plan.select_mode(RedelegationMode::Orchestrated, now),
+        }
+        plan.touch(now);
+        store.save(plan)?;
+    }
+
+    while let Some(index) = plan.next_step() {
+        let kind = plan.steps[index].kind;
+        let result = match kind {
+            RedelegationStepKind::Redelegate => client
+                .redelegate_tokens(
+                    &plan.delegator,
+                    &plan.from_validator,
+                    &plan.to_validator,
+                    plan.amount,
+                )
+                .await
+                .and_then(|outcome| {
+                    outcome
+                        .map(|response| Some(response.transaction_id))
+                        .ok_or_else(|| {
+                            WalletError::NetworkError(
+                                "Node no longer supports native redelegation".to_string(),
+                            )
+                        })
+                }),
+            RedelegationStepKind::Undelegate => {
+                match client
+                    .undelegate_tokens(&plan.delegator, &plan.from_validator, plan.amount)
+                    .await
+                {
+                    Ok(response) => {
+                        let reported = response
+                            .unbonding_completes_at
+                            .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+                        plan.unbonding_completes_at = match reported {
+                            Some(completes_at) => Some(completes_at),
+                            None => unbonding_period(client)
+                                .await
+                                .map(|period| Utc::now() + period),
+                        };
+                        Ok(Some(response.transaction_id))
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            RedelegationStepKind::AwaitUnbonding => {
+                let now = Utc::now();
+                let mut learned = false;
+                if plan.unbonding_completes_at.is_none() {
+                    plan.unbonding_completes_at = unbonding_completion(client, plan, now).await?;
+                    learned = plan.unbonding_completes_at.is_some();
+                }
+                let ready = plan
+                    .unbonding_completes_at
+                    .is_some_and(|completes_at| now >= completes_at);
+                if !ready {
+                    let step = &mut plan.steps[index];
+                    if step.status != StepStatus::Waiting || learned {
+                        step.status = StepStatus::Waiting;
+                        step.updated_at = now;
+                        plan.touch(now);
+                        store.save(plan)?;
+                    }
+                    return Ok(());
+                }
+                Ok(None)
+            }
+            RedelegationStepKind::Delegate => client
+                .delegate_tokens(&plan.delegator, &plan.to_validator, plan.amount)
+                .await
+                .map(|response| Some(response.transaction_id)),
+        };
+
+        let now = Utc::now();
+        match result {
+            Ok(transaction_id) => {
+                plan.complete_step(index, transaction_id, now);
+                plan.touch(now);
+                store.save(plan)?;
+            }
+            Err(err) => {
+                plan.fail_step(index, &err, now);
+                plan.touch(now);
+                store.save(plan)?;
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The chain's unbonding period, if the node publishes it. The undelegation
+/// has already gone through, so a failed lookup only leaves it unknown.
+async fn unbonding_period(client: &BlockchainClient) -> Option<Duration> {
+    let params = client.get_staking_params().await.ok()??.params;
+    let seconds = i64::try_from(params.unbonding_period_seconds?).ok()?;
+    Some(Duration::seconds(seconds))
+}
+
+/// When the delegator's stake unbonding from the plan's source validator is
+/// all liquid, or `None` if the node cannot say yet. Only called after our
+/// own undelegation, so a list without an entry for the validator means the
+/// node has not indexed it yet rather than that it has finished. A date found
+/// here is saved on the plan, so an entry that later disappears never sends
+/// the plan back here.
+async fn unbonding_completion(
+    client: &BlockchainClient,
+    plan: &RedelegationPlan,
+    now: DateTime<Utc>,
+) -> WalletResult<Option<DateTime<Utc>>> {
+    let Some(response) = client.get_unbonding_entries(&plan.delegator).await? else {
+        return Ok(None);
+    };
+    Ok(response
+        .entries
+        .iter()
+        .filter(|entry| entry.validator.eq_ignore_ascii_case(&plan.from_validator))
+        .map(|entry| entry.completes_at)
+        .max()
+        .map(|completes_at| completes_at.max(now)))
+}
+
+/// One JSON file per plan under the wallet cache. Clones share the set of
+/// plans currently being advanced.
+#[derive(Debug, Clone)]
+pub struct RedelegationStore {
+    dir: PathBuf,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl RedelegationStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            in_flight: Arc::default(),
+        }
+    }
+
+    pub fn from_paths(paths: &WalletPaths) -> Self {
+        Self::new(paths.cache_dir().join(PLAN_DIR))
+    }
+
+    pub fn save(&self, plan: &RedelegationPlan) -> WalletResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.plan_file(&plan.id)?;
+        let tmp_path = path.with_extension("new");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(plan)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn load(&self, id: &str) -> WalletResult<RedelegationPlan> {
+        let path = self.plan_file(id)?;
+        if !path.exists() {
+            return Err(WalletError::ValidationError(format!(
+                "Unknown redelegation plan: {}",
+                id
+            )));
+        }
+        let data = fs::read(path)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// All persisted plans, newest first.
+    pub fn list(&self) -> WalletResult<Vec<RedelegationPlan>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut plans = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let data = fs::read(&path)?;
+            let plan: RedelegationPlan = serde_json::from_slice(&data).map_err(|e| {
+                WalletError::StorageError(format!(
+                    "Corrupt redelegation plan {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            plans.push(plan);
+        }
+
+        plans.sort_by_key(|plan| std::cmp::Reverse(plan.created_at));
+        Ok(plans)
+    }
+
+    /// Plans that still have steps left to run, including failed ones.
+    pub fn unfinished(&self) -> WalletResult<Vec<RedelegationPlan>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|plan| !plan.is_finished())
+            .collect())
+    }
+
+    /// Plans the background task may resume on its own.
+    pub fn awaiting_unbonding(&self) -> WalletResult<Vec<RedelegationPlan>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(RedelegationPlan::awaits_unbonding)
+            .collect())
+    }
+
+    /// Mark `id` as being advanced until the returned guard is dropped.
+    fn claim(&self, id: &str) -> WalletResult<PlanClaim> {
+        if !self.in_flight.lock().insert(id.to_string()) {
+            return Err(WalletError::AlreadyExists(format!(
+                "Redelegation plan {} is already being advanced",
+                id
+            )));
+        }
+        Ok(PlanClaim {
+            in_flight: Arc::clone(&self.in_flight),
+            id: id.to_string(),
+        })
+    }
+
+    fn plan_file(&self, id: &str) -> WalletResult<PathBuf> {
+        let id = Uuid::parse_str(id).map_err(|_| {
+            WalletError::ValidationError(format!("Invalid redelegation plan id: {}", id))
+        })?;
+        Ok(self.dir.join(format!("{}.json", id)))
+    }
+}
+
+struct PlanClaim {
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    id: String,
+}
+
+impl Drop for PlanClaim {
+    fn drop(&mut self) {
+        self.in_flight.lock().remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn plan() -> RedelegationPlan {
+        RedelegationPlan::new("0xaaa", "0xbbb", "0xccc", 1_000)
+    }
+
+    #[test]
+    fn status_follows_step_progress() {
+        let now = Utc::now();
+        let mut plan = plan();
+        assert_eq!(plan.status, RedelegationStatus::Pending);
+
+        plan.select_mode(RedelegationMode::Orchestrated, now);
+        plan.touch(now);
+        assert_eq!(plan.status, RedelegationStatus::InProgress);
+
+        plan.complete_step(0, Some("tx-1".to_string()), now);
+        plan.steps[1].status = StepStatus::Waiting;
+        plan.touch(now);
+        assert_eq!(plan.status, RedelegationStatus::Unbonding);
+
+        assert!(plan.awaits_unbonding());
+
+        plan.fail_step(2, &WalletError::NetworkError("boom".to_string()), now);
+        plan.touch(now);
+        assert_eq!(plan.status, RedelegationStatus::Failed);
+        assert_eq!(plan.next_step(), Some(1));
+        assert!(!plan.awaits_unbonding());
+
+        plan.complete_step(1, None, now);
+        plan.complete_step(2, Some("tx-2".to_string()), now);
+        plan.touch(now);
+        assert!(plan.is_finished());
+        assert_eq!(plan.next_step(), None);
+    }
+
+    #[test]
+    fn store_round_trips_and_lists_unfinished_plans() {
+        let dir = TempDir::new().expect("temp dir");
+        let store = RedelegationStore::new(dir.path());
+
+        let mut done = plan();
+        done.select_mode(RedelegationMode::Native, Utc::now());
+        done.complete_step(0, Some("tx".to_string()), Utc::now());
+        done.touch(Utc::now());
+        let pending = plan();
+        store.save(&done).expect("save done");
+        store.save(&pending).expect("save pending");
+
+        let loaded = store.load(&done.id).expect("load");
+        assert_eq!(loaded.mode, Some(RedelegationMode::Native));
+        assert_eq!(store.list().expect("list").len(), 2);
+
+        let unfinished = store.unfinished().expect("unfinished");
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].id, pending.id);
+    }
+
+    #[test]
+    fn only_waiting_plans_resume_in_the_background() {
+        let dir = TempDir::new().expect("temp dir");
+        let store = RedelegationStore::new(dir.path());
+        let now = Utc::now();
+
+        let mut waiting = plan();
+        waiting.select_mode(RedelegationMode::Orchestrated, now);
+        waiting.complete_step(0, Some("tx".to_string()), now);
+        waiting.steps[1].status = StepStatus::Waiting;
+        waiting.touch(now);
+        let mut failed = plan();
+        failed.select_mode(RedelegationMode::Orchestrated, now);
+        failed.fail_step(0, &WalletError::ConnectionTimeout, now);
+        failed.touch(now);
+        store.save(&waiting).expect("save waiting");
+        store.save(&failed).expect("save failed");
+
+        let resumable = store.awaiting_unbonding().expect("resumable");
+        assert_eq!(resumable.len(), 1);
+        assert_eq!(resumable[0].id, waiting.id);
+        assert_eq!(store.unfinished().expect("unfinished").len(), 2);
+    }
+
+    #[test]
+    fn a_plan_cannot_be_claimed_twice() {
+        let store = RedelegationStore::new(TempDir::new().expect("temp dir").path());
+        let other = store.clone();
+        let claim = store.claim("plan").expect("first claim");
+        assert!(matches!(
+            other.claim("plan"),
+            Err(WalletError::AlreadyExists(_))
+        ));
+        drop(claim);
+        assert!(other.claim("plan").is_ok());
+    }
+
+    #[test]
+    fn store_rejects_non_uuid_ids() {
+        let dir = TempDir::new().expect("temp dir");
+        let store = RedelegationStore::new(dir.path());
+        assert!(matches!(
+            store.load("../../vault"),
+            Err(WalletError::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{DelegationInfo, ValidatorInfo};
+use crate::errors::{WalletError, WalletResult};
+
+/// Fixed-point precision of scores when splitting an amount.
+const SCORE_SCALE: f64 = 1e9;
+
+/// Relative importance of each factor when ranking validators.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoringWeights {
+    pub commission: f64,
+    pub reputation: f64,
+    pub concentration: f64,
+    pub exposure: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            commission: 0.3,
+            reputation: 0.3,
+            concentration: 0.2,
+            exposure: 0.2,
+        }
+    }
+}
+
+impl ScoringWeights {
+    fn total(&self) -> f64 {
+        self.commission + self.reputation + self.concentration + self.exposure
+    }
+}
+
+/// Filters and limits applied before a stake amount is split across validators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionCriteria {
+    #[serde(default)]
+    pub weights: ScoringWeights,
+    pub max_validators: usize,
+    #[serde(default)]
+    pub max_commission: Option<u32>,
+    #[serde(default)]
+    pub min_reputation: Option<u32>,
+    /// Validators without activity for this many hours are treated as offline.
+    pub max_inactivity_hours: i64,
+}
+
+impl Default for SelectionCriteria {
+    fn default() -> Self {
+        Self {
+            weights: ScoringWeights::default(),
+            max_validators: 3,
+            max_commission: None,
+            min_reputation: None,
+            max_inactivity_hours: 24,
+        }
+    }
+}
+
+/// Score breakdown for a single validator; every component is in `0.0..=1.0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorScore {
+    pub validator: String,
+    pub score: f64,
+    pub commission_score: f64,
+    pub reputation_score: f64,
+    pub concentration_score: f64,
+    pub exposure_score: f64,
+    pub existing_delegation: u64,
+}
+
+/// A single delegation ready to be signed and submitted via `delegate_tokens`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegateAction {
+    pub delegator_address: String,
+    pub validator_address: String,
+    pub amount: u64,
+}
+
+/// Recommended split of a stake amount across several validators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationPlan {
+    pub delegator: String,
+    pub total_amount: u64,
+    pub actions: Vec<DelegateAction>,
+    pub scores: Vec<ValidatorScore>,
+}
+
+/// Ranks validators and proposes diversified delegation splits.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorSelector {
+    criteria: SelectionCriteria,
+}
+
+impl ValidatorSelector {
+    pub fn new(criteria: SelectionCriteria) -> Self {
+        Self { criteria }
+    }
+
+    /// Score every eligible validator, best first.
+    ///
+    /// Inactive, stale, or filtered-out validators are omitted. `delegations`
+    /// are the account's current delegations and drive the exposure penalty.
+    pub fn score(
+        &self,
+        validators: &[ValidatorInfo],
+        delegations: &[DelegationInfo],
+        now: DateTime<Utc>,
+    ) -> Vec<ValidatorScore> {
+        let exposure = existing_exposure(delegations);
+        let total_exposure: u128 = exposure.values().map(|amount| *amount as u128).sum();
+
+        let eligible: Vec<&ValidatorInfo> = validators
+            .iter()
+            .filter(|validator| self.is_eligible(validator, now))
+            .collect();
+        let max_stake = eligible
+            .iter()
+            .map(|validator| bonded_stake(validator))
+            .max()
+            .unwrap_or(0);
+
+        let weights = self.criteria.weights;
+        let weight_total = weights.total();
+
+        let mut scores: Vec<ValidatorScore> = eligible
+            .into_iter()
+            .map(|validator| {
+                let commission_score = 1.0 - (validator.commission_rate.min(100) as f64 / 100.0);
+                let reputation_score = validator.reputation_score.min(100) as f64 / 100.0;
+                let concentration_score = if max_stake == 0 {
+                    1.0
+                } else {
+                    1.0 - bonded_stake(validator) as f64 / max_stake as f64
+                };
+                let existing_delegation = exposure.get(&validator.address).copied().unwrap_or(0);
+                let exposure_score = if total_exposure == 0 {
+                    1.0
+                } else {
+                    1.0 - existing_delegation as f64 / total_exposure as f64
+                };
+
+                let weighted = weights.commission * commission_score
+                    + weights.reputation * reputation_score
+                    + weights.concentration * concentration_score
+                    + weights.exposure * exposure_score;
+                let score = if weight_total > 0.0 {
+                    weighted / weight_total
+                } else {
+                    0.0
+                };
+
+                ValidatorScore {
+                    validator: validator.address.clone(),
+                    score,
+                    commission_score,
+                    reputation_score,
+                    concentration_score,
+                    exposure_score,
+                    existing_delegation,
+                }
+            })
+            .collect();
+
+        scores.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.validator.cmp(&b.validator))
+        });
+        scores
+    }
+
+    /// Split `amount` across the top-ranked validators in proportion to their score.
+    pub fn recommend(
+        &self,
+        delegator: &str,
+        amount: u64,
+        validators: &[ValidatorInfo],
+        delegations: &[DelegationInfo],
+        now: DateTime<Utc>,
+    ) -> WalletResult<DelegationPlan> {
+        if amount == 0 {
+            return Err(WalletError::ValidationError(
+                "Stake amount must be greater than zero".to_string(),
+            ));
+        }
+        if self.criteria.max_validators == 0 {
+            return Err(WalletError::ValidationError(
+                "At least one validator must be allowed".to_string(),
+            ));
+        }
+
+        let scores = self.score(validators, delegations, now);
+        let selected: Vec<&ValidatorScore> = scores
+            .iter()
+            .filter(|score| score.score > 0.0)
+            .take(self.criteria.max_validators)
+            .collect();
+        if selected.is_empty() {
+            return Err(WalletError::ValidationError(
+                "No validators match the selection criteria".to_string(),
+            ));
+        }
+
+        // Split in integers: base-unit amounts exceed f64's exact range, and
+        // floored shares must never add up to more than `amount`.
+        let weights: Vec<u128> = selected
+            .iter()
+            .map(|score| ((score.score * SCORE_SCALE).round() as u128).max(1))
+            .collect();
+        let weight_total: u128 = weights.iter().sum();
+        let mut amounts: Vec<u64> = weights
+            .iter()
+            .map(|weight| (u128::from(amount) * weight / weight_total) as u64)
+            .collect();
+        // Rounding dust goes to the best-ranked validator so the plan sums exactly.
+        let allocated: u64 = amounts.iter().sum();
+        amounts[0] += amount - allocated;
+
+        let actions = selected
+            .iter()
+            .zip(amounts)
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(score, amount)| DelegateAction {
+                delegator_address: delegator.to_string(),
+                validator_address: score.validator.clone(),
+                amount,
+            })
+            .collect();
+
+        Ok(DelegationPlan {
+            delegator: delegator.to_string(),
+            total_amount: amount,
+            actions,
+            scores,
+        })
+    }
+
+    fn is_eligible(&self, validator: &ValidatorInfo, now: DateTime<Utc>) -> bool {
+        let criteria = &self.criteria;
+        validator.is_active
+            && now - validator.last_activity <= Duration::hours(criteria.max_inactivity_hours)
+            && criteria
+                .max_commission
+                .is_none_or(|max| validator.commission_rate <= max)
+            && criteria
+                .min_reputation
+                .is_none_or(|min| validator.reputation_score >= min)
+    }
+}
+
+fn bonded_stake(validator: &ValidatorInfo) -> u64 {
+    validator
+        .stake
+        .saturating_add(validator.total_delegated.unwrap_or(0))
+}
+
+fn existing_exposure(delegations: &[DelegationInfo]) -> BTreeMap<String, u64> {
+    let mut totals = BTreeMap::new();
+    for delegation in delegations {
+        *totals.entry(delegation.delegatee.clone()).or_insert(0u64) +=
+            delegation.amount.max(0) as u64;
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(address: &str, stake: u64, commission: u32, reputation: u32) -> ValidatorInfo {
+        ValidatorInfo {
+            address: address.to_string(),
+            public_key: String::new(),
+            network_key: String::new(),
+            stake,
+            stake_amount: stake,
+            is_active: true,
+            commission_rate: commission,
+            reputation_score: reputation,
+            last_activity: Utc::now(),
+            total_delegated: None,
+            delegator_count: None,
+        }
+    }
+
+    #[test]
+    fn ranks_cheaper_and_more_reputable_validators_first() {
+        let validators = vec![
+            validator("expensive", 1_000, 20, 90),
+            validator("cheap", 1_000, 2, 95),
+            validator("flaky", 1_000, 2, 40),
+        ];
+        let scores = ValidatorSelector::default().score(&validators, &[], Utc::now());
+        let order: Vec<&str> = scores.iter().map(|s| s.validator.as_str()).collect();
+        assert_eq!(order, vec!["cheap", "expensive", "flaky"]);
+    }
+
+    #[test]
+    fn filters_inactive_and_stale_validators() {
+        let mut offline = validator("offline", 1_000, 1, 99);
+        offline.is_active = false;
+        let mut stale = validator("stale", 1_000, 1, 99);
+        stale.last_activity = Utc::now() - Duration::days(3);
+        let validators = vec![offline, stale, validator("live", 1_000, 5, 80)];
+
+        let scores = ValidatorSelector::default().score(&validators, &[], Utc::now());
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].validator, "live");
+    }
+
+    #[test]
+    fn existing_exposure_lowers_score() {
+        let validators = vec![validator("a", 1_000, 5, 90), validator("b", 1_000, 5, 90)];
+        let delegations = vec![DelegationInfo {
+            delegator: "me".to_string(),
+            delegatee: "a".to_string(),
+            amount: 500,
+            delegated_at: 0,
+        }];
+        let scores = ValidatorSelector::default().score(&validators, &delegations, Utc::now());
+        assert_eq!(scores[0].validator, "b");
+        assert_eq!(scores[1].existing_delegation, 500);
+    }
+
+    #[test]
+    fn recommendation_splits_full_amount_across_top_validators() {
+        let validators = vec![
+            validator("a", 1_000, 5, 90),
+            validator("b", 2_000, 5, 90),
+            validator("c", 3_000, 5, 90),
+            validator("d", 4_000, 50, 10),
+        ];
+        let plan = ValidatorSelector::default()
+            .recommend("me", 1_001, &validators, &[], Utc::now())
+            .expect("plan");
+
+        assert_eq!(plan.actions.len(), 3);
+        assert_eq!(plan.actions.iter().map(|a| a.amount).sum::<u64>(), 1_001);
+        assert!(plan.actions.iter().all(|a| a.delegator_address == "me"));
+        assert!(plan.actions.iter().all(|a| a.validator_address != "d"));
+
+        // Far beyond f64's exact integers; the split must still be exact.
+        let large = 1_234_567_890_123_456_789;
+        let plan = ValidatorSelector::default()
+            .recommend("me", large, &validators, &[], Utc::now())
+            .expect("plan");
+        assert_eq!(plan.actions.iter().map(|a| a.amount).sum::<u64>(), large);
+    }
+
+    #[test]
+    fn recommendation_requires_eligible_validators() {
+        let criteria = SelectionCriteria {
+            max_commission: Some(1),
+            ..SelectionCriteria::default()
+        };
+        let validators = vec![validator("a", 1_000, 5, 90)];
+        let result =
+            ValidatorSelector::new(criteria).recommend("me", 100, &validators, &[], Utc::now());
+        assert!(matches!(result, Err(WalletError::ValidationError(_))));
+    }
+}
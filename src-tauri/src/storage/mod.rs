@@ -2,4 +2,6 @@ pub mod paths;
 pub mod vault;
 
 pub use paths::WalletPaths;
-pub use vault::{VaultCreateParams, VaultManager, VaultMetadata, VaultSecrets, VaultUnlocked};
+pub use vault::{
+    AccountKey, VaultCreateParams, VaultManager, VaultMetadata, VaultSecrets, VaultUnlocked,
+};
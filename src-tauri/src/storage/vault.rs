@@ -5,6 +5,7 @@ use std::path::{Path, PathBuf};
 use argon2::{Algorithm, Argon2, Params, Version};
 use blake3::Hasher as Blake3;
 use chrono::{DateTime, Utc};
+use ed25519_dalek::SigningKey;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
@@ -44,6 +45,9 @@ pub struct VaultMetadata {
     /// Whether the wallet supports post-quantum cryptography.
     #[serde(default)]
     pub supports_post_quantum: Option<bool>,
+    /// Addresses of the accounts held besides the primary one.
+    #[serde(default)]
+    pub accounts: Vec<String>,
 }
 
 impl VaultMetadata {
@@ -58,6 +62,7 @@ impl VaultMetadata {
             public_key_hex: None,
             signature_algorithm: None,
             supports_post_quantum: None,
+            accounts: Vec::new(),
         }
     }
 
@@ -78,6 +83,17 @@ pub struct VaultSecrets {
     pub stealth_material: Vec<u8>,
     /// Serialized PQ key material for future use.
     pub pq_material: Vec<u8>,
+    /// Ed25519 keys of the accounts held besides the primary one.
+    #[serde(default)]
+    pub account_keys: Vec<AccountKey>,
+}
+
+/// Signing key of an account added to the vault after its creation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+pub struct AccountKey {
+    pub address: String,
+    pub seed_bytes: Vec<u8>,
 }
 
 impl VaultSecrets {
@@ -87,10 +103,49 @@ impl VaultSecrets {
             seed_bytes,
             stealth_material: Vec::new(),
             pq_material: Vec::new(),
+            account_keys: Vec::new(),
+        }
+    }
+
+    /// Ed25519 key over the seed. It controls the vault's primary address.
+    pub fn signing_key(&self) -> WalletResult<SigningKey> {
+        ed25519_key(&self.seed_bytes)
+    }
+
+    /// Key controlling `address`: the primary key or one of the added accounts.
+    pub fn signing_key_for(
+        &self,
+        metadata: &VaultMetadata,
+        address: &str,
+    ) -> WalletResult<SigningKey> {
+        if metadata
+            .primary_address
+            .as_deref()
+            .is_some_and(|primary| primary.eq_ignore_ascii_case(address))
+        {
+            return self.signing_key();
         }
+        let account = self
+            .account_keys
+            .iter()
+            .find(|account| account.address.eq_ignore_ascii_case(address))
+            .ok_or_else(|| {
+                WalletError::PermissionDenied(format!(
+                    "Account {} is not held by this wallet",
+                    address
+                ))
+            })?;
+        ed25519_key(&account.seed_bytes)
     }
 }
 
+fn ed25519_key(seed: &[u8]) -> WalletResult<SigningKey> {
+    let seed: [u8; 32] = seed.try_into().map_err(|_| {
+        WalletError::CryptoError("Unsupported key material for signing".to_string())
+    })?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct VaultFile {
     magic: [u8; 8],
@@ -235,6 +290,35 @@ impl VaultManager {
         self.update(params)
     }
 
+    /// Add an account key to the vault, re-encrypting it under the same password.
+    pub fn add_account_key(
+        &self,
+        password: &SecretString,
+        account: AccountKey,
+    ) -> WalletResult<VaultMetadata> {
+        let unlocked = self.unlock(password)?;
+        let mut metadata = unlocked.metadata.clone();
+        let mut secrets = unlocked.secrets.clone();
+        let held = metadata
+            .primary_address
+            .iter()
+            .chain(&metadata.accounts)
+            .any(|address| address.eq_ignore_ascii_case(&account.address));
+        if held {
+            return Err(WalletError::AlreadyExists(account.address.clone()));
+        }
+
+        metadata.accounts.push(account.address.clone());
+        metadata.touch();
+        secrets.account_keys.push(account);
+        self.update(VaultCreateParams {
+            password,
+            metadata: metadata.clone(),
+            secrets,
+        })?;
+        Ok(metadata)
+    }
+
     /// Check if the vault file exists on disk.
     pub fn exists(&self) -> bool {
         self.vault_path.exists()
@@ -448,6 +532,7 @@ mod tests {
             seed_bytes: vec![1, 2, 3, 4],
             stealth_material: vec![9, 9, 9],
             pq_material: vec![7, 7],
+            account_keys: Vec::new(),
         };
         let params = VaultCreateParams {
             password: &secret("correct horse battery staple"),
@@ -530,6 +615,44 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn added_accounts_sign_with_their_own_key() {
+        let dir = TempDir::new().unwrap();
+        let manager = VaultManager::new(dir.path().join("wallet.vault"));
+        let mut metadata = VaultMetadata::new("Accounts");
+        metadata.primary_address = Some("0xprimary".to_string());
+        manager
+            .create(VaultCreateParams {
+                password: &secret("accounts"),
+                metadata,
+                secrets: VaultSecrets::new(vec![1; 32]),
+            })
+            .unwrap();
+
+        let account = AccountKey {
+            address: "0xSecond".to_string(),
+            seed_bytes: vec![2; 32],
+        };
+        let metadata = manager
+            .add_account_key(&secret("accounts"), account.clone())
+            .unwrap();
+        assert_eq!(metadata.accounts, vec!["0xSecond".to_string()]);
+        assert!(matches!(
+            manager.add_account_key(&secret("accounts"), account),
+            Err(WalletError::AlreadyExists(_))
+        ));
+
+        let unlocked = manager.unlock(&secret("accounts")).unwrap();
+        let secrets = &unlocked.secrets;
+        let key = |address: &str| secrets.signing_key_for(&unlocked.metadata, address);
+        assert_eq!(key("0xPRIMARY").unwrap().to_bytes(), [1; 32]);
+        assert_eq!(key("0xsecond").unwrap().to_bytes(), [2; 32]);
+        assert!(matches!(
+            key("0xthird"),
+            Err(WalletError::PermissionDenied(_))
+        ));
+    }
+
     #[test]
     fn metadata_can_be_read_without_unlock() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,352 @@
+/// TLS trust for RPC and WebSocket connections
+///
+/// Endpoints are verified against the bundled web PKI roots unless the
+/// network config gives their host a custom CA bundle or pinned key hashes.
+/// A pinned key is trusted on its own, so self-hosted nodes with self-signed
+/// certificates can be pinned instead of disabling verification.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::server::ParsedCertificate;
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+
+use crate::config_store::{EndpointTls, NetworkConfig};
+use crate::errors::{WalletError, WalletResult};
+
+const PIN_PREFIX: &str = "sha256/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsStatus {
+    /// Certificate verification is disabled for every endpoint.
+    pub untrusted_certs: bool,
+    /// Hosts with pinned keys or a custom CA bundle.
+    pub customized_hosts: Vec<String>,
+    pub warning: Option<String>,
+}
+
+/// Whether `network` disables certificate checks. Never in production.
+pub fn untrusted_certs_active(network: &NetworkConfig, production: bool) -> bool {
+    network.allow_untrusted_certs && !production
+}
+
+pub fn status(network: &NetworkConfig, production: bool) -> TlsStatus {
+    let warning = if untrusted_certs_active(network, production) {
+        Some(
+            "TLS certificate verification is disabled: any server can impersonate your RPC nodes"
+                .to_string(),
+        )
+    } else if network.allow_untrusted_certs {
+        Some("Untrusted certificates are not allowed in production and are ignored".to_string())
+    } else {
+        None
+    };
+    let mut customized_hosts: Vec<String> = network
+        .endpoint_tls
+        .keys()
+        .filter_map(|endpoint| host_of(endpoint).ok())
+        .collect();
+    customized_hosts.dedup();
+    TlsStatus {
+        untrusted_certs: untrusted_certs_active(network, production),
+        customized_hosts,
+        warning,
+    }
+}
+
+/// Check TLS settings before they are saved.
+pub fn validate(network: &NetworkConfig, production: bool) -> WalletResult<()> {
+    if network.allow_untrusted_certs && production {
+        return Err(WalletError::ValidationError(
+            "Untrusted certificates cannot be allowed in production".to_string(),
+        ));
+    }
+    client_config(network, production).map(|_| ())
+}
+
+/// Certificate checks with the bundled web PKI roots only.
+pub fn default_config() -> WalletResult<Arc<ClientConfig>> {
+    client_config(&NetworkConfig::default(), true)
+}
+
+/// TLS client configuration honouring `network`'s trust settings.
+pub fn client_config(network: &NetworkConfig, production: bool) -> WalletResult<Arc<ClientConfig>> {
+    let provider = Arc::new(crypto::ring::default_provider());
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let default = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(|e| WalletError::CryptoError(format!("TLS verifier setup failed: {}", e)))?;
+
+    let mut hosts = HashMap::new();
+    for (endpoint, settings) in &network.endpoint_tls {
+        let policy = HostPolicy::from_settings(endpoint, settings, &provider)?;
+        if hosts.insert(host_of(endpoint)?, policy).is_some() {
+            return Err(WalletError::ValidationError(format!(
+                "Several TLS settings apply to the host of {}",
+                endpoint
+            )));
+        }
+    }
+
+    let accept_any = untrusted_certs_active(network, production);
+    if accept_any {
+        log::warn!("TLS certificate verification is DISABLED for RPC endpoints");
+    }
+    let verifier = EndpointVerifier {
+        default,
+        hosts,
+        accept_any,
+        provider: provider.clone(),
+    };
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| WalletError::CryptoError(format!("TLS setup failed: {}", e)))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+/// SHA-256 of a certificate's SubjectPublicKeyInfo, the value pins hold.
+fn spki_sha256(certificate: &CertificateDer<'_>) -> Option<[u8; 32]> {
+    let parsed = ParsedCertificate::try_from(certificate).ok()?;
+    Some(Sha256::digest(parsed.subject_public_key_info().as_ref()).into())
+}
+
+fn parse_pin(pin: &str) -> WalletResult<[u8; 32]> {
+    let encoded = pin.trim();
+    let encoded = encoded.strip_prefix(PIN_PREFIX).unwrap_or(encoded);
+    BASE64
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| {
+            WalletError::ValidationError(format!(
+                "Pins must be base64 SHA-256 key hashes, got '{}'",
+                pin
+            ))
+        })
+}
+
+pub(crate) fn host_of(endpoint: &str) -> WalletResult<String> {
+    Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .ok_or_else(|| WalletError::ValidationError(format!("Invalid endpoint: {}", endpoint)))
+}
+
+#[derive(Debug)]
+struct HostPolicy {
+    /// Verifier over the host's own CA bundle, replacing the web PKI roots.
+    ca: Option<Arc<WebPkiServerVerifier>>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl HostPolicy {
+    fn from_settings(
+        endpoint: &str,
+        settings: &EndpointTls,
+        provider: &Arc<CryptoProvider>,
+    ) -> WalletResult<Self> {
+        let pins = settings
+            .pinned_spki_sha256
+            .iter()
+            .map(|pin| parse_pin(pin))
+            .collect::<WalletResult<Vec<_>>>()?;
+        let ca = match &settings.ca_bundle_pem {
+            Some(pem) => {
+                let mut roots = RootCertStore::empty();
+                for certificate in CertificateDer::pem_slice_iter(pem.as_bytes()) {
+                    let certificate = certificate.map_err(|e| {
+                        WalletError::ValidationError(format!(
+                            "Invalid CA bundle for {}: {}",
+                            endpoint, e
+                        ))
+                    })?;
+                    roots.add(certificate).map_err(|e| {
+                        WalletError::ValidationError(format!(
+                            "Invalid CA certificate for {}: {}",
+                            endpoint, e
+                        ))
+                    })?;
+                }
+                if roots.is_empty() {
+                    return Err(WalletError::ValidationError(format!(
+                        "CA bundle for {} contains no certificates",
+                        endpoint
+                    )));
+                }
+                Some(
+                    WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                        .build()
+                        .map_err(|e| {
+                            WalletError::ValidationError(format!(
+                                "Unusable CA bundle for {}: {}",
+                                endpoint, e
+                            ))
+                        })?,
+                )
+            }
+            None => None,
+        };
+        if ca.is_none() && pins.is_empty() {
+            return Err(WalletError::ValidationError(format!(
+                "TLS settings for {} need pins or a CA bundle",
+                endpoint
+            )));
+        }
+        Ok(Self { ca, pins })
+    }
+
+    fn is_pinned(&self, certificate: &CertificateDer<'_>) -> bool {
+        spki_sha256(certificate).is_some_and(|digest| self.pins.contains(&digest))
+    }
+}
+
+#[derive(Debug)]
+struct EndpointVerifier {
+    default: Arc<WebPkiServerVerifier>,
+    hosts: HashMap<String, HostPolicy>,
+    accept_any: bool,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for EndpointVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        if self.accept_any {
+            return Ok(ServerCertVerified::assertion());
+        }
+        let host = server_name.to_str().to_ascii_lowercase();
+        let Some(policy) = self.hosts.get(host.as_str()) else {
+            return self.default.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            );
+        };
+
+        if let Some(ca) = &policy.ca {
+            ca.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        }
+        if !policy.pins.is_empty() && !policy.is_pinned(end_entity) {
+            return Err(tokio_rustls::rustls::Error::General(format!(
+                "Certificate key of {} does not match its pins",
+                host
+            )));
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinned(endpoint: &str, pin: &str) -> NetworkConfig {
+        let mut network = NetworkConfig::default();
+        network.endpoint_tls.insert(
+            endpoint.to_string(),
+            EndpointTls {
+                pinned_spki_sha256: vec![pin.to_string()],
+                ca_bundle_pem: None,
+            },
+        );
+        network
+    }
+
+    #[test]
+    fn untrusted_certs_are_refused_in_production() {
+        let network = NetworkConfig {
+            allow_untrusted_certs: true,
+            ..NetworkConfig::default()
+        };
+
+        assert!(untrusted_certs_active(&network, false));
+        assert!(status(&network, false).warning.is_some());
+        assert!(!untrusted_certs_active(&network, true));
+        assert!(!status(&network, true).untrusted_certs);
+        assert!(validate(&network, true).is_err());
+        assert!(validate(&network, false).is_ok());
+    }
+
+    #[test]
+    fn pins_and_bundles_are_checked_when_building() {
+        let pin = format!("sha256/{}", BASE64.encode([7u8; 32]));
+        let network = pinned("https://node.example:8443", &pin);
+        assert!(client_config(&network, true).is_ok());
+        assert_eq!(
+            status(&network, true).customized_hosts,
+            vec!["node.example".to_string()]
+        );
+
+        assert!(client_config(&pinned("https://node.example", "not-a-hash"), true).is_err());
+
+        let mut network = NetworkConfig::default();
+        network.endpoint_tls.insert(
+            "https://node.example".to_string(),
+            EndpointTls {
+                pinned_spki_sha256: Vec::new(),
+                ca_bundle_pem: Some(
+                    "-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----\n".to_string(),
+                ),
+            },
+        );
+        assert!(client_config(&network, true).is_err());
+    }
+}
@@ -0,0 +1,1425 @@
+//! In-process Silica node for integration tests.
+//!
+//! Serves the JSON-RPC methods `BlockchainClient` calls from in-memory state
+//! over plain HTTP on a loopback port, so wallet flows run offline and
+//! deterministically. Writes are checked the way a node would: transfers
+//! against the sender's registered key, nonce and balance, and signed
+//! governance payloads against the key bound to the signing account.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use silica_wallet_lib::blockchain::{BlockchainTransaction, PublicKey};
+use silica_wallet_lib::config_store::TrustedValidator;
+use silica_wallet_lib::governance::batch::{AccountDelegation, AccountSigned, AccountVote};
+use silica_wallet_lib::governance::{ProposalState, SignedProposal, VoteSupport};
+use silica_wallet_lib::light_client::{AccountProof, HeaderSignature, SignedHeader};
+use silica_wallet_lib::*;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Reported as a JSON number, as Chert nodes do.
+pub const CHAIN_ID: u64 = 1337;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const HEADER_DOMAIN: &[u8] = b"silica-header:v1:";
+const HEADER_SIGNING_DOMAIN: &[u8] = b"silica-header-commit:v1:";
+const ACCOUNT_LEAF_DOMAIN: &[u8] = b"silica-account:v1:";
+const VOTING_PERIOD_DAYS: i64 = 3;
+const PROPOSAL_THRESHOLD: i64 = 100_000;
+const DEFAULT_UNBONDING_DAYS: i64 = 21;
+const REWARD_APY: f64 = 5.0;
+/// Lockbox terms the mock chain offers and the multiplier each earns.
+const LOCKBOX_MULTIPLIERS: &[(u32, f64)] = &[(6, 1.25), (12, 1.5), (24, 2.0)];
+
+/// State tree leaf of an account, encoded the way the node commits to it
+/// rather than through the wallet's own helpers.
+pub fn account_leaf(address: &str, balance: u64, nonce: u64) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(ACCOUNT_LEAF_DOMAIN)
+        .chain_update(address.to_ascii_lowercase())
+        .chain_update([0])
+        .chain_update(balance.to_string())
+        .chain_update([0])
+        .chain_update(nonce.to_be_bytes())
+        .finalize()
+        .into()
+}
+
+/// Hash validators sign for a header that keeps the current validator set.
+pub fn header_hash(header: &SignedHeader) -> [u8; 32] {
+    let field = |hex_hash: &str| hex::decode(hex_hash).expect("hex header field");
+    Sha256::new()
+        .chain_update(HEADER_DOMAIN)
+        .chain_update(header.height.to_be_bytes())
+        .chain_update(field(&header.parent_hash))
+        .chain_update(field(&header.state_root))
+        .chain_update(header.timestamp.to_be_bytes())
+        .finalize()
+        .into()
+}
+
+/// Address of an Ed25519 account key, as the wallet derives it.
+pub fn address_of(key: &VerifyingKey) -> String {
+    PublicKey::from_bytes(key.to_bytes().to_vec())
+        .and_then(|key| key.to_address(AddressType::Account))
+        .expect("valid account key")
+        .as_string()
+        .to_string()
+}
+
+/// A running mock node; the server stops when this is dropped.
+pub struct MockNode {
+    url: String,
+    state: Arc<Mutex<NodeState>>,
+    server: JoinHandle<()>,
+}
+
+impl MockNode {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock node");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(NodeState::new()));
+        let server = tokio::spawn(serve(listener, state.clone()));
+        Self { url, state, server }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A client talking to this node only.
+    pub fn client(&self) -> BlockchainClient {
+        BlockchainClient::new(self.url.clone()).expect("mock node client")
+    }
+
+    /// Register an account controlled by `key` with `balance`, returning its
+    /// address.
+    pub fn open_account(&self, key: &VerifyingKey, balance: u64) -> String {
+        let address = address_of(key);
+        self.open_account_at(&address, key, balance);
+        address
+    }
+
+    /// Register `address` as controlled by `key`, for accounts whose address
+    /// the wallet derived itself.
+    pub fn open_account_at(&self, address: &str, key: &VerifyingKey, balance: u64) {
+        let mut state = self.state.lock();
+        let account = state.accounts.entry(normalize(address)).or_default();
+        account.public_key = Some(*key);
+        account.balance = balance;
+        state.commit();
+    }
+
+    pub fn add_validator(&self, address: &str, commission_rate: u32) {
+        let key = hex::encode(Sha256::digest(address.as_bytes()));
+        let info = ValidatorInfo {
+            address: address.to_string(),
+            public_key: key.clone(),
+            network_key: key,
+            stake: 1_000_000,
+            stake_amount: 1_000_000,
+            is_active: true,
+            commission_rate,
+            reputation_score: 90,
+            last_activity: Utc::now(),
+            total_delegated: None,
+            delegator_count: None,
+        };
+        self.state
+            .lock()
+            .validators
+            .insert(normalize(address), info);
+    }
+
+    /// Credit `amount` of unclaimed staking rewards to `address`.
+    pub fn add_rewards(&self, address: &str, amount: u64) {
+        let mut state = self.state.lock();
+        let rewards = state.rewards_mut(address);
+        rewards.pending_rewards += amount;
+        rewards.total_earned += amount;
+    }
+
+    /// Governance power `address` holds before delegations.
+    pub fn set_voting_power(&self, address: &str, power: i64) {
+        self.state
+            .lock()
+            .voting_power
+            .insert(normalize(address), power);
+    }
+
+    /// Account unsigned governance writes are attributed to, as for a node
+    /// whose RPC session is bound to one account.
+    pub fn set_operator(&self, address: &str) {
+        self.state.lock().operator = Some(address.to_string());
+    }
+
+    pub fn set_unbonding_period(&self, period: Duration) {
+        self.state.lock().unbonding_period = period;
+    }
+
+    /// Leave the completion time out of undelegation responses, as nodes
+    /// that predate it do.
+    pub fn omit_unbonding_completion(&self) {
+        self.state.lock().reports_unbonding_completion = false;
+    }
+
+    /// Hold new unbonding entries back from `staking_get_unbonding_entries`
+    /// until [`MockNode::index_unbonding`], like a node whose indexer lags.
+    pub fn delay_unbonding_index(&self) {
+        self.state.lock().unindexed_unbonding = Some(Vec::new());
+    }
+
+    /// Publish the unbonding entries held back so far.
+    pub fn index_unbonding(&self) {
+        let mut state = self.state.lock();
+        for (delegator, entry) in state.unindexed_unbonding.take().unwrap_or_default() {
+            state.unbonding.entry(delegator).or_default().push(entry);
+        }
+    }
+
+    /// Answer `method` with method-not-found, like a node without it.
+    pub fn disable_method(&self, method: &str) {
+        self.state.lock().disabled.insert(method.to_string());
+    }
+
+    /// Answer every batch request with a single error, like a node without
+    /// batch support.
+    pub fn reject_batches(&self) {
+        self.state.lock().batches_rejected = true;
+    }
+
+    /// The node's header signer, to pin in a light client.
+    pub fn trusted_validators(&self) -> Vec<TrustedValidator> {
+        vec![TrustedValidator {
+            public_key: hex::encode(self.state.lock().signer.verifying_key().to_bytes()),
+            voting_power: 1,
+        }]
+    }
+
+    /// Requests received for `method`, batched calls included.
+    pub fn calls(&self, method: &str) -> usize {
+        self.state
+            .lock()
+            .calls
+            .iter()
+            .filter(|call| *call == method)
+            .count()
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[derive(Debug)]
+struct Fault {
+    code: i64,
+    message: String,
+}
+
+impl Fault {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+
+    fn server(message: impl Into<String>) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: message.into(),
+        }
+    }
+}
+
+type Reply = Result<Value, Fault>;
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, Fault> {
+    serde_json::from_value(params).map_err(|e| Fault::invalid_params(e.to_string()))
+}
+
+fn reply<T: Serialize>(value: T) -> Reply {
+    Ok(serde_json::to_value(value).expect("serializable reply"))
+}
+
+fn normalize(address: &str) -> String {
+    address.to_ascii_lowercase()
+}
+
+#[derive(Deserialize)]
+struct AddressParams {
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct AccountParams {
+    account: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionParams {
+    tx_id: String,
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    address: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SendParams {
+    transaction: BlockchainTransaction,
+}
+
+#[derive(Deserialize)]
+struct HeaderParams {
+    height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct StakeParams {
+    delegator: String,
+    validator: String,
+    amount: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RedelegateParams {
+    delegator: String,
+    from_validator: String,
+    to_validator: String,
+    amount: u64,
+}
+
+#[derive(Deserialize)]
+struct WithdrawParams {
+    account: String,
+    source: String,
+    amount: u64,
+}
+
+#[derive(Deserialize)]
+struct ProposalParams {
+    proposal_id: i64,
+}
+
+#[derive(Deserialize)]
+struct VotesParams {
+    proposal_id: i64,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Default)]
+struct Account {
+    public_key: Option<VerifyingKey>,
+    balance: u64,
+    nonce: u64,
+}
+
+struct NodeState {
+    height: u64,
+    signer: SigningKey,
+    accounts: BTreeMap<String, Account>,
+    headers: BTreeMap<u64, SignedHeader>,
+    transactions: Vec<TransactionInfo>,
+    validators: BTreeMap<String, ValidatorInfo>,
+    /// Delegated stake keyed by (delegator, validator).
+    stakes: BTreeMap<(String, String), DelegationInfo>,
+    unbonding: BTreeMap<String, Vec<UnbondingEntry>>,
+    unbonding_period: Duration,
+    reports_unbonding_completion: bool,
+    /// Entries not yet visible to queries, while indexing is delayed.
+    unindexed_unbonding: Option<Vec<(String, UnbondingEntry)>>,
+    rewards: BTreeMap<String, StakingRewards>,
+    lockboxes: BTreeMap<String, Vec<LockBoxRecord>>,
+    auto_stake: BTreeMap<String, AutoStakeRecord>,
+    proposals: BTreeMap<i64, ProposalDetail>,
+    votes: BTreeMap<i64, Vec<VoteInfo>>,
+    voting_power: BTreeMap<String, i64>,
+    /// Governance delegations keyed by (delegator, delegatee).
+    power_delegations: BTreeMap<(String, String), DelegationInfo>,
+    operator: Option<String>,
+    disabled: BTreeSet<String>,
+    batches_rejected: bool,
+    calls: Vec<String>,
+    next_write: u64,
+}
+
+impl NodeState {
+    fn new() -> Self {
+        Self {
+            height: 1,
+            signer: SigningKey::from_bytes(&[0x5a; 32]),
+            accounts: BTreeMap::new(),
+            headers: BTreeMap::new(),
+            transactions: Vec::new(),
+            validators: BTreeMap::new(),
+            stakes: BTreeMap::new(),
+            unbonding: BTreeMap::new(),
+            unbonding_period: Duration::days(DEFAULT_UNBONDING_DAYS),
+            reports_unbonding_completion: true,
+            unindexed_unbonding: None,
+            rewards: BTreeMap::new(),
+            lockboxes: BTreeMap::new(),
+            auto_stake: BTreeMap::new(),
+            proposals: BTreeMap::new(),
+            votes: BTreeMap::new(),
+            voting_power: BTreeMap::new(),
+            power_delegations: BTreeMap::new(),
+            operator: None,
+            disabled: BTreeSet::new(),
+            batches_rejected: false,
+            calls: Vec::new(),
+            next_write: 0,
+        }
+    }
+
+    /// Seal the current state into a new block.
+    fn commit(&mut self) {
+        self.height += 1;
+    }
+
+    /// Id for a state-changing call that is not a transfer.
+    fn write_id(&mut self) -> String {
+        self.next_write += 1;
+        self.commit();
+        format!("0x{:064x}", self.next_write)
+    }
+
+    fn handle(&mut self, method: &str, params: Value) -> Reply {
+        self.calls.push(method.to_string());
+        if self.disabled.contains(method) {
+            return Err(Fault {
+                code: METHOD_NOT_FOUND,
+                message: format!("Method not found: {}", method),
+            });
+        }
+        match method {
+            "get_chain_id" => Ok(json!({ "chainId": CHAIN_ID })),
+            "eth_blockNumber" => Ok(json!({ "block_number": format!("0x{:x}", self.height) })),
+            "eth_gasPrice" => Ok(json!({ "gas_price": "0x1" })),
+            "get_balance" => self.get_balance(parse(params)?),
+            "get_transaction" => self.get_transaction(parse(params)?),
+            "get_transaction_history" => self.get_transaction_history(parse(params)?),
+            "send_transaction" => self.send_transaction(parse(params)?),
+            "get_block_header" => self.get_block_header(parse(params)?),
+            "get_account_proof" => self.get_account_proof(parse(params)?),
+            "staking_get_validators" => self.get_validators(),
+            "staking_get_user_delegations" => self.get_user_delegations(parse(params)?),
+            "staking_get_rewards" => self.get_rewards(parse(params)?),
+            "staking_get_lockbox_records" => self.get_lockbox_records(parse(params)?),
+            "staking_get_auto_stake_status" => self.get_auto_stake_status(parse(params)?),
+            "staking_get_unbonding_entries" => self.get_unbonding_entries(parse(params)?),
+            "staking_get_params" => self.get_staking_params(),
+            "staking_delegate_tokens" => self.delegate_tokens(parse(params)?),
+            "staking_undelegate_tokens" => self.undelegate_tokens(parse(params)?),
+            "staking_redelegate_tokens" => self.redelegate_tokens(parse(params)?),
+            "staking_withdraw" => self.withdraw(parse(params)?),
+            "staking_create_lockbox_stake" => self.create_lockbox_stake(parse(params)?),
+            "staking_toggle_auto_staking" => self.toggle_auto_staking(parse(params)?),
+            "staking_claim_rewards" => self.claim_rewards(parse(params)?),
+            "governance_list_proposals" => self.list_proposals(parse(params)?),
+            "governance_get_proposal" => self.get_proposal(parse(params)?),
+            "governance_get_proposal_votes" => self.get_proposal_votes(parse(params)?),
+            "governance_get_voting_power" => self.get_voting_power(parse(params)?),
+            "governance_get_delegations" => self.get_delegations(parse(params)?),
+            "governance_get_proposal_threshold" => reply(ProposalThresholdResponse {
+                threshold: PROPOSAL_THRESHOLD,
+            }),
+            "governance_create_proposal" => self.create_proposal(parse(params)?),
+            "governance_cast_vote" => self.cast_vote(parse(params)?),
+            "governance_cast_signed_vote" => self.cast_signed_vote(parse(params)?),
+            "governance_delegate_voting_power" => self.delegate_voting_power(parse(params)?),
+            "governance_delegate_signed" => self.delegate_signed(parse(params)?),
+            _ => Err(Fault {
+                code: METHOD_NOT_FOUND,
+                message: format!("Method not found: {}", method),
+            }),
+        }
+    }
+
+    // Accounts and transfers
+
+    fn get_balance(&self, params: AddressParams) -> Reply {
+        let (balance, nonce) = self
+            .accounts
+            .get(&normalize(&params.address))
+            .map_or((0, 0), |account| (account.balance, account.nonce));
+        reply(BalanceResponse {
+            address: params.address,
+            balance: balance.to_string(),
+            nonce,
+            verification: None,
+        })
+    }
+
+    fn get_transaction(&self, params: TransactionParams) -> Reply {
+        let transaction = self
+            .transactions
+            .iter()
+            .find(|tx| tx.transaction_id == params.tx_id)
+            .ok_or_else(|| Fault::server(format!("transaction {} not found", params.tx_id)))?;
+        reply(transaction)
+    }
+
+    fn get_transaction_history(&self, params: PageParams) -> Reply {
+        let address = normalize(&params.address);
+        let matching: Vec<&TransactionInfo> = self
+            .transactions
+            .iter()
+            .rev()
+            .filter(|tx| {
+                normalize(&tx.from_address) == address || normalize(&tx.to_address) == address
+            })
+            .collect();
+        reply(TransactionHistoryResponse {
+            total_count: matching.len() as u64,
+            transactions: matching
+                .into_iter()
+                .skip(params.offset.unwrap_or(0))
+                .take(params.limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    fn send_transaction(&mut self, params: SendParams) -> Reply {
+        let tx = params.transaction;
+        if self
+            .transactions
+            .iter()
+            .any(|known| known.transaction_id == tx.tx_id)
+        {
+            return Err(Fault::server("transaction already known"));
+        }
+        let sender = self
+            .accounts
+            .get(&normalize(&tx.sender))
+            .ok_or_else(|| Fault::server(format!("unknown sender {}", tx.sender)))?;
+        let key = sender
+            .public_key
+            .ok_or_else(|| Fault::server(format!("no key registered for {}", tx.sender)))?;
+        let key = PublicKey::from_bytes(key.to_bytes().to_vec())
+            .map_err(|e| Fault::server(e.to_string()))?;
+        if !tx.verify_signature(&key).unwrap_or(false) {
+            return Err(Fault::server("invalid transaction signature"));
+        }
+        if tx.nonce < sender.nonce {
+            return Err(Fault::server(format!(
+                "nonce too low: expected {}, got {}",
+                sender.nonce, tx.nonce
+            )));
+        }
+        if tx.nonce > sender.nonce {
+            return Err(Fault::server(format!(
+                "nonce too high: expected {}, got {}",
+                sender.nonce, tx.nonce
+            )));
+        }
+        let total = tx
+            .amount
+            .checked_add(tx.fee)
+            .ok_or_else(|| Fault::invalid_params("amount overflows"))?;
+        if sender.balance < total {
+            return Err(Fault::server(format!(
+                "insufficient funds: balance {}, need {}",
+                sender.balance, total
+            )));
+        }
+
+        let sender = self.accounts.get_mut(&normalize(&tx.sender)).unwrap();
+        sender.balance -= total;
+        sender.nonce += 1;
+        self.accounts
+            .entry(normalize(&tx.recipient))
+            .or_default()
+            .balance += tx.amount;
+        self.commit();
+        self.transactions.push(TransactionInfo {
+            transaction_id: tx.tx_id.clone(),
+            from_address: tx.sender,
+            to_address: tx.recipient,
+            amount: tx.amount.to_string(),
+            fee: tx.fee.to_string(),
+            status: "confirmed".to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            block_height: Some(self.height),
+            transaction_type: Some("transfer".to_string()),
+            explorer_url: None,
+        });
+        Ok(json!({ "tx_id": tx.tx_id }))
+    }
+
+    fn debit(&mut self, address: &str, amount: u64) -> Result<(), Fault> {
+        let account = self.accounts.entry(normalize(address)).or_default();
+        if account.balance < amount {
+            return Err(Fault::server(format!(
+                "insufficient balance: {} available, {} needed",
+                account.balance, amount
+            )));
+        }
+        account.balance -= amount;
+        Ok(())
+    }
+
+    fn credit(&mut self, address: &str, amount: u64) {
+        self.accounts.entry(normalize(address)).or_default().balance += amount;
+    }
+
+    // Light client
+
+    /// Leaves of the state tree in address order, padded to a power of two.
+    fn state_leaves(&self) -> (Vec<String>, Vec<[u8; 32]>) {
+        let addresses: Vec<String> = self.accounts.keys().cloned().collect();
+        let mut leaves: Vec<[u8; 32]> = self
+            .accounts
+            .iter()
+            .map(|(address, account)| account_leaf(address, account.balance, account.nonce))
+            .collect();
+        leaves.resize(leaves.len().max(1).next_power_of_two(), [0; 32]);
+        (addresses, leaves)
+    }
+
+    /// Root of `leaves` and the sibling path of leaf `index`.
+    fn merkle_path(mut leaves: Vec<[u8; 32]>, mut index: usize) -> ([u8; 32], Vec<String>) {
+        let mut path = Vec::new();
+        while leaves.len() > 1 {
+            path.push(hex::encode(leaves[index ^ 1]));
+            leaves = leaves
+                .chunks(2)
+                .map(|pair| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(pair[0]);
+                    hasher.update(pair[1]);
+                    hasher.finalize().into()
+                })
+                .collect();
+            index /= 2;
+        }
+        (leaves[0], path)
+    }
+
+    fn header(&mut self, height: u64) -> Result<SignedHeader, Fault> {
+        if let Some(header) = self.headers.get(&height) {
+            return Ok(header.clone());
+        }
+        if height != self.height {
+            return Err(Fault::server(format!("no header at height {}", height)));
+        }
+        let (_, leaves) = self.state_leaves();
+        let (root, _) = Self::merkle_path(leaves, 0);
+        let parent_hash = height
+            .checked_sub(1)
+            .and_then(|parent| self.headers.get(&parent))
+            .map_or_else(|| hex::encode([0u8; 32]), |parent| parent.hash.clone());
+        let mut header = SignedHeader {
+            height,
+            hash: String::new(),
+            parent_hash,
+            state_root: hex::encode(root),
+            timestamp: Utc::now().timestamp() as u64,
+            validators: Vec::new(),
+            signatures: Vec::new(),
+        };
+        let hash = header_hash(&header);
+        let mut message = HEADER_SIGNING_DOMAIN.to_vec();
+        message.extend_from_slice(&hash);
+        header.hash = hex::encode(hash);
+        header.signatures.push(HeaderSignature {
+            public_key: hex::encode(self.signer.verifying_key().to_bytes()),
+            signature: hex::encode(self.signer.sign(&message).to_bytes()),
+        });
+        self.headers.insert(height, header.clone());
+        Ok(header)
+    }
+
+    fn get_block_header(&mut self, params: HeaderParams) -> Reply {
+        let height = params.height.unwrap_or(self.height);
+        reply(self.header(height)?)
+    }
+
+    fn get_account_proof(&mut self, params: AddressParams) -> Reply {
+        let (addresses, leaves) = self.state_leaves();
+        let address = normalize(&params.address);
+        let index = addresses
+            .iter()
+            .position(|known| *known == address)
+            .ok_or_else(|| Fault::server(format!("unknown account {}", params.address)))?;
+        let (_, proof) = Self::merkle_path(leaves, index);
+        let account = &self.accounts[&address];
+        reply(AccountProof {
+            address: params.address,
+            balance: account.balance.to_string(),
+            nonce: account.nonce,
+            height: self.height,
+            leaf_index: index as u64,
+            proof,
+        })
+    }
+
+    // Staking
+
+    fn rewards_mut(&mut self, address: &str) -> &mut StakingRewards {
+        self.rewards
+            .entry(normalize(address))
+            .or_insert(StakingRewards {
+                total_earned: 0,
+                pending_rewards: 0,
+                current_apy: REWARD_APY,
+            })
+    }
+
+    fn active_validator(&self, address: &str) -> Result<(), Fault> {
+        match self.validators.get(&normalize(address)) {
+            Some(validator) if validator.is_active => Ok(()),
+            _ => Err(Fault::server(format!("unknown validator {}", address))),
+        }
+    }
+
+    fn get_validators(&self) -> Reply {
+        let validators = self
+            .validators
+            .values()
+            .map(|validator| {
+                let stakes: Vec<&DelegationInfo> = self
+                    .stakes
+                    .values()
+                    .filter(|stake| normalize(&stake.delegatee) == normalize(&validator.address))
+                    .collect();
+                ValidatorInfo {
+                    total_delegated: Some(stakes.iter().map(|stake| stake.amount as u64).sum()),
+                    delegator_count: Some(stakes.len() as u32),
+                    ..validator.clone()
+                }
+            })
+            .collect();
+        reply(GetValidatorsResponse { validators })
+    }
+
+    fn get_user_delegations(&self, params: AddressParams) -> Reply {
+        let address = normalize(&params.address);
+        reply(GetUserDelegationsResponse {
+            delegations: self
+                .stakes
+                .iter()
+                .filter(|((delegator, _), _)| *delegator == address)
+                .map(|(_, stake)| stake.clone())
+                .collect(),
+        })
+    }
+
+    fn get_rewards(&mut self, params: AddressParams) -> Reply {
+        let rewards = self.rewards_mut(&params.address).clone();
+        reply(GetStakingRewardsResponse { rewards })
+    }
+
+    fn get_lockbox_records(&self, params: AccountParams) -> Reply {
+        reply(GetLockboxRecordsResponse {
+            records: self
+                .lockboxes
+                .get(&normalize(&params.account))
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+
+    fn auto_stake(&self, account: &str) -> AutoStakeRecord {
+        self.auto_stake
+            .get(&normalize(account))
+            .cloned()
+            .unwrap_or_else(|| AutoStakeRecord {
+                account: account.to_string(),
+                balance: 0,
+                maturity_timestamp: Utc::now(),
+                is_active: false,
+            })
+    }
+
+    fn get_auto_stake_status(&self, params: AccountParams) -> Reply {
+        reply(GetAutoStakeStatusResponse {
+            status: self.auto_stake(&params.account),
+        })
+    }
+
+    fn get_unbonding_entries(&self, params: AddressParams) -> Reply {
+        reply(GetUnbondingEntriesResponse {
+            entries: self
+                .unbonding
+                .get(&normalize(&params.address))
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Change the stake of `delegator` with `validator` by `delta`.
+    fn adjust_stake(
+        &mut self,
+        delegator: &str,
+        validator: &str,
+        delta: i64,
+    ) -> Result<DelegationInfo, Fault> {
+        let key = (normalize(delegator), normalize(validator));
+        let current = self.stakes.get(&key).map_or(0, |stake| stake.amount);
+        let amount = current + delta;
+        if amount < 0 {
+            return Err(Fault::server(format!(
+                "insufficient balance: {} delegated to {}",
+                current, validator
+            )));
+        }
+        let stake = DelegationInfo {
+            delegator: delegator.to_string(),
+            delegatee: validator.to_string(),
+            amount,
+            delegated_at: Utc::now().timestamp(),
+        };
+        if amount == 0 {
+            self.stakes.remove(&key);
+        } else {
+            self.stakes.insert(key, stake.clone());
+        }
+        Ok(stake)
+    }
+
+    fn delegate_tokens(&mut self, params: StakeParams) -> Reply {
+        self.active_validator(&params.validator)?;
+        self.debit(&params.delegator, params.amount)?;
+        let delegation =
+            self.adjust_stake(&params.delegator, &params.validator, params.amount as i64)?;
+        reply(DelegateTokensResponse {
+            transaction_id: self.write_id(),
+            delegation,
+        })
+    }
+
+    fn undelegate_tokens(&mut self, params: StakeParams) -> Reply {
+        let updated_delegation = self.adjust_stake(
+            &params.delegator,
+            &params.validator,
+            -(params.amount as i64),
+        )?;
+        let completes_at = Utc::now() + self.unbonding_period;
+        let delegator = normalize(&params.delegator);
+        let entry = UnbondingEntry {
+            validator: params.validator,
+            amount: params.amount,
+            completes_at,
+        };
+        match self.unindexed_unbonding.as_mut() {
+            Some(unindexed) => unindexed.push((delegator, entry)),
+            None => self.unbonding.entry(delegator).or_default().push(entry),
+        }
+        reply(UndelegateTokensResponse {
+            transaction_id: self.write_id(),
+            updated_delegation,
+            unbonding_completes_at: self
+                .reports_unbonding_completion
+                .then(|| completes_at.timestamp()),
+        })
+    }
+
+    fn redelegate_tokens(&mut self, params: RedelegateParams) -> Reply {
+        self.active_validator(&params.to_validator)?;
+        self.adjust_stake(
+            &params.delegator,
+            &params.from_validator,
+            -(params.amount as i64),
+        )?;
+        let delegation = self.adjust_stake(
+            &params.delegator,
+            &params.to_validator,
+            params.amount as i64,
+        )?;
+        reply(RedelegateTokensResponse {
+            transaction_id: self.write_id(),
+            delegation: Some(delegation),
+        })
+    }
+
+    /// Take up to `amount` out of matured positions, oldest first.
+    fn withdraw(&mut self, params: WithdrawParams) -> Reply {
+        let account = normalize(&params.account);
+        let now = Utc::now();
+        let withdrawn = match params.source.as_str() {
+            "unbonding" => {
+                let entries = self.unbonding.entry(account).or_default();
+                let matured: u64 = entries
+                    .iter()
+                    .filter(|entry| entry.completes_at <= now)
+                    .map(|entry| entry.amount)
+                    .sum();
+                if matured < params.amount {
+                    return Err(Fault::server(format!(
+                        "insufficient balance: {} has matured",
+                        matured
+                    )));
+                }
+                let mut remaining = params.amount;
+                for entry in entries.iter_mut().filter(|entry| entry.completes_at <= now) {
+                    let taken = entry.amount.min(remaining);
+                    entry.amount -= taken;
+                    remaining -= taken;
+                }
+                entries.retain(|entry| entry.amount > 0);
+                params.amount
+            }
+            "lockbox" => {
+                let records = self.lockboxes.entry(account).or_default();
+                let matured = records
+                    .iter_mut()
+                    .find(|record| {
+                        record.is_active
+                            && record.unlock_at <= now
+                            && record.amount == params.amount
+                    })
+                    .ok_or_else(|| Fault::server("no matured lockbox holds that amount"))?;
+                matured.is_active = false;
+                params.amount
+            }
+            "autoStake" => {
+                let record = self
+                    .auto_stake
+                    .get_mut(&account)
+                    .filter(|record| record.maturity_timestamp <= now)
+                    .ok_or_else(|| Fault::server("auto-stake balance has not matured"))?;
+                if record.balance < params.amount {
+                    return Err(Fault::server("insufficient balance in auto-stake"));
+                }
+                record.balance -= params.amount;
+                params.amount
+            }
+            other => return Err(Fault::invalid_params(format!("unknown source {}", other))),
+        };
+        self.credit(&params.account, withdrawn);
+        reply(WithdrawStakeResponse {
+            transaction_id: self.write_id(),
+            withdrawn_amount: withdrawn,
+        })
+    }
+
+    fn get_staking_params(&self) -> Reply {
+        reply(GetStakingParamsResponse {
+            params: StakingParams {
+                unbonding_period_seconds: u64::try_from(self.unbonding_period.num_seconds()).ok(),
+                lockbox_multipliers: LOCKBOX_MULTIPLIERS
+                    .iter()
+                    .map(|&(term_months, multiplier)| LockboxMultiplier {
+                        term_months,
+                        multiplier,
+                    })
+                    .collect(),
+            },
+        })
+    }
+
+    fn create_lockbox_stake(&mut self, params: CreateLockboxStakeRequest) -> Reply {
+        let Some(&(_, multiplier)) = LOCKBOX_MULTIPLIERS
+            .iter()
+            .find(|(term, _)| *term == params.term_months)
+        else {
+            return Err(Fault::invalid_params("unsupported lockbox term"));
+        };
+        self.debit(&params.account, params.amount)?;
+        let locked_at: DateTime<Utc> = Utc::now();
+        let record = LockBoxRecord {
+            account: params.account.clone(),
+            amount: params.amount,
+            term_months: params.term_months,
+            locked_at,
+            unlock_at: locked_at + Duration::days(30 * params.term_months as i64),
+            multiplier,
+            is_active: true,
+        };
+        self.lockboxes
+            .entry(normalize(&params.account))
+            .or_default()
+            .push(record.clone());
+        reply(CreateLockboxStakeResponse {
+            transaction_id: self.write_id(),
+            lockbox_record: record,
+        })
+    }
+
+    fn toggle_auto_staking(&mut self, params: ToggleAutoStakingRequest) -> Reply {
+        let mut record = self.auto_stake(&params.account);
+        record.is_active = params.enable;
+        if params.enable {
+            record.maturity_timestamp = Utc::now() + Duration::days(30);
+        }
+        self.auto_stake
+            .insert(normalize(&params.account), record.clone());
+        self.commit();
+        reply(ToggleAutoStakingResponse {
+            success: true,
+            auto_stake_status: record,
+        })
+    }
+
+    fn claim_rewards(&mut self, params: ClaimStakingRewardsRequest) -> Reply {
+        let rewards = self.rewards_mut(&params.account);
+        let claimed_amount = std::mem::take(&mut rewards.pending_rewards);
+        self.credit(&params.account, claimed_amount);
+        reply(ClaimStakingRewardsResponse {
+            transaction_id: self.write_id(),
+            claimed_amount,
+        })
+    }
+
+    // Governance
+
+    /// Own power, minus what is delegated away, plus what is delegated in.
+    fn total_power(&self, address: &str) -> i64 {
+        let address = normalize(address);
+        let own = self.voting_power.get(&address).copied().unwrap_or(0);
+        self.power_delegations
+            .iter()
+            .fold(own, |power, ((from, to), delegation)| {
+                if *from == address {
+                    power - delegation.amount
+                } else if *to == address {
+                    power + delegation.amount
+                } else {
+                    power
+                }
+            })
+    }
+
+    /// Check a signature from `signer`, which must be the account bound to
+    /// `public_key`.
+    fn check_signer(
+        &self,
+        signer: &str,
+        algorithm: &str,
+        public_key: &str,
+        signature: &str,
+        message: &[u8],
+    ) -> Result<(), Fault> {
+        if algorithm != "Ed25519" {
+            return Err(Fault::invalid_params(format!(
+                "unsupported algorithm {}",
+                algorithm
+            )));
+        }
+        let key = hex::decode(public_key)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .ok_or_else(|| Fault::invalid_params("invalid public key"))?;
+        let signature = hex::decode(signature)
+            .ok()
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+            .map(|bytes| Signature::from_bytes(&bytes))
+            .ok_or_else(|| Fault::invalid_params("invalid signature encoding"))?;
+        if key.verify(message, &signature).is_err() {
+            return Err(Fault::server("invalid signature"));
+        }
+        let bound = self
+            .accounts
+            .get(&normalize(signer))
+            .and_then(|account| account.public_key);
+        if bound != Some(key) {
+            return Err(Fault::server(format!("signer does not control {}", signer)));
+        }
+        Ok(())
+    }
+
+    fn operator(&self) -> Result<String, Fault> {
+        self.operator
+            .clone()
+            .ok_or_else(|| Fault::server("unsigned governance requests need an operator account"))
+    }
+
+    fn list_proposals(&self, params: Option<GetProposalsRequest>) -> Reply {
+        let filter = params.unwrap_or(GetProposalsRequest {
+            state: None,
+            proposer: None,
+            limit: None,
+            offset: None,
+        });
+        let proposals = self
+            .proposals
+            .values()
+            .rev()
+            .filter(|proposal| {
+                filter
+                    .state
+                    .as_ref()
+                    .is_none_or(|state| ProposalState::from(state.as_str()) == proposal.state)
+            })
+            .filter(|proposal| {
+                filter
+                    .proposer
+                    .as_ref()
+                    .is_none_or(|proposer| proposer.eq_ignore_ascii_case(&proposal.proposer))
+            })
+            .skip(filter.offset.unwrap_or(0) as usize)
+            .take(filter.limit.map_or(usize::MAX, |limit| limit as usize))
+            .map(|proposal| ProposalSummary {
+                proposal_id: proposal.proposal_id,
+                proposer: proposal.proposer.clone(),
+                description: proposal.description.clone(),
+                vote_start: proposal.vote_start,
+                vote_end: proposal.vote_end,
+                votes_for: proposal.votes_for,
+                votes_against: proposal.votes_against,
+                votes_abstain: proposal.votes_abstain,
+                state: proposal.state.clone(),
+                created_at: proposal.created_at,
+            })
+            .collect();
+        reply(GetProposalsResponse { proposals })
+    }
+
+    fn proposal(&self, proposal_id: i64) -> Result<&ProposalDetail, Fault> {
+        self.proposals
+            .get(&proposal_id)
+            .ok_or_else(|| Fault::server(format!("proposal {} not found", proposal_id)))
+    }
+
+    fn get_proposal(&self, params: ProposalParams) -> Reply {
+        reply(GetProposalResponse {
+            proposal: self.proposal(params.proposal_id)?.clone(),
+            decoded_actions: Vec::new(),
+        })
+    }
+
+    fn get_proposal_votes(&self, params: VotesParams) -> Reply {
+        self.proposal(params.proposal_id)?;
+        reply(GetProposalVotesResponse {
+            votes: self
+                .votes
+                .get(&params.proposal_id)
+                .into_iter()
+                .flatten()
+                .skip(params.offset.unwrap_or(0))
+                .take(params.limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    fn get_voting_power(&self, params: AddressParams) -> Reply {
+        let address = normalize(&params.address);
+        let voting_power = self.voting_power.get(&address).copied().unwrap_or(0);
+        let delegated_power = self
+            .power_delegations
+            .iter()
+            .filter(|((_, to), _)| *to == address)
+            .map(|(_, delegation)| delegation.amount)
+            .sum();
+        reply(GetVotingPowerResponse {
+            voting_power: VotingPowerInfo {
+                address: params.address.clone(),
+                voting_power,
+                delegated_power,
+                total_power: self.total_power(&params.address),
+            },
+        })
+    }
+
+    fn get_delegations(&self, params: AddressParams) -> Reply {
+        let address = normalize(&params.address);
+        reply(GetDelegationsResponse {
+            delegations: self
+                .power_delegations
+                .iter()
+                .filter(|((from, to), _)| *from == address || *to == address)
+                .map(|(_, delegation)| delegation.clone())
+                .collect(),
+        })
+    }
+
+    fn create_proposal(&mut self, proposal: SignedProposal) -> Reply {
+        let payload = &proposal.payload;
+        let message = payload
+            .signing_bytes()
+            .map_err(|e| Fault::invalid_params(e.to_string()))?;
+        self.check_signer(
+            &payload.proposer,
+            &proposal.algorithm,
+            &proposal.public_key,
+            &proposal.signature,
+            &message,
+        )?;
+        let threshold = PROPOSAL_THRESHOLD;
+        let power = self.total_power(&payload.proposer);
+        if power < threshold {
+            return Err(Fault::server(format!(
+                "proposer voting power {} is below the proposal threshold of {}",
+                power, threshold
+            )));
+        }
+
+        let proposal_id = self.proposals.keys().next_back().map_or(1, |id| id + 1);
+        let now = Utc::now().timestamp();
+        self.proposals.insert(
+            proposal_id,
+            ProposalDetail {
+                proposal_id,
+                proposer: payload.proposer.clone(),
+                targets: payload.targets.clone(),
+                values: payload.values.clone(),
+                signatures: payload.signatures.clone(),
+                calldatas: payload.calldatas.clone(),
+                description: format!("# {}\n\n{}", payload.title, payload.description),
+                vote_start: now,
+                vote_end: now + Duration::days(VOTING_PERIOD_DAYS).num_seconds(),
+                votes_for: 0,
+                votes_against: 0,
+                votes_abstain: 0,
+                state: ProposalState::Active,
+                executed_at: None,
+                created_at: now,
+                updated_at: now,
+                has_voted: None,
+                user_vote: None,
+            },
+        );
+        reply(CreateProposalResponse {
+            proposal_id,
+            transaction_id: Some(self.write_id()),
+        })
+    }
+
+    fn cast_vote(&mut self, request: CastVoteRequest) -> Reply {
+        let vote = AccountVote {
+            voter: self.operator()?,
+            proposal_id: request.proposal_id,
+            support: request.support,
+            reason: request.reason,
+        };
+        self.record_vote(vote)
+    }
+
+    fn cast_signed_vote(&mut self, signed: AccountSigned<AccountVote>) -> Reply {
+        let message = signed
+            .payload
+            .signing_bytes()
+            .map_err(|e| Fault::invalid_params(e.to_string()))?;
+        self.check_signer(
+            &signed.payload.voter,
+            &signed.algorithm,
+            &signed.public_key,
+            &signed.signature,
+            &message,
+        )?;
+        self.record_vote(signed.payload)
+    }
+
+    fn record_vote(&mut self, vote: AccountVote) -> Reply {
+        let now = Utc::now().timestamp();
+        let proposal = self.proposal(vote.proposal_id)?;
+        if proposal.state != ProposalState::Active
+            || now < proposal.vote_start
+            || now >= proposal.vote_end
+        {
+            return Err(Fault::server(format!(
+                "voting on proposal {} is not open",
+                vote.proposal_id
+            )));
+        }
+        let weight = self.total_power(&vote.voter);
+        if weight <= 0 {
+            return Err(Fault::server(format!("{} has no voting power", vote.voter)));
+        }
+
+        let info = VoteInfo {
+            proposal_id: vote.proposal_id,
+            voter: vote.voter.clone(),
+            support: vote.support,
+            weight,
+            reason: vote.reason,
+            voted_at: now,
+        };
+        let votes = self.votes.entry(vote.proposal_id).or_default();
+        // A re-vote replaces the voter's earlier one.
+        let previous = votes
+            .iter()
+            .position(|known| known.voter.eq_ignore_ascii_case(&vote.voter))
+            .map(|index| votes.remove(index));
+        votes.push(info.clone());
+
+        let proposal = self.proposals.get_mut(&vote.proposal_id).unwrap();
+        if let Some(previous) = previous {
+            *tally(proposal, previous.support) -= previous.weight;
+        }
+        *tally(proposal, info.support) += info.weight;
+        proposal.updated_at = now;
+        self.commit();
+        reply(CastVoteResponse { vote: info })
+    }
+
+    fn delegate_voting_power(&mut self, request: DelegateRequest) -> Reply {
+        let delegation = AccountDelegation {
+            delegator: self.operator()?,
+            delegatee: request.delegatee,
+            amount: request.amount,
+        };
+        self.record_delegation(delegation)
+    }
+
+    fn delegate_signed(&mut self, signed: AccountSigned<AccountDelegation>) -> Reply {
+        let message = signed
+            .payload
+            .signing_bytes()
+            .map_err(|e| Fault::invalid_params(e.to_string()))?;
+        self.check_signer(
+            &signed.payload.delegator,
+            &signed.algorithm,
+            &signed.public_key,
+            &signed.signature,
+            &message,
+        )?;
+        self.record_delegation(signed.payload)
+    }
+
+    fn record_delegation(&mut self, delegation: AccountDelegation) -> Reply {
+        let key = (
+            normalize(&delegation.delegator),
+            normalize(&delegation.delegatee),
+        );
+        let own = self.voting_power.get(&key.0).copied().unwrap_or(0);
+        let delegated_elsewhere: i64 = self
+            .power_delegations
+            .iter()
+            .filter(|((from, to), _)| *from == key.0 && *to != key.1)
+            .map(|(_, existing)| existing.amount)
+            .sum();
+        if delegation.amount <= 0 || delegation.amount > own - delegated_elsewhere {
+            return Err(Fault::server(format!(
+                "insufficient voting power to delegate {}",
+                delegation.amount
+            )));
+        }
+        let info = DelegationInfo {
+            delegator: delegation.delegator,
+            delegatee: delegation.delegatee,
+            amount: delegation.amount,
+            delegated_at: Utc::now().timestamp(),
+        };
+        self.power_delegations.insert(key, info.clone());
+        self.commit();
+        reply(DelegateResponse { delegation: info })
+    }
+}
+
+fn tally(proposal: &mut ProposalDetail, support: VoteSupport) -> &mut i64 {
+    match support {
+        VoteSupport::For => &mut proposal.votes_for,
+        VoteSupport::Against => &mut proposal.votes_against,
+        VoteSupport::Abstain => &mut proposal.votes_abstain,
+    }
+}
+
+// HTTP transport
+
+fn answer(state: &Mutex<NodeState>, request: Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32600, "message": "Invalid request" },
+        });
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    match state.lock().handle(method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(fault) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": fault.code, "message": fault.message },
+        }),
+    }
+}
+
+fn respond(state: &Mutex<NodeState>, body: &[u8]) -> Value {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(_)) if state.lock().batches_rejected => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32600, "message": "Batch requests are not supported" },
+        }),
+        Ok(Value::Array(requests)) => Value::Array(
+            requests
+                .into_iter()
+                .map(|request| answer(state, request))
+                .collect(),
+        ),
+        Ok(request) => answer(state, request),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": format!("Parse error: {}", e) },
+        }),
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<NodeState>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve_connection(stream, state.clone()));
+    }
+}
+
+/// Answer HTTP/1.1 requests on `stream` until the client closes it.
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<NodeState>>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+
+        let (status, response) = if request_line.starts_with("POST /jsonrpc ") {
+            ("200 OK", serde_json::to_vec(&respond(&state, &body))?)
+        } else {
+            ("404 Not Found", Vec::new())
+        };
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            status,
+            response.len()
+        );
+        let stream = reader.get_mut();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&response).await?;
+    }
+}
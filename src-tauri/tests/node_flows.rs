@@ -0,0 +1,768 @@
+//! Wallet flows driven end to end against the in-process mock node.
+
+mod mock_node;
+
+use chrono::{Duration, Utc};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use mock_node::MockNode;
+use serde_json::json;
+use silica_wallet_lib::blockchain::{BlockchainTransaction, PrivateKey};
+use silica_wallet_lib::commands;
+use silica_wallet_lib::config_store::{LightClientConfig, TrustedValidator};
+use silica_wallet_lib::crypto::WalletKeyPair;
+use silica_wallet_lib::governance::batch::{AccountDelegation, AccountVote};
+use silica_wallet_lib::governance::{ProposalState, VoteSupport};
+use silica_wallet_lib::light_client::{
+    self, AccountProof, HeaderSignature, LightClient, SignedHeader,
+};
+use silica_wallet_lib::rpc_batch::RpcBatch;
+use silica_wallet_lib::staking::lockbox::plan_lockbox;
+use silica_wallet_lib::staking::maturity::{self, MaturityAction, MaturityKind};
+use silica_wallet_lib::staking::redelegation::{self, RedelegationMode, RedelegationStatus};
+use silica_wallet_lib::staking::{
+    MultiplierSchedule, RedelegationPlan, RedelegationStore, StakingSnapshot,
+};
+use silica_wallet_lib::{
+    AddAccountRequest, BatchVoteRequest, BlockchainClient, CastVoteRequest, CreateProposalRequest,
+    CreateWalletRequest, CreateWalletResponse, ExecuteMaturityActionRequest,
+    PlanLockboxStakeRequest, ProposalAction, RedelegateTokensRequest, WalletContext, WalletError,
+};
+use tempfile::TempDir;
+
+const VALIDATOR_A: &str = "0x00000000000000000000000000000000000000a1";
+const VALIDATOR_B: &str = "0x00000000000000000000000000000000000000b2";
+
+fn key(seed: u8) -> PrivateKey {
+    PrivateKey::from_bytes(vec![seed; 32]).unwrap()
+}
+
+fn verifying_key(key: &PrivateKey) -> VerifyingKey {
+    VerifyingKey::from_bytes(key.public_key().as_bytes().try_into().unwrap()).unwrap()
+}
+
+fn transfer(
+    signer: &PrivateKey,
+    from: &str,
+    to: &str,
+    amount: u64,
+    nonce: u64,
+) -> serde_json::Value {
+    let mut tx =
+        BlockchainTransaction::new(from.to_string(), to.to_string(), amount, 1_000, nonce, None)
+            .unwrap();
+    tx.sign(signer).unwrap();
+    serde_json::to_value(tx).unwrap()
+}
+
+async fn balance(client: &BlockchainClient, address: &str) -> (String, u64) {
+    let response = client.get_balance(address).await.unwrap();
+    (response.balance, response.nonce)
+}
+
+const PASSWORD: &str = "Mock4Node!Flows";
+
+/// A wallet in `dir` whose endpoint pool is just `node`.
+fn wallet_on(node: &MockNode, dir: &TempDir) -> WalletContext {
+    let mut context = WalletContext::initialize(dir.path().to_path_buf()).unwrap();
+    context
+        .update_config(|config| {
+            config.network.primary_endpoint = node.url().to_string();
+            config.network.failover_endpoints.clear();
+            Ok(())
+        })
+        .unwrap();
+    context
+}
+
+/// Create the vault through the command, as the onboarding screen does.
+fn create_wallet(context: &WalletContext) -> CreateWalletResponse {
+    commands::wallet::create_wallet(
+        context,
+        CreateWalletRequest {
+            wallet_name: "Flows".to_string(),
+            password: PASSWORD.to_string(),
+            mnemonic_word_count: 12,
+            use_post_quantum: false,
+        },
+    )
+    .unwrap()
+}
+
+fn public_key(hex_key: &str) -> VerifyingKey {
+    VerifyingKey::from_bytes(&hex::decode(hex_key).unwrap().try_into().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn transfers_move_funds_and_refresh_cached_balances() {
+    let node = MockNode::start().await;
+    let client = node.client();
+    let alice_key = key(1);
+    let alice = node.open_account(&verifying_key(&alice_key), 1_000_000);
+    let bob = node.open_account(&verifying_key(&key(2)), 0);
+
+    assert_eq!(
+        client.verify_chain().await.unwrap(),
+        mock_node::CHAIN_ID.to_string()
+    );
+    assert_eq!(balance(&client, &alice).await, ("1000000".to_string(), 0));
+    balance(&client, &alice).await;
+    assert_eq!(node.calls("get_balance"), 1, "second read is cached");
+
+    let receipt = client
+        .submit_transfer(&alice_key, &alice, &bob, 250_000, 1_000, None)
+        .await
+        .unwrap();
+    assert_eq!(receipt.fee, 1_000);
+    assert_eq!(balance(&client, &alice).await, ("749000".to_string(), 1));
+    assert_eq!(balance(&client, &bob).await, ("250000".to_string(), 0));
+
+    let info = client.get_transaction(&receipt.tx_id).await.unwrap();
+    assert_eq!(info.status, "confirmed");
+    let history = client
+        .get_transaction_history(&bob, Some(10), None)
+        .await
+        .unwrap();
+    assert_eq!(history.total_count, 1);
+    assert_eq!(history.transactions[0].transaction_id, receipt.tx_id);
+
+    // Back-to-back transfers each sign at a nonce fresh from the node.
+    let next = client
+        .submit_transfer(&alice_key, &alice, &bob, 1_000, 1_000, None)
+        .await
+        .unwrap();
+    assert_eq!(next.nonce, receipt.nonce + 1);
+}
+
+#[tokio::test]
+async fn pinned_clients_refuse_transfers_to_nodes_on_another_chain() {
+    let node = MockNode::start().await;
+    let client = node.client();
+    let alice_key = key(1);
+    let alice = node.open_account(&verifying_key(&alice_key), 100_000);
+    let bob = node.open_account(&verifying_key(&key(2)), 0);
+    client
+        .chain_pin()
+        .set_expected(Some("silica-othernet-1".to_string()));
+
+    let sent = client
+        .submit_transfer(&alice_key, &alice, &bob, 1_000, 10, None)
+        .await;
+    assert!(matches!(sent, Err(WalletError::ChainMismatch(_))));
+    assert_eq!(node.calls("send_transaction"), 0);
+}
+
+#[tokio::test]
+async fn rejected_transfers_map_to_wallet_errors() {
+    let node = MockNode::start().await;
+    let client = node.client();
+    let alice_key = key(1);
+    let alice = node.open_account(&verifying_key(&alice_key), 100_000);
+    let bob = node.open_account(&verifying_key(&key(2)), 0);
+
+    client
+        .send_transaction(transfer(&alice_key, &alice, &bob, 10_000, 0))
+        .await
+        .unwrap();
+
+    let replayed = client
+        .send_transaction(transfer(&alice_key, &alice, &bob, 10_000, 0))
+        .await;
+    assert!(matches!(replayed, Err(WalletError::NonceConflict(_))));
+
+    let overdrawn = client
+        .send_transaction(transfer(&alice_key, &alice, &bob, 500_000, 1))
+        .await;
+    assert!(matches!(
+        overdrawn,
+        Err(WalletError::InsufficientBalance(_))
+    ));
+
+    let forged = client
+        .send_transaction(transfer(&key(9), &alice, &bob, 10_000, 1))
+        .await;
+    match forged {
+        Err(WalletError::RpcError { message, .. }) => assert!(message.contains("signature")),
+        other => panic!("forged transfer accepted: {:?}", other),
+    }
+
+    assert_eq!(balance(&client, &alice).await, ("89000".to_string(), 1));
+}
+
+#[tokio::test]
+async fn staking_positions_round_trip_through_the_node() {
+    let node = MockNode::start().await;
+    let client = node.client();
+    let account = node.open_account(&verifying_key(&key(3)), 1_000_000);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.set_unbonding_period(Duration::zero());
+
+    client
+        .delegate_tokens(&account, VALIDATOR_A, 300_000)
+        .await
+        .unwrap();
+    client
+        .create_lockbox_stake(&account, 100_000, 12)
+        .await
+        .unwrap();
+    client.toggle_auto_staking(&account, true).await.unwrap();
+    let validators = client.get_validators().await.unwrap().validators;
+    assert_eq!(validators[0].total_delegated, Some(300_000));
+
+    // The node reports 600000 base units; planning must not read them as CHERT.
+    let (balance_now, rewards, lockbox, params) = tokio::try_join!(
+        client.get_balance(&account),
+        client.get_staking_rewards(&account),
+        client.get_lockbox_records(&account),
+        client.get_staking_params(),
+    )
+    .unwrap();
+    let schedule = MultiplierSchedule::from_chain(
+        &params.unwrap().params.lockbox_multipliers,
+        &lockbox.records,
+    );
+    let plan = |amount: u64| {
+        plan_lockbox(
+            &PlanLockboxStakeRequest {
+                account: account.clone(),
+                amount,
+                term_months: vec![6, 12, 18],
+                liquidity_horizon_months: Some(12),
+            },
+            rewards.rewards.current_apy,
+            &balance_now.spendable().unwrap(),
+            &schedule,
+            chrono::Utc::now(),
+        )
+    };
+    assert!(matches!(
+        plan(600_001),
+        Err(WalletError::InvalidAmount(message)) if message.contains("exceeds spendable balance")
+    ));
+    let lockbox_plan = plan(600_000).unwrap();
+    assert_eq!(lockbox_plan.projections.len(), 2);
+    assert_eq!(lockbox_plan.unpriced_terms, vec![18]);
+    assert_eq!(lockbox_plan.recommended_term, Some(12));
+
+    node.add_rewards(&account, 4_200);
+    let snapshot = StakingSnapshot::capture(&client, &account).await.unwrap();
+    assert_eq!(snapshot.staked_amount(), 400_000);
+    assert_eq!(snapshot.rewards.pending_rewards, 4_200);
+    let claimed = client.claim_staking_rewards(&account).await.unwrap();
+    assert_eq!(claimed.claimed_amount, 4_200);
+    assert_eq!(balance(&client, &account).await.0, "604200");
+
+    client
+        .undelegate_tokens(&account, VALIDATOR_A, 100_000)
+        .await
+        .unwrap();
+    let entries = maturity::fetch_entries(&client, &account).await.unwrap();
+    assert_eq!(
+        entries.len(),
+        2,
+        "lockbox and unbonding; auto-stake is empty"
+    );
+    let unbonding = entries
+        .iter()
+        .find(|entry| entry.kind == MaturityKind::Unbonding)
+        .unwrap();
+    maturity::execute_action(&client, unbonding, MaturityAction::Withdraw)
+        .await
+        .unwrap();
+    assert_eq!(balance(&client, &account).await.0, "704200");
+    assert!(client
+        .get_unbonding_entries(&account)
+        .await
+        .unwrap()
+        .unwrap()
+        .entries
+        .is_empty());
+}
+
+#[tokio::test]
+async fn wallet_commands_send_stake_and_redelegate_from_the_primary_account() {
+    let dir = TempDir::new().unwrap();
+    let node = MockNode::start().await;
+    let context = wallet_on(&node, &dir);
+    let created = create_wallet(&context);
+    let account = created.address.clone();
+    node.open_account_at(&account, &public_key(&created.public_key), 1_000_000);
+    let bob = node.open_account(&verifying_key(&key(2)), 0);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.set_unbonding_period(Duration::zero());
+    let client = context.rpc_client();
+
+    let receipt = commands::transfer::send_transfer(&context, client, &bob, 100_000, Some(1_000))
+        .await
+        .unwrap();
+    assert_eq!(receipt.sender, account);
+    assert_eq!(receipt.explorer_url, None, "no network profile is selected");
+    let without_fee = commands::transfer::send_transfer(&context, client, &bob, 1_000, None).await;
+    assert!(matches!(without_fee, Err(WalletError::ValidationError(_))));
+    assert_eq!(balance(client, &bob).await.0, "100000");
+
+    let stake = |amount: u64| {
+        json!({
+            "delegatorAddress": account,
+            "validatorAddress": VALIDATOR_A,
+            "amount": amount,
+        })
+    };
+    commands::staking::delegate_tokens(&context, &stake(300_000))
+        .await
+        .unwrap();
+    let missing = json!({ "delegatorAddress": account, "validatorAddress": VALIDATOR_A });
+    assert!(commands::staking::delegate_tokens(&context, &missing)
+        .await
+        .is_err());
+    commands::staking::undelegate_tokens(&context, &stake(100_000))
+        .await
+        .unwrap();
+    assert_eq!(balance(client, &account).await.0, "599000");
+
+    // The background scan is what puts maturities in the tracker.
+    let tracker = context.maturity_tracker();
+    let entries = maturity::fetch_entries(client, &account).await.unwrap();
+    tracker.sync_account(&account, entries, Utc::now()).unwrap();
+    let unbonding = tracker
+        .entries()
+        .unwrap()
+        .into_iter()
+        .find(|entry| entry.kind == MaturityKind::Unbonding)
+        .unwrap();
+    let executed = commands::staking::execute_maturity_action(
+        &context,
+        ExecuteMaturityActionRequest {
+            entry_id: unbonding.id.clone(),
+            action: MaturityAction::Withdraw,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(executed.entry_id, unbonding.id);
+    assert!(tracker.find(&unbonding.id).is_err(), "no longer tracked");
+    assert_eq!(balance(client, &account).await.0, "699000");
+
+    let redelegate = |to_validator: &str| RedelegateTokensRequest {
+        delegator_address: account.clone(),
+        from_validator: VALIDATOR_A.to_string(),
+        to_validator: to_validator.to_string(),
+        amount: 50_000,
+    };
+    assert!(
+        commands::staking::redelegate_tokens(&context, redelegate(VALIDATOR_A))
+            .await
+            .is_err()
+    );
+    let plan = commands::staking::redelegate_tokens(&context, redelegate(VALIDATOR_B))
+        .await
+        .unwrap();
+    assert_eq!(plan.mode, Some(RedelegationMode::Native));
+    assert_eq!(plan.status, RedelegationStatus::Completed);
+    assert_eq!(context.redelegations().list().unwrap().len(), 1);
+    let delegated = StakingSnapshot::capture(client, &account)
+        .await
+        .unwrap()
+        .delegated_by_validator();
+    assert_eq!(delegated[VALIDATOR_A], 150_000);
+    assert_eq!(delegated[VALIDATOR_B], 50_000);
+}
+
+#[tokio::test]
+async fn redelegation_falls_back_when_the_node_lacks_native_support() {
+    let dir = TempDir::new().unwrap();
+    let store = RedelegationStore::new(dir.path());
+    let node = MockNode::start().await;
+    let client = node.client();
+    let account = node.open_account(&verifying_key(&key(4)), 1_000_000);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.set_unbonding_period(Duration::zero());
+    client
+        .delegate_tokens(&account, VALIDATOR_A, 200_000)
+        .await
+        .unwrap();
+
+    let mut native = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut native)
+        .await
+        .unwrap();
+    assert_eq!(native.mode, Some(RedelegationMode::Native));
+
+    node.disable_method("staking_redelegate_tokens");
+    let mut orchestrated = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut orchestrated)
+        .await
+        .unwrap();
+    assert_eq!(orchestrated.mode, Some(RedelegationMode::Orchestrated));
+    assert_eq!(orchestrated.status, RedelegationStatus::Completed);
+
+    let snapshot = StakingSnapshot::capture(&client, &account).await.unwrap();
+    let delegated = snapshot.delegated_by_validator();
+    assert_eq!(delegated[VALIDATOR_A], 100_000);
+    assert_eq!(delegated[VALIDATOR_B], 100_000);
+}
+
+#[tokio::test]
+async fn orchestrated_redelegations_wait_for_the_chains_unbonding_period() {
+    let dir = TempDir::new().unwrap();
+    let store = RedelegationStore::new(dir.path());
+    let node = MockNode::start().await;
+    let client = node.client();
+    let account = node.open_account(&verifying_key(&key(4)), 1_000_000);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.set_unbonding_period(Duration::days(21));
+    node.disable_method("staking_redelegate_tokens");
+    node.omit_unbonding_completion();
+    client
+        .delegate_tokens(&account, VALIDATOR_A, 200_000)
+        .await
+        .unwrap();
+
+    let mut from_params = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut from_params)
+        .await
+        .unwrap();
+    assert_eq!(from_params.status, RedelegationStatus::Unbonding);
+    let expected = Utc::now() + Duration::days(21);
+    let completes_at = from_params.unbonding_completes_at.unwrap();
+    assert!((expected - completes_at).num_seconds().abs() < 60);
+
+    // Without published parameters, the node's unbonding entries tell.
+    node.disable_method("staking_get_params");
+    let mut from_entries = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut from_entries)
+        .await
+        .unwrap();
+    assert_eq!(from_entries.status, RedelegationStatus::Unbonding);
+    let latest = client
+        .get_unbonding_entries(&account)
+        .await
+        .unwrap()
+        .unwrap()
+        .entries
+        .iter()
+        .map(|entry| entry.completes_at)
+        .max();
+    assert_eq!(from_entries.unbonding_completes_at, latest);
+
+    // A node that reports neither leaves the plan waiting without a date.
+    node.disable_method("staking_get_unbonding_entries");
+    let mut unknown = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut unknown)
+        .await
+        .unwrap();
+    assert_eq!(unknown.status, RedelegationStatus::Unbonding);
+    assert_eq!(unknown.unbonding_completes_at, None);
+    assert_eq!(node.calls("staking_delegate_tokens"), 1);
+}
+
+#[tokio::test]
+async fn redelegations_keep_waiting_until_the_node_indexes_the_unbonding() {
+    let dir = TempDir::new().unwrap();
+    let store = RedelegationStore::new(dir.path());
+    let node = MockNode::start().await;
+    let client = node.client();
+    let account = node.open_account(&verifying_key(&key(4)), 1_000_000);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.disable_method("staking_redelegate_tokens");
+    node.disable_method("staking_get_params");
+    node.omit_unbonding_completion();
+    node.delay_unbonding_index();
+    client
+        .delegate_tokens(&account, VALIDATOR_A, 200_000)
+        .await
+        .unwrap();
+
+    let mut plan = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    redelegation::advance(&client, &store, &mut plan)
+        .await
+        .unwrap();
+    redelegation::advance(&client, &store, &mut plan)
+        .await
+        .unwrap();
+    assert_eq!(plan.status, RedelegationStatus::Unbonding);
+    assert_eq!(plan.unbonding_completes_at, None);
+    assert_eq!(node.calls("staking_delegate_tokens"), 1);
+
+    node.index_unbonding();
+    redelegation::advance(&client, &store, &mut plan)
+        .await
+        .unwrap();
+    assert_eq!(plan.status, RedelegationStatus::Unbonding);
+    let completes_at = plan.unbonding_completes_at.unwrap();
+    assert!(completes_at > Utc::now() + Duration::days(20));
+    assert_eq!(node.calls("staking_delegate_tokens"), 1);
+}
+
+#[tokio::test]
+async fn nodes_without_a_chain_id_only_block_pinned_writes() {
+    let dir = TempDir::new().unwrap();
+    let store = RedelegationStore::new(dir.path());
+    let node = MockNode::start().await;
+    let client = node.client();
+    let account = node.open_account(&verifying_key(&key(4)), 1_000_000);
+    node.add_validator(VALIDATOR_A, 5);
+    node.add_validator(VALIDATOR_B, 8);
+    node.disable_method("get_chain_id");
+
+    client
+        .delegate_tokens(&account, VALIDATOR_A, 200_000)
+        .await
+        .expect("unpinned writes skip the chain check");
+
+    client
+        .chain_pin()
+        .set_expected(Some(mock_node::CHAIN_ID.to_string()));
+    let mut plan = RedelegationPlan::new(&account, VALIDATOR_A, VALIDATOR_B, 50_000);
+    let refused = redelegation::advance(&client, &store, &mut plan).await;
+    assert!(matches!(refused, Err(WalletError::ChainMismatch(_))));
+    assert_eq!(plan.mode, None, "not mistaken for missing native support");
+    assert_eq!(node.calls("staking_redelegate_tokens"), 0);
+}
+
+#[tokio::test]
+async fn governance_proposals_votes_and_delegations_are_signed_by_their_accounts() {
+    let dir = TempDir::new().unwrap();
+    let node = MockNode::start().await;
+    let context = wallet_on(&node, &dir);
+    let created = create_wallet(&context);
+    let client = context.rpc_client();
+
+    // The wallet's primary account, one added to its vault, and an outsider.
+    let (added, mnemonic) = WalletKeyPair::generate_with_mnemonic(12, None, None, false).unwrap();
+    commands::wallet::add_wallet_account(
+        &context,
+        AddAccountRequest {
+            password: PASSWORD.to_string(),
+            mnemonic,
+        },
+    )
+    .unwrap();
+    let primary = created.address.clone();
+    let member = added.address();
+    let outsider_key = SigningKey::from_bytes(&[9; 32]);
+    node.open_account_at(&primary, &public_key(&created.public_key), 0);
+    node.open_account_at(&member, &public_key(&added.public_key_hex()), 0);
+    let outsider = node.open_account(&outsider_key.verifying_key(), 0);
+    node.set_voting_power(&primary, 60_000);
+    node.set_voting_power(&member, 40_000);
+    node.set_voting_power(&outsider, 10_000);
+
+    let request = CreateProposalRequest {
+        title: "Raise the cap".to_string(),
+        description: "Lift the treasury cap".to_string(),
+        actions: vec![ProposalAction {
+            target: VALIDATOR_A.to_string(),
+            value: String::new(),
+            signature: String::new(),
+            calldata: "0x".to_string(),
+        }],
+    };
+    let below_threshold = commands::governance::create_proposal(&context, request.clone()).await;
+    assert!(below_threshold.is_err());
+    assert_eq!(
+        node.calls("governance_create_proposal"),
+        0,
+        "refused before signing"
+    );
+    node.set_voting_power(&primary, 150_000);
+    let proposal_id = commands::governance::create_proposal(&context, request)
+        .await
+        .unwrap()
+        .proposal_id;
+
+    let results = commands::governance::batch_cast_vote(
+        &context,
+        BatchVoteRequest {
+            proposal_id,
+            support: VoteSupport::For,
+            reason: None,
+            accounts: vec![member.clone(), outsider.clone()],
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 2);
+    let result = |account: &str| {
+        results
+            .iter()
+            .find(|result| result.account.eq_ignore_ascii_case(account))
+            .unwrap()
+    };
+    assert_eq!(result(&member).vote.as_ref().unwrap().weight, 40_000);
+    assert!(result(&outsider).vote.is_none());
+    assert!(result(&outsider).error.is_some(), "not a vault account");
+
+    let impersonated = AccountVote {
+        voter: outsider.clone(),
+        proposal_id,
+        support: VoteSupport::For,
+        reason: None,
+    }
+    .sign(&SigningKey::from_bytes(&[8; 32]))
+    .unwrap();
+    assert!(client.cast_signed_vote(&impersonated).await.is_err());
+
+    let delegation = AccountDelegation {
+        delegator: outsider.clone(),
+        delegatee: primary.clone(),
+        amount: 10_000,
+    };
+    client
+        .delegate_signed(&delegation.sign(&outsider_key).unwrap())
+        .await
+        .unwrap();
+    let power = client.get_voting_power(&primary).await.unwrap();
+    assert_eq!(power.voting_power.total_power, 160_000);
+
+    node.set_operator(&primary);
+    commands::governance::cast_vote(
+        &context,
+        CastVoteRequest {
+            proposal_id,
+            support: VoteSupport::Against,
+            reason: Some("Too soon".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+
+    let proposal = client.get_proposal(proposal_id).await.unwrap().proposal;
+    assert_eq!(proposal.state, ProposalState::Active);
+    assert_eq!(
+        (proposal.votes_for, proposal.votes_against),
+        (40_000, 160_000)
+    );
+    let votes = client
+        .get_proposal_votes(proposal_id, None, None)
+        .await
+        .unwrap();
+    assert_eq!(votes.votes.len(), 2);
+    let listed = client.get_proposals(None).await.unwrap().proposals;
+    assert_eq!(listed[0].proposer, primary);
+}
+
+#[tokio::test]
+async fn light_client_verifies_balances_against_signed_headers() {
+    let node = MockNode::start().await;
+    let alice_key = key(5);
+    let alice = node.open_account(&verifying_key(&alice_key), 500_000);
+    let bob = node.open_account(&verifying_key(&key(6)), 0);
+    let light_client = |validators| {
+        node.client()
+            .with_light_client(LightClient::new(LightClientConfig {
+                enabled: true,
+                validators,
+            }))
+    };
+
+    let client = light_client(node.trusted_validators());
+    let first = client
+        .get_balance(&alice)
+        .await
+        .unwrap()
+        .verification
+        .unwrap();
+    assert!(first.verified);
+    client
+        .send_transaction(transfer(&alice_key, &alice, &bob, 1_000, 0))
+        .await
+        .unwrap();
+    let after = client.get_balance(&bob).await.unwrap();
+    assert_eq!(after.balance, "1000");
+    let verification = after.verification.unwrap();
+    assert!(verification.verified);
+    assert!(verification.height > first.height);
+
+    let mut untrusted = node.trusted_validators();
+    untrusted[0].public_key =
+        hex::encode(SigningKey::from_bytes(&[1; 32]).verifying_key().to_bytes());
+    let verification = light_client(untrusted)
+        .get_balance(&alice)
+        .await
+        .unwrap()
+        .verification
+        .unwrap();
+    assert!(!verification.verified);
+}
+
+#[tokio::test]
+async fn batched_reads_are_checked_and_survive_nodes_without_batches() {
+    let node = MockNode::start().await;
+    let alice = node.open_account(&verifying_key(&key(3)), 750_000);
+    let client = node
+        .client()
+        .with_light_client(LightClient::new(LightClientConfig {
+            enabled: true,
+            validators: node.trusted_validators(),
+        }));
+    node.reject_batches();
+
+    let mut batch = RpcBatch::new();
+    let balance = batch.get_balance(&alice);
+    let delegations = batch.get_user_delegations(&alice);
+    let response = client.send_batch(&batch).await.unwrap();
+
+    let balance = response.get(balance).unwrap();
+    assert_eq!(balance.balance, "750000");
+    assert!(balance.verification.unwrap().verified);
+    assert!(response.get(delegations).unwrap().delegations.is_empty());
+    assert_eq!(node.calls("get_account_proof"), 1);
+}
+
+#[test]
+fn wallet_and_mock_node_agree_with_an_independent_proof_vector() {
+    // Computed outside this codebase (Python hashlib and `cryptography`) from
+    // the leaf and header encodings, with the header signed by the Ed25519
+    // key whose seed is 32 bytes of 42.
+    const LEAF_A: &str = "1214f1693a5623c3c0e49e423a79300fc99740e2c0287acd410778db9d067b6f";
+    const LEAF_B: &str = "482871bd138a832b3e68c1e2461d4ecfd33fa30d244c86282aeededb5b11fcd5";
+    const STATE_ROOT: &str = "8f47239f9c67f82da8571b735766d78459185f1174a7e13838d08a493461e698";
+    const HEADER_HASH: &str = "6b4aa2a188062dd60bc83f458e1d6ad9c8093b827c64b2d1c63ed2ccc2396ee1";
+    const SIGNER: &str = "197f6b23e16c8532c6abc838facd5ea789be0c76b2920334039bfa8b3d368d61";
+    const SIGNATURE: &str = "f6da8560518568df4c18c93c189eb22d0429541c4dac292849f391c6bf388473\
+                             bdf5fe56e5ef4605aae47d571d1e0998dda6ec95e31c6d6c74cda48abb43f004";
+
+    assert_eq!(
+        hex::encode(mock_node::account_leaf(VALIDATOR_A, 5_000, 2)),
+        LEAF_A
+    );
+    assert_eq!(
+        hex::encode(mock_node::account_leaf(VALIDATOR_B, 750_000, 0)),
+        LEAF_B
+    );
+    let header = SignedHeader {
+        height: 7,
+        hash: HEADER_HASH.to_string(),
+        parent_hash: "11".repeat(32),
+        state_root: STATE_ROOT.to_string(),
+        timestamp: 1_700_000_000,
+        validators: Vec::new(),
+        signatures: vec![HeaderSignature {
+            public_key: SIGNER.to_string(),
+            signature: SIGNATURE.to_string(),
+        }],
+    };
+    assert_eq!(hex::encode(mock_node::header_hash(&header)), HEADER_HASH);
+
+    let trusted = [TrustedValidator {
+        public_key: SIGNER.to_string(),
+        voting_power: 10,
+    }];
+    light_client::verify_header(&header, &trusted).unwrap();
+    let mut proof = AccountProof {
+        address: VALIDATOR_B.to_string(),
+        balance: "750000".to_string(),
+        nonce: 0,
+        height: 7,
+        leaf_index: 1,
+        proof: vec![LEAF_A.to_string()],
+    };
+    light_client::verify_account_proof(&proof, &header).unwrap();
+    proof.balance = "750001".to_string();
+    assert!(light_client::verify_account_proof(&proof, &header).is_err());
+}
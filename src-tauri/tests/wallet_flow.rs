@@ -1,5 +1,5 @@
-use chert_wallet_lib::crypto::{StealthKeyMaterial, WalletKeyPair};
-use chert_wallet_lib::{VaultMetadata, VaultSecrets, WalletContext, WalletError, WalletResult};
+use silica_wallet_lib::crypto::{StealthKeyMaterial, WalletKeyPair};
+use silica_wallet_lib::{VaultMetadata, VaultSecrets, WalletContext, WalletError, WalletResult};
 use secrecy::SecretString;
 use tempfile::TempDir;
 
@@ -16,6 +16,7 @@ fn wallet_create_lock_unlock_export_flow() -> WalletResult<()> {
         seed_bytes: keypair.core_keypair.private_key.clone(),
         stealth_material: stealth_keys.encode(),
         pq_material: Vec::new(),
+        account_keys: Vec::new(),
     };
 
     let password = SecretString::from("Password123!".to_string());
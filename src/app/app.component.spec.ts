@@ -108,6 +108,10 @@ const mockBackend = {
   formatAmount: async () => ({ formatted: '0' }),
   getBalance: async () => ({ address: 'chert_mock', balance: '0', nonce: 0 }),
   getTransactionHistory: async () => ({ transactions: [], totalCount: 0 }),
+  getTlsStatus: async () => ({ untrustedCerts: false, customizedHosts: [], warning: null }),
+  getConnectorStatus: async () => ({ listening: null, lastError: null, pending: [] }),
+  resolveConnectorRequest: async () => ({ listening: null, lastError: null, pending: [] }),
+  onConnectorRequest: async () => () => {},
   getProposals: async () => ({ proposals: [] }),
   getProposal: async () => ({ proposal: { id: 1, title: 'Mock', description: 'Mock', proposer: 'mock', voteStart: 0, voteEnd: 0, votesFor: 0, votesAgainst: 0, votesAbstain: 0, state: 'pending', executedAt: null, createdAt: '', updatedAt: '' } }),
   getProposalVotes: async () => ({ votes: [] }),
@@ -3,11 +3,12 @@ import { CommonModule } from '@angular/common';
 import { RouterOutlet, RouterLink, RouterLinkActive } from '@angular/router';
 import { ReactiveFormsModule, FormBuilder, FormGroup, Validators } from '@angular/forms';
 import { WasmService } from './core/services/wasm.service';
-import { WalletInfoResponse, CreateWalletRequest, ImportWalletRequest } from './core/types/wallet.types';
+import { WalletInfoResponse, CreateWalletRequest, ImportWalletRequest, TlsStatus } from './core/types/wallet.types';
 import { WALLET_BACKEND, WalletBackend } from './core/services/wallet-backend.interface';
 import { ModalHostComponent } from './core/ui/modal-host/modal-host.component';
 import { UnlockWalletComponent } from './core/ui/unlock-wallet/unlock-wallet.component';
 import { ThemeToggleComponent } from './core/ui/theme-toggle/theme-toggle.component';
+import { ConnectorPromptComponent } from './core/ui/connector-prompt/connector-prompt.component';
 
 import { ModalDismissedError, ModalService } from './core/services/modal.service';
 
@@ -21,7 +22,7 @@ interface TutorialStep {
 @Component({
   selector: 'app-root',
   standalone: true,
-  imports: [CommonModule, RouterOutlet, RouterLink, RouterLinkActive, ReactiveFormsModule, ModalHostComponent, UnlockWalletComponent, ThemeToggleComponent],
+  imports: [CommonModule, RouterOutlet, RouterLink, RouterLinkActive, ReactiveFormsModule, ModalHostComponent, UnlockWalletComponent, ThemeToggleComponent, ConnectorPromptComponent],
   templateUrl: './app.component.html',
   styleUrl: './app.component.scss'
 })
@@ -32,6 +33,8 @@ export class AppComponent implements OnInit, OnDestroy, AfterViewInit {
   walletInfo: WalletInfoResponse | null = null;
   loading = false;
   errorMessage: string | null = null;
+  // Stays up for the whole session while certificate checks are relaxed
+  tlsStatus: TlsStatus | null = null;
   
   // Fast loading state - shows spinner while initializing if wallet exists
   initializing = true;
@@ -170,6 +173,8 @@ export class AppComponent implements OnInit, OnDestroy, AfterViewInit {
   }
 
   async ngOnInit() {
+    void this.loadTlsStatus();
+
     try {
       // Initialize WASM module (optional - wallet can work without it for basic operations)
       console.log('Initializing WASM module...');
@@ -199,6 +204,14 @@ export class AppComponent implements OnInit, OnDestroy, AfterViewInit {
     }
   }
 
+  private async loadTlsStatus(): Promise<void> {
+    try {
+      this.tlsStatus = await this.walletBackend.getTlsStatus();
+    } catch (error) {
+      console.error('Failed to read TLS status:', error);
+    }
+  }
+
   ngAfterViewInit() {
     if (typeof window === 'undefined' || typeof document === 'undefined') {
       return;
@@ -0,0 +1,84 @@
+import { Injectable, computed, inject, signal } from '@angular/core';
+import { WALLET_BACKEND, WalletBackend } from './wallet-backend.interface';
+import { PendingApproval } from '../types/wallet.types';
+
+/** How often a shown prompt is checked for requests withdrawn by their site. */
+const PENDING_REFRESH_MS = 5_000;
+
+/**
+ * Queue of dApp requests waiting for the user, fed by the connector's
+ * `connector://request` events and answered through `resolve_connector_request`.
+ */
+@Injectable({ providedIn: 'root' })
+export class ConnectorApprovalService {
+  private readonly walletBackend = inject<WalletBackend>(WALLET_BACKEND);
+  private readonly pendingSignal = signal<PendingApproval[]>([]);
+  private readonly resolvingSignal = signal(false);
+  private readonly errorSignal = signal<string | null>(null);
+  private unlisten: (() => void) | null = null;
+  private refreshTimer: number | null = null;
+
+  readonly pending = computed(() => this.pendingSignal());
+  /** The request shown in the prompt: the oldest one still waiting. */
+  readonly current = computed(() => this.pendingSignal()[0] ?? null);
+  readonly resolving = computed(() => this.resolvingSignal());
+  readonly error = computed(() => this.errorSignal());
+
+  async start(): Promise<void> {
+    if (this.unlisten) {
+      return;
+    }
+    this.unlisten = await this.walletBackend.onConnectorRequest(() => void this.refresh());
+    this.refreshTimer = window.setInterval(() => {
+      if (this.pendingSignal().length > 0) {
+        void this.refresh();
+      }
+    }, PENDING_REFRESH_MS);
+    await this.refresh();
+  }
+
+  stop(): void {
+    this.unlisten?.();
+    this.unlisten = null;
+    if (this.refreshTimer !== null) {
+      window.clearInterval(this.refreshTimer);
+      this.refreshTimer = null;
+    }
+  }
+
+  approve(id: string): Promise<void> {
+    return this.resolve(id, true);
+  }
+
+  reject(id: string): Promise<void> {
+    return this.resolve(id, false);
+  }
+
+  /**
+   * Reload the queue from the backend, which also drops requests the site
+   * withdrew or that timed out.
+   */
+  async refresh(): Promise<void> {
+    try {
+      const status = await this.walletBackend.getConnectorStatus();
+      this.pendingSignal.set(status.pending);
+    } catch (error) {
+      console.error('Failed to load connector requests:', error);
+    }
+  }
+
+  private async resolve(id: string, approve: boolean): Promise<void> {
+    this.resolvingSignal.set(true);
+    this.errorSignal.set(null);
+    try {
+      const status = await this.walletBackend.resolveConnectorRequest(id, approve);
+      this.pendingSignal.set(status.pending);
+    } catch (error) {
+      // Usually the request was withdrawn meanwhile.
+      this.errorSignal.set(error instanceof Error ? error.message : `${error}`);
+      await this.refresh();
+    } finally {
+      this.resolvingSignal.set(false);
+    }
+  }
+}
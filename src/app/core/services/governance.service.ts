@@ -100,6 +100,7 @@ export class GovernanceService {
       case 'defeated': return 'Defeated';
       case 'queued': return 'Queued';
       case 'executed': return 'Executed';
+      case 'canceled':
       case 'cancelled': return 'Cancelled';
       case 'vetoed': return 'Vetoed';
       case 'expired': return 'Expired';
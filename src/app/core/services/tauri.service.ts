@@ -5,6 +5,7 @@
 
 import { Injectable } from '@angular/core';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { WalletBackend } from './wallet-backend.interface';
 import {
   BalanceResponse,
@@ -12,6 +13,7 @@ import {
   CastVoteResponse,
   ChangePasswordRequest,
   ChangePasswordResponse,
+  ConnectorStatus,
   CreateWalletRequest,
   CreateWalletResponse,
   DelegateRequest,
@@ -28,8 +30,10 @@ import {
   ImportWalletRequest,
   ImportWalletResponse,
   LockWalletResponse,
+  PendingApproval,
   SignMessageRequest,
   SignMessageResponse,
+  TlsStatus,
   TransactionHistoryResponse,
   UnlockWalletRequest,
   UnlockWalletResponse,
@@ -109,6 +113,23 @@ export class TauriService implements WalletBackend {
     });
   }
 
+  async getTlsStatus(): Promise<TlsStatus> {
+    return invoke<TlsStatus>('get_tls_status');
+  }
+
+  // dApp connector approvals
+  async getConnectorStatus(): Promise<ConnectorStatus> {
+    return invoke<ConnectorStatus>('get_connector_status');
+  }
+
+  async resolveConnectorRequest(id: string, approve: boolean): Promise<ConnectorStatus> {
+    return invoke<ConnectorStatus>('resolve_connector_request', { id, approve });
+  }
+
+  async onConnectorRequest(handler: (approval: PendingApproval) => void): Promise<() => void> {
+    return listen<PendingApproval>('connector://request', (event) => handler(event.payload));
+  }
+
   async sendTransaction(request: {
     from_address: string;
     to_address: string;
@@ -5,6 +5,7 @@ import {
   CastVoteResponse,
   ChangePasswordRequest,
   ChangePasswordResponse,
+  ConnectorStatus,
   CreateWalletRequest,
   CreateWalletResponse,
   DelegateRequest,
@@ -21,8 +22,10 @@ import {
   ImportWalletRequest,
   ImportWalletResponse,
   LockWalletResponse,
+  PendingApproval,
   SignMessageRequest,
   SignMessageResponse,
+  TlsStatus,
   TransactionHistoryResponse,
   UnlockWalletRequest,
   UnlockWalletResponse,
@@ -47,6 +50,13 @@ export interface WalletBackend {
   formatAmount(request: FormatAmountRequest): Promise<FormatAmountResponse>;
   getBalance(address: string, nodeUrl?: string): Promise<BalanceResponse>;
   getTransactionHistory(address: string, limit?: number, offset?: number, nodeUrl?: string): Promise<TransactionHistoryResponse>;
+  getTlsStatus(): Promise<TlsStatus>;
+
+  // dApp connector approvals
+  getConnectorStatus(): Promise<ConnectorStatus>;
+  resolveConnectorRequest(id: string, approve: boolean): Promise<ConnectorStatus>;
+  /** Calls `handler` for each new request; resolves to an unsubscribe function. */
+  onConnectorRequest(handler: (approval: PendingApproval) => void): Promise<() => void>;
 
   // Staking methods
   getValidators(): Promise<{ validators: any[] }>;
@@ -7,6 +7,7 @@ import {
   CastVoteResponse,
   ChangePasswordRequest,
   ChangePasswordResponse,
+  ConnectorStatus,
   CreateWalletRequest,
   CreateWalletResponse,
   DelegateRequest,
@@ -23,8 +24,10 @@ import {
   ImportWalletRequest,
   ImportWalletResponse,
   LockWalletResponse,
+  PendingApproval,
   SignMessageRequest,
   SignMessageResponse,
+  TlsStatus,
   TransactionHistoryResponse,
   UnlockWalletRequest,
   UnlockWalletResponse,
@@ -360,6 +363,24 @@ export class WebWalletBackendService implements WalletBackend {
     }
   }
 
+  // The browser verifies certificates itself; there is nothing to relax.
+  async getTlsStatus(): Promise<TlsStatus> {
+    return { untrustedCerts: false, customizedHosts: [], warning: null };
+  }
+
+  // The dApp connector only runs in the desktop app, so nothing ever waits here.
+  async getConnectorStatus(): Promise<ConnectorStatus> {
+    return { listening: null, lastError: null, pending: [] };
+  }
+
+  async resolveConnectorRequest(_id: string, _approve: boolean): Promise<ConnectorStatus> {
+    throw new Error('The dApp connector is only available in the desktop app');
+  }
+
+  async onConnectorRequest(_handler: (approval: PendingApproval) => void): Promise<() => void> {
+    return () => {};
+  }
+
   // Staking methods - web implementation with proper error handling
   async getValidators(): Promise<{ validators: any[] }> {
     const rpcUrl = `${this.defaultNodeUrl}/jsonrpc`;
@@ -145,10 +145,58 @@ export interface FormatAmountResponse {
   formatted: string;
 }
 
+export interface BalanceVerification {
+  verified: boolean;
+  height?: number | null;
+  reason?: string | null;
+}
+
 export interface BalanceResponse {
   address: string;
   balance: string;
   nonce: number;
+  /** Present only when light-client verification is enabled. */
+  verification?: BalanceVerification;
+}
+
+export interface TlsStatus {
+  /** Certificate verification is disabled for every endpoint. */
+  untrustedCerts: boolean;
+  customizedHosts: string[];
+  warning?: string | null;
+}
+
+/** What a dApp asks the wallet to do through the local connector. */
+export type ConnectorRequest =
+  | { method: 'requestAccounts' }
+  | {
+      method: 'signTypedData';
+      account: string;
+      domain: unknown;
+      primaryType: string;
+      message: unknown;
+    }
+  | {
+      method: 'sendTransaction';
+      from: string;
+      to: string;
+      /** Base units. */
+      amount: number;
+      fee: number;
+      data?: string | null;
+    };
+
+export interface PendingApproval {
+  id: string;
+  origin: string;
+  request: ConnectorRequest;
+  receivedAt: string;
+}
+
+export interface ConnectorStatus {
+  listening?: string | null;
+  lastError?: string | null;
+  pending: PendingApproval[];
 }
 
 export interface TransactionInfo {
@@ -186,6 +234,7 @@ export interface ProposalDetail {
   proposer: string;
   targets: string[];
   values: string[];
+  signatures?: string[];
   calldatas: string[];
   description: string;
   vote_start: number;
@@ -0,0 +1,52 @@
+import { CommonModule } from '@angular/common';
+import { Component, OnDestroy, OnInit, inject } from '@angular/core';
+import { ConnectorApprovalService } from '../../services/connector-approval.service';
+import { ConnectorRequest } from '../../types/wallet.types';
+
+@Component({
+  selector: 'app-connector-prompt',
+  standalone: true,
+  imports: [CommonModule],
+  templateUrl: './connector-prompt.component.html',
+  // Shares the modal window's look
+  styleUrls: ['../modal-host/modal-host.component.scss', './connector-prompt.component.scss']
+})
+export class ConnectorPromptComponent implements OnInit, OnDestroy {
+  private readonly approvals = inject(ConnectorApprovalService);
+
+  readonly current = this.approvals.current;
+  readonly pending = this.approvals.pending;
+  readonly resolving = this.approvals.resolving;
+  readonly error = this.approvals.error;
+
+  ngOnInit(): void {
+    void this.approvals.start();
+  }
+
+  ngOnDestroy(): void {
+    this.approvals.stop();
+  }
+
+  title(request: ConnectorRequest): string {
+    switch (request.method) {
+      case 'requestAccounts':
+        return 'Connect to site';
+      case 'signTypedData':
+        return 'Sign typed data';
+      case 'sendTransaction':
+        return 'Send transaction';
+    }
+  }
+
+  pretty(value: unknown): string {
+    return JSON.stringify(value, null, 2);
+  }
+
+  approve(id: string): void {
+    void this.approvals.approve(id);
+  }
+
+  reject(id: string): void {
+    void this.approvals.reject(id);
+  }
+}
@@ -144,7 +144,7 @@ export class ProposalDetailComponent implements OnInit {
     const now = Math.floor(Date.now() / 1000);
     return now >= this.proposal.vote_start &&
            now <= this.proposal.vote_end &&
-           this.proposal.state === 'Active' &&
+           this.proposal.state.toLowerCase() === 'active' &&
            !this.proposal.has_voted;
   }
 
@@ -169,6 +169,7 @@ export class ProposalDetailComponent implements OnInit {
       case 'defeated': return 'badge-defeated';
       case 'queued': return 'badge-queued';
       case 'executed': return 'badge-executed';
+      case 'canceled':
       case 'cancelled': return 'badge-cancelled';
       case 'vetoed': return 'badge-vetoed';
       case 'expired': return 'badge-expired';
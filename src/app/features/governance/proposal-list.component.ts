@@ -105,6 +105,7 @@ export class ProposalListComponent implements OnInit {
       case 'defeated': return 'badge-defeated';
       case 'queued': return 'badge-queued';
       case 'executed': return 'badge-executed';
+      case 'canceled':
       case 'cancelled': return 'badge-cancelled';
       case 'vetoed': return 'badge-vetoed';
       case 'expired': return 'badge-expired';